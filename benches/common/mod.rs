@@ -0,0 +1,71 @@
+#![allow(dead_code)]
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use ray_tracer::{vector::{Vector3, Float, consts::PI}, world::{World, Object, Sphere, Material}, scene_file};
+
+pub const WIDTH: u32 = 480;
+pub const HEIGHT: u32 = 270;
+
+pub fn at_resolution(mut world: World, width: u32, height: u32) -> World {
+  let camera_info = world.camera_mut().camera_info_mut();
+  camera_info.viewport_width = width;
+  camera_info.viewport_height = height;
+
+  world
+}
+
+pub fn demo() -> World {
+  let world = scene_file::from_str(include_str!("../../demo.json")).unwrap();
+  at_resolution(world, WIDTH, HEIGHT)
+}
+
+pub fn lots_of_balls() -> World {
+  let world = scene_file::from_str(include_str!("../../lots of balls.json")).unwrap();
+  at_resolution(world, WIDTH, HEIGHT)
+}
+
+pub fn random_spheres(count: usize) -> World {
+  let mut rng = StdRng::seed_from_u64(count as u64);
+  let mut world = World::new();
+
+  world.objects_mut().retain(|object| matches!(object, Object::Plane(_)));
+
+  for _ in 0..count {
+    let mut material = Material::new();
+    material.color = [rng.random(), rng.random(), rng.random()];
+    material.reflectivity = if rng.random_bool(0.3) { rng.random_range(0.2..0.9) } else { 0.0 };
+    material.transparency = if rng.random_bool(0.1) { rng.random_range(0.3..0.9) } else { 0.0 };
+    material.refractive_index = rng.random_range(1.0..1.6);
+
+    let mut sphere = Sphere::new();
+    sphere.position = Vector3::new(rng.random_range(-30.0..30.0), rng.random_range(-1.0..15.0), rng.random_range(5.0..60.0));
+    sphere.radius = rng.random_range(0.2..2.0);
+    sphere.material = material;
+
+    world.objects_mut().push(Object::Sphere(sphere));
+  }
+
+  at_resolution(world, WIDTH, HEIGHT)
+}
+
+pub fn mirror_room(depth: u32) -> World {
+  let mut world = World::new();
+
+  world.objects_mut().clear();
+
+  for i in 0..8 {
+    let angle = i as Float * PI / 4.0;
+
+    let mut sphere = Sphere::new();
+    sphere.position = Vector3::new(angle.cos() * 6.0, 0.0, angle.sin() * 6.0 + 10.0);
+    sphere.radius = 2.5;
+    sphere.material.reflectivity = 0.95;
+
+    world.objects_mut().push(Object::Sphere(sphere));
+  }
+
+  *world.camera_mut().depth_mut() = depth;
+  world.camera_mut().camera_info_mut().position = Vector3::new(0.0, 0.0, 10.0);
+
+  at_resolution(world, WIDTH, HEIGHT)
+}
\ No newline at end of file
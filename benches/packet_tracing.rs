@@ -0,0 +1,62 @@
+mod common;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use ray_tracer::{renderer::Renderer, world::World, packet::{RayPacket, LANES}};
+
+fn render(world: &World, packet_tracing: bool) -> Vec<[u8; 3]> {
+  let mut world = world.clone();
+  let mut renderer = Renderer::new();
+  *renderer.packet_tracing_mut() = packet_tracing;
+
+  renderer.render(&mut world).image_buffer.clone()
+}
+
+fn packet_tracing(c: &mut Criterion) {
+  let world = common::lots_of_balls();
+
+  assert!(render(&world, true) == render(&world, false), "packet and scalar renders differ");
+
+  let camera_info = world.camera().camera_info();
+  let pixels = camera_info.viewport_width as u64 * camera_info.viewport_height as u64;
+
+  let mut group = c.benchmark_group("lots of balls");
+  group.throughput(Throughput::Elements(pixels));
+
+  for (name, packet_tracing) in [("scalar", false), ("packet", true)] {
+    let mut world = world.clone();
+    let mut renderer = Renderer::new();
+    *renderer.packet_tracing_mut() = packet_tracing;
+
+    group.bench_function(name, |b| b.iter(|| {
+      renderer.render(&mut world);
+    }));
+  }
+
+  group.finish();
+}
+
+fn primary_rays(c: &mut Criterion) {
+  let mut world = common::lots_of_balls();
+  world.camera_mut().calc_rays();
+
+  let rays = world.camera().rays().clone();
+
+  let mut group = c.benchmark_group("lots of balls primary rays");
+  group.throughput(Throughput::Elements(rays.len() as u64));
+
+  group.bench_function("scalar", |b| b.iter(|| {
+    rays.iter().filter(|ray| Renderer::trace_ray(ray, &world).is_some()).count()
+  }));
+
+  group.bench_function("packet", |b| b.iter(|| {
+    rays.chunks_exact(LANES).map(|rays| {
+      let hits = Renderer::trace_packet(&RayPacket::new(rays), rays, &world);
+      hits.iter().filter(|hit| hit.is_some()).count()
+    }).sum::<usize>()
+  }));
+
+  group.finish();
+}
+
+criterion_group!(benches, packet_tracing, primary_rays);
+criterion_main!(benches);
\ No newline at end of file
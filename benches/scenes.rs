@@ -0,0 +1,85 @@
+mod common;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput, BenchmarkId};
+use ray_tracer::{renderer::Renderer, world::{World, Object, CameraType}};
+
+fn primary_rays(world: &World) -> u64 {
+  let camera_info = world.camera().camera_info();
+  camera_info.viewport_width as u64 * camera_info.viewport_height as u64
+}
+
+fn render_scenes(c: &mut Criterion) {
+  let scenes = vec![
+    ("demo", common::demo()),
+    ("lots of balls", common::lots_of_balls()),
+    ("100 random spheres", common::random_spheres(100)),
+    ("1000 random spheres", common::random_spheres(1000)),
+    ("mirror room depth 20", common::mirror_room(20)),
+  ];
+
+  let mut group = c.benchmark_group("render");
+  group.sample_size(10);
+
+  for (name, world) in scenes {
+    let mut world = world;
+    let mut renderer = Renderer::new();
+
+    group.throughput(Throughput::Elements(primary_rays(&world)));
+    group.bench_function(name, |b| b.iter(|| {
+      renderer.render(&mut world);
+    }));
+  }
+
+  group.finish();
+}
+
+fn calc_rays(c: &mut Criterion) {
+  let mut group = c.benchmark_group("calc rays");
+
+  for camera_type in [CameraType::Perspective, CameraType::Orthographic] {
+    let mut world = common::lots_of_balls();
+    world.camera_mut().camera_info_mut().camera_type = camera_type;
+
+    group.throughput(Throughput::Elements(primary_rays(&world)));
+    group.bench_function(BenchmarkId::from_parameter(camera_type.to_string()), |b| b.iter(|| {
+      world.camera_mut().calc_rays();
+    }));
+  }
+
+  group.finish();
+}
+
+fn intersections(c: &mut Criterion) {
+  let mut world = common::lots_of_balls();
+  world.camera_mut().calc_rays();
+
+  let rays = world.camera().rays();
+  let sphere = world.objects().iter().find_map(|object| match object {
+    Object::Sphere(sphere) => Some(sphere),
+    _ => None,
+  }).unwrap();
+  let plane = world.objects().iter().find_map(|object| match object {
+    Object::Plane(plane) => Some(plane),
+    _ => None,
+  }).unwrap();
+
+  let mut group = c.benchmark_group("intersections");
+  group.throughput(Throughput::Elements(rays.len() as u64));
+
+  group.bench_function("sphere", |b| b.iter(|| {
+    rays.iter().filter(|ray| sphere.trace_ray(ray)[0].is_some()).count()
+  }));
+
+  group.bench_function("plane", |b| b.iter(|| {
+    rays.iter().filter(|ray| plane.trace_ray(ray).is_some()).count()
+  }));
+
+  group.bench_function("world", |b| b.iter(|| {
+    rays.iter().filter(|ray| Renderer::trace_ray(ray, &world).is_some()).count()
+  }));
+
+  group.finish();
+}
+
+criterion_group!(benches, render_scenes, calc_rays, intersections);
+criterion_main!(benches);
\ No newline at end of file
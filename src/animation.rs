@@ -0,0 +1,299 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{world::{World, Light, RGB}, vector::{Vector3, Float}};
+
+#[derive(PartialEq, Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub enum Interpolation {
+  #[default]
+  Linear,
+  Bezier,
+}
+
+impl ToString for Interpolation {
+  fn to_string(&self) -> String {
+    match *self {
+      Interpolation::Linear => String::from("Linear"),
+      Interpolation::Bezier => String::from("Bezier"),
+    }
+  }
+}
+
+pub trait Animatable: Copy {
+  fn add(self, other: Self) -> Self;
+  fn scale(self, factor: Float) -> Self;
+}
+
+impl Animatable for Float {
+  fn add(self, other: Self) -> Self {
+    self + other
+  }
+
+  fn scale(self, factor: Float) -> Self {
+    self * factor
+  }
+}
+
+impl Animatable for Vector3 {
+  fn add(self, other: Self) -> Self {
+    self + other
+  }
+
+  fn scale(self, factor: Float) -> Self {
+    self * factor
+  }
+}
+
+impl Animatable for RGB {
+  fn add(self, other: Self) -> Self {
+    [self[0] + other[0], self[1] + other[1], self[2] + other[2]]
+  }
+
+  fn scale(self, factor: Float) -> Self {
+    let factor = factor as f32;
+
+    [self[0] * factor, self[1] * factor, self[2] * factor]
+  }
+}
+
+// The interpolation of a keyframe applies to the segment that starts at it.
+#[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Keyframe<T> {
+  pub frame: Float,
+  pub value: T,
+  #[serde(default)]
+  pub interpolation: Interpolation,
+}
+
+impl<T> Keyframe<T> {
+  pub fn new(frame: Float, value: T, interpolation: Interpolation) -> Keyframe<T> {
+    Keyframe {
+      frame,
+      value,
+      interpolation,
+    }
+  }
+}
+
+pub fn insert_keyframe<T>(keyframes: &mut Vec<Keyframe<T>>, keyframe: Keyframe<T>) {
+  match keyframes.iter().position(|existing| existing.frame >= keyframe.frame) {
+    Some(index) if keyframes[index].frame == keyframe.frame => keyframes[index] = keyframe,
+    Some(index) => keyframes.insert(index, keyframe),
+    None => keyframes.push(keyframe),
+  }
+}
+
+pub fn sample<T: Animatable>(keyframes: &[Keyframe<T>], frame: Float) -> Option<T> {
+  let first = keyframes.first()?;
+  let last = keyframes.last()?;
+
+  if frame <= first.frame {
+    return Some(first.value);
+  }
+
+  if frame >= last.frame {
+    return Some(last.value);
+  }
+
+  let index = keyframes.iter().rposition(|keyframe| keyframe.frame <= frame)?;
+  let (from, to) = (&keyframes[index], &keyframes[index + 1]);
+
+  let duration = to.frame - from.frame;
+  let t = if duration > 0.0 { (frame - from.frame) / duration } else { 1.0 };
+
+  match from.interpolation {
+    Interpolation::Linear => Some(from.value.scale(1.0 - t).add(to.value.scale(t))),
+    Interpolation::Bezier => {
+      // Handles follow the Catmull-Rom tangent through the neighbouring keyframes.
+      let before = if index > 0 { &keyframes[index - 1] } else { from };
+      let after = keyframes.get(index + 2).unwrap_or(to);
+
+      let tangent = |previous: &Keyframe<T>, next: &Keyframe<T>| {
+        let span = next.frame - previous.frame;
+
+        if span > 0.0 {
+          next.value.add(previous.value.scale(-1.0)).scale(duration / span / 3.0)
+        } else {
+          from.value.scale(0.0)
+        }
+      };
+
+      let p1 = from.value.add(tangent(before, to));
+      let p2 = to.value.add(tangent(from, after).scale(-1.0));
+
+      let s = 1.0 - t;
+
+      Some(
+        from.value.scale(s * s * s)
+          .add(p1.scale(3.0 * s * s * t))
+          .add(p2.scale(3.0 * s * t * t))
+          .add(to.value.scale(t * t * t))
+      )
+    },
+  }
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub enum Track {
+  CameraPosition(Vec<Keyframe<Vector3>>),
+  CameraForward(Vec<Keyframe<Vector3>>),
+  CameraFov(Vec<Keyframe<Float>>),
+  ObjectPosition {
+    object: usize,
+    keyframes: Vec<Keyframe<Vector3>>,
+  },
+  ObjectColor {
+    object: usize,
+    keyframes: Vec<Keyframe<RGB>>,
+  },
+  LightColor {
+    light: usize,
+    keyframes: Vec<Keyframe<RGB>>,
+  },
+  LightPosition {
+    light: usize,
+    keyframes: Vec<Keyframe<Vector3>>,
+  },
+  LightDirection {
+    light: usize,
+    keyframes: Vec<Keyframe<Vector3>>,
+  },
+}
+
+impl ToString for Track {
+  fn to_string(&self) -> String {
+    match self {
+      Track::CameraPosition(_) => String::from("Camera Position"),
+      Track::CameraForward(_) => String::from("Camera Forward"),
+      Track::CameraFov(_) => String::from("Camera FOV"),
+      Track::ObjectPosition { object, .. } => format!("Object {} Position", object),
+      Track::ObjectColor { object, .. } => format!("Object {} Color", object),
+      Track::LightColor { light, .. } => format!("Light {} Color", light),
+      Track::LightPosition { light, .. } => format!("Light {} Position", light),
+      Track::LightDirection { light, .. } => format!("Light {} Direction", light),
+    }
+  }
+}
+
+impl Track {
+  // The object or light a track animates, the camera tracks have neither.
+  pub fn object(&self) -> Option<usize> {
+    match self {
+      Track::ObjectPosition { object, .. } | Track::ObjectColor { object, .. } => Some(*object),
+      _ => None,
+    }
+  }
+
+  pub fn light(&self) -> Option<usize> {
+    match self {
+      Track::LightColor { light, .. } | Track::LightPosition { light, .. } | Track::LightDirection { light, .. } => Some(*light),
+      _ => None,
+    }
+  }
+
+  pub fn frames(&self) -> Vec<Float> {
+    fn frames<T>(keyframes: &[Keyframe<T>]) -> Vec<Float> {
+      keyframes.iter().map(|keyframe| keyframe.frame).collect()
+    }
+
+    match self {
+      Track::CameraPosition(keyframes) |
+      Track::CameraForward(keyframes) |
+      Track::ObjectPosition { keyframes, .. } |
+      Track::LightPosition { keyframes, .. } |
+      Track::LightDirection { keyframes, .. } => frames(keyframes),
+      Track::CameraFov(keyframes) => frames(keyframes),
+      Track::ObjectColor { keyframes, .. } |
+      Track::LightColor { keyframes, .. } => frames(keyframes),
+    }
+  }
+
+  // Tracks pointing at missing objects or lights of the wrong kind are skipped.
+  pub fn apply(&self, world: &mut World, frame: Float) {
+    match self {
+      Track::CameraPosition(keyframes) => if let Some(position) = sample(keyframes, frame) {
+        world.camera_mut().camera_info_mut().position = position;
+      },
+      Track::CameraForward(keyframes) => if let Some(forward) = sample(keyframes, frame) {
+        world.camera_mut().camera_info_mut().forward = forward;
+      },
+      Track::CameraFov(keyframes) => if let Some(fov) = sample(keyframes, frame) {
+        world.camera_mut().camera_info_mut().vertical_fov = fov;
+      },
+      Track::ObjectPosition { object, keyframes } => {
+        if let (Some(position), Some(object)) = (sample(keyframes, frame), world.objects_mut().get_mut(*object)) {
+          *object.position_mut() = position;
+        }
+      },
+      Track::ObjectColor { object, keyframes } => {
+        if let (Some(color), Some(object)) = (sample(keyframes, frame), world.objects_mut().get_mut(*object)) {
+          object.material_mut().color = color;
+        }
+      },
+      Track::LightColor { light, keyframes } => {
+        if let (Some(color), Some(light)) = (sample(keyframes, frame), world.lights_mut().get_mut(*light)) {
+          match light {
+            Light::Ambient(light) => light.color = color,
+            Light::Directional(light) => light.color = color,
+            Light::Point(light) => light.color = color,
+          }
+        }
+      },
+      Track::LightPosition { light, keyframes } => {
+        if let (Some(position), Some(Light::Point(light))) = (sample(keyframes, frame), world.lights_mut().get_mut(*light)) {
+          light.position = position;
+        }
+      },
+      Track::LightDirection { light, keyframes } => {
+        if let (Some(direction), Some(Light::Directional(light))) = (sample(keyframes, frame), world.lights_mut().get_mut(*light)) {
+          light.direction = direction;
+        }
+      },
+    }
+  }
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Animation {
+  pub start: u32,
+  pub end: u32,
+  pub frame_rate: Float,
+  pub tracks: Vec<Track>,
+}
+
+impl Default for Animation {
+  fn default() -> Self {
+    Animation::new()
+  }
+}
+
+impl Animation {
+  pub fn new() -> Animation {
+    Animation {
+      start: 0,
+      end: 100,
+      frame_rate: 24.0,
+      tracks: Vec::new(),
+    }
+  }
+
+  pub fn apply(&self, world: &mut World, frame: Float) {
+    for track in &self.tracks {
+      track.apply(world, frame);
+    }
+  }
+
+  // Points tracks at the new indices after objects or lights are removed or reordered.
+  pub fn reindex(&mut self, object: impl Fn(usize) -> usize, light: impl Fn(usize) -> usize) {
+    for track in &mut self.tracks {
+      match track {
+        Track::ObjectPosition { object: index, .. } |
+        Track::ObjectColor { object: index, .. } => *index = object(*index),
+        Track::LightColor { light: index, .. } |
+        Track::LightPosition { light: index, .. } |
+        Track::LightDirection { light: index, .. } => *index = light(*index),
+        Track::CameraPosition(_) | Track::CameraForward(_) | Track::CameraFov(_) => (),
+      }
+    }
+  }
+}
\ No newline at end of file
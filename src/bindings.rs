@@ -0,0 +1,362 @@
+use std::{fmt, fs, path::Path};
+
+use glium::glutin::event::{VirtualKeyCode, MouseButton};
+use serde::{Serialize, Deserialize};
+
+use crate::{event_manager::EventManager, scene_file::{self, SceneError, SceneFormat}, vector::Float};
+
+// Looked for in the working directory when the app starts.
+pub const BINDINGS_FILE: &str = "bindings.toml";
+
+const KEYS: &[(&str, VirtualKeyCode)] = &[
+  ("A", VirtualKeyCode::A), ("B", VirtualKeyCode::B), ("C", VirtualKeyCode::C), ("D", VirtualKeyCode::D),
+  ("E", VirtualKeyCode::E), ("F", VirtualKeyCode::F), ("G", VirtualKeyCode::G), ("H", VirtualKeyCode::H),
+  ("I", VirtualKeyCode::I), ("J", VirtualKeyCode::J), ("K", VirtualKeyCode::K), ("L", VirtualKeyCode::L),
+  ("M", VirtualKeyCode::M), ("N", VirtualKeyCode::N), ("O", VirtualKeyCode::O), ("P", VirtualKeyCode::P),
+  ("Q", VirtualKeyCode::Q), ("R", VirtualKeyCode::R), ("S", VirtualKeyCode::S), ("T", VirtualKeyCode::T),
+  ("U", VirtualKeyCode::U), ("V", VirtualKeyCode::V), ("W", VirtualKeyCode::W), ("X", VirtualKeyCode::X),
+  ("Y", VirtualKeyCode::Y), ("Z", VirtualKeyCode::Z),
+  ("0", VirtualKeyCode::Key0), ("1", VirtualKeyCode::Key1), ("2", VirtualKeyCode::Key2), ("3", VirtualKeyCode::Key3),
+  ("4", VirtualKeyCode::Key4), ("5", VirtualKeyCode::Key5), ("6", VirtualKeyCode::Key6), ("7", VirtualKeyCode::Key7),
+  ("8", VirtualKeyCode::Key8), ("9", VirtualKeyCode::Key9),
+  ("Up", VirtualKeyCode::Up), ("Down", VirtualKeyCode::Down), ("Left", VirtualKeyCode::Left), ("Right", VirtualKeyCode::Right),
+  ("Space", VirtualKeyCode::Space), ("Tab", VirtualKeyCode::Tab), ("Enter", VirtualKeyCode::Return),
+  ("Backspace", VirtualKeyCode::Back), ("Escape", VirtualKeyCode::Escape),
+  ("PageUp", VirtualKeyCode::PageUp), ("PageDown", VirtualKeyCode::PageDown),
+  ("Home", VirtualKeyCode::Home), ("End", VirtualKeyCode::End),
+  ("Insert", VirtualKeyCode::Insert), ("Delete", VirtualKeyCode::Delete),
+  ("F1", VirtualKeyCode::F1), ("F2", VirtualKeyCode::F2), ("F3", VirtualKeyCode::F3), ("F4", VirtualKeyCode::F4),
+  ("F5", VirtualKeyCode::F5), ("F6", VirtualKeyCode::F6), ("F7", VirtualKeyCode::F7), ("F8", VirtualKeyCode::F8),
+  ("F9", VirtualKeyCode::F9), ("F10", VirtualKeyCode::F10), ("F11", VirtualKeyCode::F11), ("F12", VirtualKeyCode::F12),
+];
+
+const MOUSE_BUTTONS: [(&str, MouseButton); 3] = [
+  ("LeftMouse", MouseButton::Left),
+  ("RightMouse", MouseButton::Right),
+  ("MiddleMouse", MouseButton::Middle),
+];
+
+// A key, a mouse button or a modifier, written by name in the bindings file.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Binding {
+  Key(VirtualKeyCode),
+  Mouse(MouseButton),
+  Shift,
+  Ctrl,
+  Alt,
+}
+
+impl ToString for Binding {
+  fn to_string(&self) -> String {
+    let name = match self {
+      Binding::Key(key) => KEYS.iter().find(|(_, k)| k == key).map(|(name, _)| *name),
+      Binding::Mouse(button) => MOUSE_BUTTONS.iter().find(|(_, b)| b == button).map(|(name, _)| *name),
+      Binding::Shift => Some("Shift"),
+      Binding::Ctrl => Some("Ctrl"),
+      Binding::Alt => Some("Alt"),
+    };
+
+    String::from(name.unwrap_or("Unknown"))
+  }
+}
+
+impl From<Binding> for String {
+  fn from(binding: Binding) -> Self {
+    binding.to_string()
+  }
+}
+
+impl TryFrom<String> for Binding {
+  type Error = UnknownBinding;
+
+  fn try_from(name: String) -> Result<Self, Self::Error> {
+    Binding::parse(&name).ok_or(UnknownBinding(name))
+  }
+}
+
+impl Binding {
+  // Names are matched without caring about case.
+  pub fn parse(name: &str) -> Option<Binding> {
+    let name = name.trim();
+
+    let modifier = [("Shift", Binding::Shift), ("Ctrl", Binding::Ctrl), ("Alt", Binding::Alt)].into_iter()
+      .find(|(modifier, _)| modifier.eq_ignore_ascii_case(name))
+      .map(|(_, binding)| binding);
+
+    let key = || KEYS.iter()
+      .find(|(key, _)| key.eq_ignore_ascii_case(name))
+      .map(|(_, key)| Binding::Key(*key));
+
+    let button = || MOUSE_BUTTONS.iter()
+      .find(|(button, _)| button.eq_ignore_ascii_case(name))
+      .map(|(_, button)| Binding::Mouse(*button));
+
+    modifier.or_else(key).or_else(button)
+  }
+
+  // Parses a comma separated list, like "W, Up".
+  pub fn parse_list(text: &str) -> Result<Vec<Binding>, UnknownBinding> {
+    text.split(',')
+      .map(str::trim)
+      .filter(|name| !name.is_empty())
+      .map(|name| Binding::parse(name).ok_or_else(|| UnknownBinding(String::from(name))))
+      .collect()
+  }
+
+  pub fn list_to_string(bindings: &[Binding]) -> String {
+    bindings.iter().map(|binding| binding.to_string()).collect::<Vec<_>>().join(", ")
+  }
+
+  pub fn is_down(&self, event_manager: &EventManager) -> bool {
+    let modifiers = event_manager.modifiers();
+
+    match *self {
+      Binding::Key(key) => event_manager.is_key_down(key),
+      Binding::Mouse(button) => event_manager.is_mouse_down(button),
+      Binding::Shift => modifiers.shift(),
+      Binding::Ctrl => modifiers.ctrl(),
+      Binding::Alt => modifiers.alt(),
+    }
+  }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct UnknownBinding(pub String);
+
+impl fmt::Display for UnknownBinding {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "unknown key or button \"{}\"", self.0)
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Profile {
+  Fly,
+  Orbit,
+}
+
+impl ToString for Profile {
+  fn to_string(&self) -> String {
+    match self {
+      Profile::Fly => String::from("Fly"),
+      Profile::Orbit => String::from("Orbit"),
+    }
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+  Forward,
+  Back,
+  Left,
+  Right,
+  Up,
+  Down,
+  Rotate,
+  Pan,
+  Fast,
+  Slow,
+  FrameSelected,
+  FrameAll,
+}
+
+impl ToString for Action {
+  fn to_string(&self) -> String {
+    match self {
+      Action::Forward => String::from("Forward"),
+      Action::Back => String::from("Back"),
+      Action::Left => String::from("Left"),
+      Action::Right => String::from("Right"),
+      Action::Up => String::from("Up"),
+      Action::Down => String::from("Down"),
+      Action::Rotate => String::from("Rotate"),
+      Action::Pan => String::from("Pan"),
+      Action::Fast => String::from("Fast"),
+      Action::Slow => String::from("Slow"),
+      Action::FrameSelected => String::from("Frame Selected"),
+      Action::FrameAll => String::from("Frame All"),
+    }
+  }
+}
+
+impl Action {
+  pub const ALL: [Action; 12] = [
+    Action::Forward, Action::Back, Action::Left, Action::Right, Action::Up, Action::Down,
+    Action::Rotate, Action::Pan, Action::Fast, Action::Slow, Action::FrameSelected, Action::FrameAll,
+  ];
+}
+
+// What each action is bound to in one profile. Rotate looks around when flying
+// and turns the camera around the point it orbits otherwise.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Controls {
+  pub forward: Vec<Binding>,
+  pub back: Vec<Binding>,
+  pub left: Vec<Binding>,
+  pub right: Vec<Binding>,
+  pub up: Vec<Binding>,
+  pub down: Vec<Binding>,
+  pub rotate: Vec<Binding>,
+  pub pan: Vec<Binding>,
+  pub fast: Vec<Binding>,
+  pub slow: Vec<Binding>,
+  pub frame_selected: Vec<Binding>,
+  pub frame_all: Vec<Binding>,
+}
+
+impl Default for Controls {
+  fn default() -> Self {
+    Controls::fly()
+  }
+}
+
+impl Controls {
+  pub fn fly() -> Controls {
+    Controls {
+      forward: vec![Binding::Key(VirtualKeyCode::W)],
+      back: vec![Binding::Key(VirtualKeyCode::S)],
+      left: vec![Binding::Key(VirtualKeyCode::A)],
+      right: vec![Binding::Key(VirtualKeyCode::D)],
+      up: vec![Binding::Key(VirtualKeyCode::Q)],
+      down: vec![Binding::Key(VirtualKeyCode::E)],
+      rotate: vec![Binding::Mouse(MouseButton::Left)],
+      pan: vec![Binding::Mouse(MouseButton::Middle)],
+      fast: vec![Binding::Shift],
+      slow: vec![Binding::Ctrl],
+      frame_selected: vec![Binding::Key(VirtualKeyCode::F)],
+      frame_all: vec![Binding::Key(VirtualKeyCode::Home)],
+    }
+  }
+
+  pub fn orbit() -> Controls {
+    Controls {
+      forward: vec![Binding::Key(VirtualKeyCode::W), Binding::Key(VirtualKeyCode::Up)],
+      back: vec![Binding::Key(VirtualKeyCode::S), Binding::Key(VirtualKeyCode::Down)],
+      left: vec![Binding::Key(VirtualKeyCode::A), Binding::Key(VirtualKeyCode::Left)],
+      right: vec![Binding::Key(VirtualKeyCode::D), Binding::Key(VirtualKeyCode::Right)],
+      ..Controls::fly()
+    }
+  }
+
+  pub fn bindings(&self, action: Action) -> &Vec<Binding> {
+    match action {
+      Action::Forward => &self.forward,
+      Action::Back => &self.back,
+      Action::Left => &self.left,
+      Action::Right => &self.right,
+      Action::Up => &self.up,
+      Action::Down => &self.down,
+      Action::Rotate => &self.rotate,
+      Action::Pan => &self.pan,
+      Action::Fast => &self.fast,
+      Action::Slow => &self.slow,
+      Action::FrameSelected => &self.frame_selected,
+      Action::FrameAll => &self.frame_all,
+    }
+  }
+
+  pub fn bindings_mut(&mut self, action: Action) -> &mut Vec<Binding> {
+    match action {
+      Action::Forward => &mut self.forward,
+      Action::Back => &mut self.back,
+      Action::Left => &mut self.left,
+      Action::Right => &mut self.right,
+      Action::Up => &mut self.up,
+      Action::Down => &mut self.down,
+      Action::Rotate => &mut self.rotate,
+      Action::Pan => &mut self.pan,
+      Action::Fast => &mut self.fast,
+      Action::Slow => &mut self.slow,
+      Action::FrameSelected => &mut self.frame_selected,
+      Action::FrameAll => &mut self.frame_all,
+    }
+  }
+
+  pub fn is_active(&self, action: Action, event_manager: &EventManager) -> bool {
+    self.bindings(action).iter().any(|binding| binding.is_down(event_manager))
+  }
+
+  // For actions that happen once per key press rather than while held.
+  pub fn is_pressed(&self, action: Action, event_manager: &EventManager) -> bool {
+    event_manager.key_pressed().is_some_and(|key| self.bindings(action).contains(&Binding::Key(key)))
+  }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bindings {
+  pub profile: Profile,
+  // Flying speed is multiplied by this for every step the wheel turns.
+  pub wheel_speed: Float,
+  // Fraction of the distance to the orbit point covered by one wheel step.
+  pub wheel_zoom: Float,
+  // Movement is multiplied by these while Fast or Slow is held.
+  pub fast_factor: Float,
+  pub slow_factor: Float,
+  pub fly: Controls,
+  pub orbit: Controls,
+}
+
+impl Default for Bindings {
+  fn default() -> Self {
+    Bindings::new()
+  }
+}
+
+impl Bindings {
+  pub fn new() -> Bindings {
+    Bindings {
+      profile: Profile::Fly,
+      wheel_speed: 1.2,
+      wheel_zoom: 0.1,
+      fast_factor: 4.0,
+      slow_factor: 0.25,
+      fly: Controls::fly(),
+      orbit: Controls::orbit(),
+    }
+  }
+
+  pub fn load(path: &Path) -> Result<Bindings, SceneError> {
+    scene_file::parse(&fs::read_to_string(path)?, SceneFormat::Toml)
+  }
+
+  pub fn save(&self, path: &Path) -> Result<(), SceneError> {
+    let text = toml::to_string_pretty(self).map_err(|err| SceneError::Serialize(err.to_string()))?;
+    fs::write(path, text)?;
+
+    Ok(())
+  }
+
+  // The controls of the current profile.
+  pub fn controls(&self) -> &Controls {
+    match self.profile {
+      Profile::Fly => &self.fly,
+      Profile::Orbit => &self.orbit,
+    }
+  }
+
+  pub fn controls_mut(&mut self) -> &mut Controls {
+    match self.profile {
+      Profile::Fly => &mut self.fly,
+      Profile::Orbit => &mut self.orbit,
+    }
+  }
+
+  // Holding Fast and Slow together applies both.
+  pub fn speed_factor(&self, event_manager: &EventManager) -> Float {
+    let controls = self.controls();
+    let mut factor = 1.0;
+
+    if controls.is_active(Action::Fast, event_manager) {
+      factor *= self.fast_factor;
+    }
+
+    if controls.is_active(Action::Slow, event_manager) {
+      factor *= self.slow_factor;
+    }
+
+    factor
+  }
+}
\ No newline at end of file
@@ -0,0 +1,118 @@
+use std::{fs::File, io::BufWriter, path::Path};
+
+use serde::{Serialize, Deserialize};
+
+use crate::{world::CameraInfo, vector::{Vector3, Float}, animation::{self, Animatable, Track, Keyframe, Interpolation}, scene_file::SceneError};
+
+#[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct CameraSample {
+  pub time: Float,
+  pub camera_info: CameraInfo,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct CameraPath {
+  pub samples: Vec<CameraSample>,
+}
+
+impl Default for CameraPath {
+  fn default() -> Self {
+    CameraPath::new()
+  }
+}
+
+impl CameraPath {
+  pub fn new() -> CameraPath {
+    CameraPath {
+      samples: Vec::new(),
+    }
+  }
+
+  pub fn load(path: &Path) -> Result<CameraPath, SceneError> {
+    Ok(serde_json::from_reader(File::open(path)?)?)
+  }
+
+  pub fn save(&self, path: &Path) -> Result<(), SceneError> {
+    serde_json::to_writer_pretty(BufWriter::new(File::create(path)?), self)?;
+
+    Ok(())
+  }
+
+  pub fn record(&mut self, time: Float, camera_info: CameraInfo) {
+    self.samples.push(CameraSample { time, camera_info });
+  }
+
+  pub fn duration(&self) -> Float {
+    self.samples.last().map_or(0.0, |sample| sample.time)
+  }
+
+  // Settings that can't be blended, like the viewport, come from the earlier sample.
+  pub fn sample(&self, time: Float) -> Option<CameraInfo> {
+    let index = self.samples.iter().rposition(|sample| sample.time <= time).unwrap_or(0);
+    let from = self.samples.get(index)?;
+
+    let to = match self.samples.get(index + 1) {
+      Some(to) if time > from.time => to,
+      _ => return Some(from.camera_info),
+    };
+
+    let t = (time - from.time) / (to.time - from.time);
+    let lerp = |a: Vector3, b: Vector3| a * (1.0 - t) + b * t;
+
+    let mut camera_info = from.camera_info;
+    camera_info.position = lerp(from.camera_info.position, to.camera_info.position);
+    camera_info.forward = lerp(from.camera_info.forward, to.camera_info.forward).normalise();
+    camera_info.vertical_fov = from.camera_info.vertical_fov * (1.0 - t) + to.camera_info.vertical_fov * t;
+    camera_info.camera_height = from.camera_info.camera_height * (1.0 - t) + to.camera_info.camera_height * t;
+
+    Some(camera_info)
+  }
+
+  // Keys that linear interpolation between their neighbours reproduces within
+  // the tolerance are dropped, a recording has one sample per displayed frame.
+  pub fn to_tracks(&self, frame_rate: Float, tolerance: Float) -> Vec<Track> {
+    let keyframes = |value: fn(&CameraInfo) -> Vector3| -> Vec<Keyframe<Vector3>> {
+      self.samples.iter()
+        .map(|sample| Keyframe::new(sample.time * frame_rate, value(&sample.camera_info), Interpolation::Linear))
+        .collect()
+    };
+
+    let fov = self.samples.iter()
+      .map(|sample| Keyframe::new(sample.time * frame_rate, sample.camera_info.vertical_fov, Interpolation::Linear))
+      .collect();
+
+    let distance = |a: Vector3, b: Vector3| (a - b).mag();
+
+    vec![
+      Track::CameraPosition(simplify(keyframes(|camera_info| camera_info.position), distance, tolerance)),
+      Track::CameraForward(simplify(keyframes(|camera_info| camera_info.forward), distance, tolerance)),
+      Track::CameraFov(simplify(fov, |a: Float, b: Float| (a - b).abs(), tolerance)),
+    ]
+  }
+}
+
+fn simplify<T: Animatable>(keyframes: Vec<Keyframe<T>>, distance: impl Fn(T, T) -> Float, tolerance: Float) -> Vec<Keyframe<T>> {
+  if keyframes.len() < 3 {
+    return keyframes;
+  }
+
+  let mut kept = vec![keyframes[0]];
+  let mut anchor = 0;
+
+  for end in 2..keyframes.len() {
+    let segment = [keyframes[anchor], keyframes[end]];
+
+    let fits = keyframes[anchor + 1..end].iter().all(|keyframe| {
+      animation::sample(&segment, keyframe.frame).is_some_and(|value| distance(value, keyframe.value) <= tolerance)
+    });
+
+    if !fits {
+      anchor = end - 1;
+      kept.push(keyframes[anchor]);
+    }
+  }
+
+  kept.push(keyframes[keyframes.len() - 1]);
+
+  kept
+}
\ No newline at end of file
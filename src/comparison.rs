@@ -0,0 +1,228 @@
+use crate::image::Image;
+
+const DIVIDER_COLOR: [u8; 3] = [255, 255, 255];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CompareMode {
+  Split,
+  Difference,
+}
+
+impl ToString for CompareMode {
+  fn to_string(&self) -> String {
+    match self {
+      CompareMode::Split => String::from("Split"),
+      CompareMode::Difference => String::from("Difference"),
+    }
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CompareSettings {
+  pub mode: CompareMode,
+  // Fraction of the width, left of it shows the snapshot and right of it the live render.
+  pub split: f32,
+  // Differences are multiplied by this so small ones stay visible.
+  pub gain: f32,
+}
+
+impl Default for CompareSettings {
+  fn default() -> Self {
+    CompareSettings::new()
+  }
+}
+
+impl CompareSettings {
+  pub fn new() -> CompareSettings {
+    CompareSettings {
+      mode: CompareMode::Split,
+      split: 0.5,
+      gain: 4.0,
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+  pub name: String,
+  pub image: Image,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Metrics {
+  pub rmse: f64,
+  // In decibels, infinite for identical images.
+  pub psnr: f64,
+}
+
+impl Metrics {
+  // Both over all channels on the 0 to 255 scale, images of different sizes can't be compared.
+  pub fn between(a: &Image, b: &Image) -> Option<Metrics> {
+    if !same_size(a, b) || a.pixels.is_empty() {
+      return None;
+    }
+
+    let squared: f64 = a.pixels.iter().zip(&b.pixels)
+      .flat_map(|(a, b)| a.iter().zip(b).map(|(a, b)| (*a as f64 - *b as f64).powi(2)))
+      .sum();
+
+    let rmse = (squared / (a.pixels.len() * 3) as f64).sqrt();
+    let psnr = 20.0 * (255.0 / rmse).log10();
+
+    Some(Metrics { rmse, psnr })
+  }
+}
+
+// Stored renders and how the live render is compared against one of them.
+pub struct Comparison {
+  snapshots: Vec<Snapshot>,
+  active: Option<usize>,
+  settings: CompareSettings,
+  live: Option<Image>,
+  metrics: Option<Metrics>,
+}
+
+impl Default for Comparison {
+  fn default() -> Self {
+    Comparison::new()
+  }
+}
+
+impl Comparison {
+  pub fn new() -> Comparison {
+    Comparison {
+      snapshots: Vec::new(),
+      active: None,
+      settings: CompareSettings::new(),
+      live: None,
+      metrics: None,
+    }
+  }
+
+  pub fn snapshots(&self) -> &[Snapshot] {
+    &self.snapshots
+  }
+
+  // The last complete render.
+  pub fn live(&self) -> Option<&Image> {
+    self.live.as_ref()
+  }
+
+  pub fn set_live(&mut self, image: Image) {
+    self.live = Some(image);
+    self.update_metrics();
+  }
+
+  pub fn add_snapshot(&mut self, name: &str, image: Image) {
+    self.snapshots.push(Snapshot { name: String::from(name), image });
+  }
+
+  // Stores the last complete render, if there is one yet.
+  pub fn take_snapshot(&mut self, name: &str) -> bool {
+    match self.live.clone() {
+      Some(image) => {
+        self.add_snapshot(name, image);
+        true
+      },
+      None => false,
+    }
+  }
+
+  pub fn remove_snapshot(&mut self, index: usize) {
+    if index >= self.snapshots.len() {
+      return;
+    }
+
+    self.snapshots.remove(index);
+
+    let active = match self.active {
+      Some(active) if active == index => None,
+      Some(active) if active > index => Some(active - 1),
+      active => active,
+    };
+
+    self.select(active);
+  }
+
+  // The snapshot the live render is compared against, None shows the live render alone.
+  pub fn active(&self) -> Option<usize> {
+    self.active
+  }
+
+  pub fn select(&mut self, snapshot: Option<usize>) {
+    self.active = snapshot.filter(|index| *index < self.snapshots.len());
+    self.update_metrics();
+  }
+
+  pub fn settings(&self) -> &CompareSettings {
+    &self.settings
+  }
+
+  pub fn settings_mut(&mut self) -> &mut CompareSettings {
+    &mut self.settings
+  }
+
+  // Between the last complete render and the active snapshot.
+  pub fn metrics(&self) -> Option<Metrics> {
+    self.metrics
+  }
+
+  // What the viewport shows instead of the live image, None when not comparing
+  // or when the live image is at a different resolution than the snapshot.
+  pub fn compose(&self, live: &Image) -> Option<Image> {
+    let snapshot = &self.snapshots[self.active?].image;
+
+    match self.settings.mode {
+      CompareMode::Split => split(snapshot, live, self.settings.split),
+      CompareMode::Difference => difference(snapshot, live, self.settings.gain),
+    }
+  }
+
+  fn update_metrics(&mut self) {
+    self.metrics = self.active.zip(self.live.as_ref())
+      .and_then(|(active, live)| Metrics::between(&self.snapshots[active].image, live));
+  }
+}
+
+// The left side from `a`, the right side from `b`, with a divider between them.
+pub fn split(a: &Image, b: &Image, position: f32) -> Option<Image> {
+  if !same_size(a, b) {
+    return None;
+  }
+
+  let width = a.width as usize;
+  let divider = (position.clamp(0.0, 1.0) * a.width as f32) as usize;
+
+  let pixels = a.pixels.iter().zip(&b.pixels).enumerate()
+    .map(|(i, (a, b))| {
+      let x = i % width;
+
+      if x == divider {
+        DIVIDER_COLOR
+      } else if x < divider {
+        *a
+      } else {
+        *b
+      }
+    })
+    .collect();
+
+  Some(Image::new(a.width, a.height, pixels))
+}
+
+// Per channel absolute difference, scaled by `gain`.
+pub fn difference(a: &Image, b: &Image, gain: f32) -> Option<Image> {
+  if !same_size(a, b) {
+    return None;
+  }
+
+  let pixels = a.pixels.iter().zip(&b.pixels)
+    .map(|(a, b)| [0, 1, 2].map(|i| (a[i].abs_diff(b[i]) as f32 * gain).min(255.0) as u8))
+    .collect();
+
+  Some(Image::new(a.width, a.height, pixels))
+}
+
+fn same_size(a: &Image, b: &Image) -> bool {
+  a.width == b.width && a.height == b.height
+}
\ No newline at end of file
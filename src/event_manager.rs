@@ -1,21 +1,36 @@
 use std::collections::HashSet;
 
-use glium::glutin::event::{VirtualKeyCode, Event, ElementState, WindowEvent, MouseButton};
+use glium::glutin::event::{VirtualKeyCode, Event, ElementState, WindowEvent, MouseButton, MouseScrollDelta, ModifiersState};
+
+const CLICK_DISTANCE: f64 = 4.0;
+
+// Touchpads scroll in pixels, this many make up one wheel step.
+const PIXELS_PER_LINE: f64 = 40.0;
 
 pub struct EventManager {
   keys_down: HashSet<VirtualKeyCode>,
-  left_mouse_down: bool,
+  key_pressed: Option<VirtualKeyCode>,
+  modifiers: ModifiersState,
+  mouse_down: HashSet<MouseButton>,
+  scroll: f64,
   mouse_position: (f64, f64),
   mouse_move: (f64, f64),
+  press_position: Option<(f64, f64)>,
+  clicked: Option<(f64, f64)>,
 }
 
 impl EventManager {
   pub fn new() -> EventManager {
     EventManager {
       keys_down: HashSet::new(),
-      left_mouse_down: false,
+      key_pressed: None,
+      modifiers: ModifiersState::empty(),
+      mouse_down: HashSet::new(),
+      scroll: 0.0,
       mouse_position: (0.0, 0.0),
       mouse_move: (0.0, 0.0),
+      press_position: None,
+      clicked: None,
     }
   }
 
@@ -23,19 +38,42 @@ impl EventManager {
     self.keys_down.contains(&key)
   }
 
-  pub fn is_left_mouse_down(&self) -> bool {
-    self.left_mouse_down
+  // Key pressed by this event, including key repeats.
+  pub fn key_pressed(&self) -> Option<VirtualKeyCode> {
+    self.key_pressed
+  }
+
+  pub fn modifiers(&self) -> ModifiersState {
+    self.modifiers
+  }
+
+  pub fn is_mouse_down(&self, button: MouseButton) -> bool {
+    self.mouse_down.contains(&button)
+  }
+
+  // Wheel steps scrolled by this event, positive when scrolling up.
+  pub fn scroll(&self) -> f64 {
+    self.scroll
   }
 
   pub fn mouse_move(&self) -> (f64, f64) {
     self.mouse_move
   }
 
+  // Window position of a left click that wasn't part of a drag, only set for the release event.
+  pub fn clicked(&self) -> Option<(f64, f64)> {
+    self.clicked
+  }
+
   pub fn update(&mut self, event: &Event<()>, consumed: bool) {
     self.mouse_move = (0.0, 0.0);
+    self.clicked = None;
+    self.key_pressed = None;
+    self.scroll = 0.0;
     
     if consumed {
-      self.left_mouse_down = false;
+      self.mouse_down.clear();
+      self.press_position = None;
     }
 
     match event {
@@ -46,11 +84,15 @@ impl EventManager {
             if let Some(key) = input.virtual_keycode {
               if input.state == ElementState::Pressed {
                 self.keys_down.insert(key);
+                self.key_pressed = Some(key);
               } else {
                 self.keys_down.remove(&key);
               }
             }
           },
+          WindowEvent::ModifiersChanged(modifiers) => {
+            self.modifiers = *modifiers;
+          },
           WindowEvent::CursorMoved { position, .. } => {
             let (x, y) = self.mouse_position;
 
@@ -64,9 +106,29 @@ impl EventManager {
               position.y,
             );
           },
+          WindowEvent::MouseWheel { delta, .. } if !consumed => {
+            self.scroll = match *delta {
+              MouseScrollDelta::LineDelta(_, y) => y as f64,
+              MouseScrollDelta::PixelDelta(position) => position.y / PIXELS_PER_LINE,
+            };
+          },
           WindowEvent::MouseInput { state, button, .. } => {
+            if *state == ElementState::Pressed {
+              self.mouse_down.insert(*button);
+            } else {
+              self.mouse_down.remove(button);
+            }
+
             if *button == MouseButton::Left {
-              self.left_mouse_down = *state == ElementState::Pressed;
+              if *state == ElementState::Pressed {
+                self.press_position = Some(self.mouse_position);
+              } else if let Some((x, y)) = self.press_position.take() {
+                let (mouse_x, mouse_y) = self.mouse_position;
+
+                if (mouse_x - x).abs() + (mouse_y - y).abs() < CLICK_DISTANCE {
+                  self.clicked = Some(self.mouse_position);
+                }
+              }
             }
           },
           _ => {}
@@ -0,0 +1,288 @@
+use std::path::Path;
+
+use gltf::{Gltf, Node, Primitive, camera::Projection, khr_lights_punctual::Kind, material::AlphaMode, mesh::Mode, buffer::Data};
+
+use crate::{world::{World, Object, Mesh, Material, Light, AmbientLight, DirectionalLight, PointLight, CameraType, Triangle, RGB, Node as SceneNode}, vector::{Vector3, Float}, scene_file::SceneError};
+
+type Matrix = [[f32; 4]; 4];
+
+const IDENTITY: Matrix = [
+  [1.0, 0.0, 0.0, 0.0],
+  [0.0, 1.0, 0.0, 0.0],
+  [0.0, 0.0, 1.0, 0.0],
+  [0.0, 0.0, 0.0, 1.0],
+];
+
+pub const EXTENSIONS: [&str; 2] = ["gltf", "glb"];
+
+struct Importer {
+  world: World,
+  buffers: Vec<Data>,
+  material_names: Vec<String>,
+  has_camera: bool,
+}
+
+pub fn is_gltf(path: &Path) -> bool {
+  path.extension()
+    .and_then(|extension| extension.to_str())
+    .is_some_and(|extension| EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str()))
+}
+
+pub fn import(path: &Path) -> Result<World, SceneError> {
+  let Gltf { document, blob } = Gltf::open(path).map_err(to_scene_error)?;
+  let buffers = gltf::import_buffers(&document, path.parent(), blob).map_err(to_scene_error)?;
+
+  let mut world = World::new();
+  world.objects_mut().clear();
+  world.lights_mut().clear();
+
+  // glTF has no ambient term, without one every shadow would be pitch black.
+  world.lights_mut().push(Light::Ambient(AmbientLight::new()));
+
+  let mut material_names = Vec::new();
+
+  for material in document.materials() {
+    let base_name = match material.name() {
+      Some(name) => String::from(name),
+      None => format!("material {}", material_names.len()),
+    };
+
+    let mut name = base_name.clone();
+    let mut suffix = 1;
+
+    while world.materials().contains_key(&name) {
+      suffix += 1;
+      name = format!("{} {}", base_name, suffix);
+    }
+
+    world.materials_mut().insert(name.clone(), convert_material(&material));
+    material_names.push(name);
+  }
+
+  let mut importer = Importer {
+    world,
+    buffers,
+    material_names,
+    has_camera: false,
+  };
+
+  if let Some(scene) = document.default_scene().or_else(|| document.scenes().next()) {
+    for node in scene.nodes() {
+      importer.node(&node, &IDENTITY);
+    }
+  }
+
+  Ok(importer.world)
+}
+
+impl Importer {
+  fn node(&mut self, node: &Node, parent: &Matrix) {
+    let transform = multiply(parent, &node.transform().matrix());
+
+    if let Some(mesh) = node.mesh() {
+      for primitive in mesh.primitives() {
+        self.primitive(&primitive, &transform, node.name());
+      }
+    }
+
+    if let Some(camera) = node.camera() {
+      self.camera(&camera, &transform);
+    }
+
+    if let Some(light) = node.light() {
+      self.light(&light, &transform, node.name());
+    }
+
+    for child in node.children() {
+      self.node(&child, &transform);
+    }
+  }
+
+  fn primitive(&mut self, primitive: &Primitive, transform: &Matrix, name: Option<&str>) {
+    let reader = primitive.reader(|buffer| self.buffers.get(buffer.index()).map(|data| &data.0[..]));
+
+    let positions: Vec<Vector3> = match reader.read_positions() {
+      Some(positions) => positions.map(|position| convert_point(transform, position)).collect(),
+      None => return,
+    };
+
+    let indices: Vec<usize> = match reader.read_indices() {
+      Some(indices) => indices.into_u32().map(|index| index as usize).collect(),
+      None => (0..positions.len()).collect(),
+    };
+
+    let corners: Vec<[usize; 3]> = match primitive.mode() {
+      Mode::Triangles => indices.chunks_exact(3).map(|chunk| [chunk[0], chunk[1], chunk[2]]).collect(),
+      Mode::TriangleStrip => (2..indices.len()).map(|i| {
+        if i % 2 == 0 {
+          [indices[i - 2], indices[i - 1], indices[i]]
+        } else {
+          [indices[i - 1], indices[i - 2], indices[i]]
+        }
+      }).collect(),
+      Mode::TriangleFan => (2..indices.len()).map(|i| [indices[0], indices[i - 1], indices[i]]).collect(),
+      _ => return,
+    };
+
+    // Mirroring z flips the winding once, a mirroring node transform flips it back.
+    let mirrored = determinant(transform) < 0.0;
+
+    let triangles: Vec<Triangle> = corners.into_iter()
+      .filter(|corner| corner.iter().all(|index| *index < positions.len()))
+      .map(|[a, b, c]| if mirrored {
+        [positions[a], positions[b], positions[c]]
+      } else {
+        [positions[a], positions[c], positions[b]]
+      })
+      .collect();
+
+    if triangles.is_empty() {
+      return;
+    }
+
+    let mut mesh = Mesh::new(triangles);
+    mesh.node = scene_node(name);
+
+    if let Some([min, max]) = mesh.bounds() {
+      let center = (min + max) * 0.5;
+
+      for vertex in mesh.triangles.iter_mut().flat_map(|triangle| triangle.iter_mut()) {
+        *vertex = *vertex - center;
+      }
+
+      mesh.position = center;
+      mesh.update_bounds();
+    }
+
+    if let Some(index) = primitive.material().index() {
+      let name = &self.material_names[index];
+
+      mesh.material = self.world.materials()[name];
+      mesh.material_name = Some(name.clone());
+    }
+
+    self.world.objects_mut().push(Object::Mesh(mesh));
+  }
+
+  fn camera(&mut self, camera: &gltf::Camera, transform: &Matrix) {
+    if self.has_camera {
+      return;
+    }
+
+    self.has_camera = true;
+
+    let camera_info = self.world.camera_mut().camera_info_mut();
+    camera_info.position = convert_point(transform, [0.0, 0.0, 0.0]);
+    camera_info.forward = convert_direction(transform, [0.0, 0.0, -1.0]).normalise();
+
+    match camera.projection() {
+      Projection::Perspective(perspective) => {
+        camera_info.camera_type = CameraType::Perspective;
+        camera_info.vertical_fov = (perspective.yfov() as Float / 2.0).tan();
+
+        if let Some(aspect_ratio) = perspective.aspect_ratio() {
+          camera_info.viewport_width = (camera_info.viewport_height as f32 * aspect_ratio).round().max(1.0) as u32;
+        }
+      },
+      Projection::Orthographic(orthographic) => {
+        camera_info.camera_type = CameraType::Orthographic;
+        camera_info.camera_height = orthographic.ymag() as Float;
+      },
+    }
+  }
+
+  fn light(&mut self, light: &gltf::khr_lights_punctual::Light, transform: &Matrix, name: Option<&str>) {
+    let color = light_color(light.color(), light.intensity());
+
+    let light = match light.kind() {
+      Kind::Directional => Light::Directional(DirectionalLight {
+        color,
+        direction: convert_direction(transform, [0.0, 0.0, -1.0]).normalise(),
+        node: scene_node(name.or(light.name())),
+      }),
+      Kind::Point | Kind::Spot { .. } => Light::Point(PointLight {
+        color,
+        position: convert_point(transform, [0.0, 0.0, 0.0]),
+        node: scene_node(name.or(light.name())),
+      }),
+    };
+
+    self.world.lights_mut().push(light);
+  }
+}
+
+fn convert_material(material: &gltf::Material) -> Material {
+  let pbr = material.pbr_metallic_roughness();
+  let [red, green, blue, alpha] = pbr.base_color_factor();
+
+  let metallic = pbr.metallic_factor() as Float;
+  let roughness = pbr.roughness_factor() as Float;
+
+  let transmission = material.transmission().map_or(0.0, |transmission| transmission.transmission_factor()) as Float;
+  let blend = if material.alpha_mode() == AlphaMode::Blend { 1.0 - alpha as Float } else { 0.0 };
+
+  Material {
+    ambient_reflection: 1.0,
+    has_specular: roughness < 1.0,
+    // Blinn-Phong exponent with roughly the same highlight width as the roughness.
+    specular_reflection: (2.0 / roughness.powi(4).max(0.001) - 2.0).clamp(1.0, 1000.0),
+    diffuse_reflection: 1.0 - metallic * (1.0 - roughness),
+    reflectivity: metallic * (1.0 - roughness),
+    transparency: transmission.max(blend),
+    refractive_index: material.ior().unwrap_or(1.5) as Float,
+    color: [red, green, blue],
+  }
+}
+
+fn scene_node(name: Option<&str>) -> SceneNode {
+  SceneNode {
+    name: name.map(String::from),
+    ..SceneNode::new()
+  }
+}
+
+// Lights here have no physical units, so the intensity can only dim the color.
+fn light_color(color: [f32; 3], intensity: f32) -> RGB {
+  let scale = intensity.clamp(0.0, 1.0);
+
+  [color[0] * scale, color[1] * scale, color[2] * scale]
+}
+
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+  let mut result = [[0.0; 4]; 4];
+
+  for column in 0..4 {
+    for row in 0..4 {
+      result[column][row] = (0..4).map(|k| a[k][row] * b[column][k]).sum();
+    }
+  }
+
+  result
+}
+
+fn determinant(m: &Matrix) -> f32 {
+  m[0][0] * (m[1][1] * m[2][2] - m[2][1] * m[1][2]) -
+  m[1][0] * (m[0][1] * m[2][2] - m[2][1] * m[0][2]) +
+  m[2][0] * (m[0][1] * m[1][2] - m[1][1] * m[0][2])
+}
+
+// glTF is right handed with the camera looking down -z, the renderer looks down +z.
+fn convert_point(m: &Matrix, [x, y, z]: [f32; 3]) -> Vector3 {
+  let transformed = convert_direction(m, [x, y, z]);
+
+  transformed + Vector3::new(m[3][0] as Float, m[3][1] as Float, -m[3][2] as Float)
+}
+
+fn convert_direction(m: &Matrix, [x, y, z]: [f32; 3]) -> Vector3 {
+  let axis = |row: usize| (m[0][row] * x + m[1][row] * y + m[2][row] * z) as Float;
+
+  Vector3::new(axis(0), axis(1), -axis(2))
+}
+
+fn to_scene_error(err: gltf::Error) -> SceneError {
+  match err {
+    gltf::Error::Io(err) => SceneError::Io(err),
+    gltf::Error::Deserialize(err) => SceneError::from(err),
+    err => SceneError::Validation(err.to_string()),
+  }
+}
\ No newline at end of file
@@ -1,77 +1,149 @@
-use std::{borrow::{Cow}};
+use std::{borrow::{Cow}, time::{Instant, Duration}};
 
 use glium::{Frame, Surface, Display, texture::{ClientFormat}, Rect, Texture2d, BlitTarget, uniforms::MagnifySamplerFilter};
 
-use crate::{renderer::Renderer, world::World};
+use crate::{render_thread::{RenderThread, RenderedFrame}, world::{World, Selection}, event_manager::EventManager, renderer::{Renderer, RenderedData}, vector::Float, stats::RenderStats, comparison::{Comparison, CompareSettings}, image::Image, bindings::Bindings};
+
+const SETTLE_TIME: Duration = Duration::from_millis(250);
+const OUTLINE_COLOR: [u8; 3] = [255, 160, 0];
 
 pub struct Graphics {
   texture: Texture2d,
-  renderer: Renderer,
+  render_thread: RenderThread,
   world: World,
+  bindings: Bindings,
+  rendered_world: World,
+  rendered_scale: f64,
+  rendered_complete: bool,
+  navigation_scale: f64,
+  last_moved: Instant,
+  frame: Option<RenderedFrame>,
+  stats: Option<RenderStats>,
+  comparison: Comparison,
+  compared: (Option<usize>, CompareSettings),
+  outlined: Option<Selection>,
+  dest_rect: BlitTarget,
+  framebuffer_height: u32,
 }
 
 impl Graphics {
   pub fn new(display: &Display) -> Graphics {
+    let render_thread = RenderThread::new();
 
-    let mut renderer = Renderer::new();
-
-    let mut world = World::new();
-
-    let data = renderer.render(&mut world);
+    let world = World::new();
+    let rendered_world = world.clone();
 
-    let image_raw = glium::texture::RawImage2d {
-      data: Cow::Borrowed(data.image_buffer.flatten()),
-      format: ClientFormat::U8U8U8,
-      width: data.image_width,
-      height: data.image_height
-    };
+    render_thread.render(rendered_world.clone());
 
-    let texture = glium::texture::Texture2d::new(display, image_raw).unwrap();
+    let texture = glium::texture::Texture2d::empty(display, 1, 1).unwrap();
 
     Graphics {
       texture,
-      renderer,
-      world
+      render_thread,
+      world,
+      bindings: Bindings::new(),
+      rendered_world,
+      rendered_scale: 1.0,
+      rendered_complete: false,
+      navigation_scale: 0.5,
+      last_moved: Instant::now() - SETTLE_TIME,
+      frame: None,
+      stats: None,
+      comparison: Comparison::new(),
+      compared: (None, CompareSettings::new()),
+      outlined: None,
+      dest_rect: BlitTarget { left: 0, bottom: 0, width: 0, height: 0 },
+      framebuffer_height: 0,
     }
   }
 
   pub fn world(&mut self) -> &mut World {
     &mut self.world
   }
+
+  // Stats of the last complete frame, handed out once.
+  pub fn take_stats(&mut self) -> Option<RenderStats> {
+    self.stats.take()
+  }
+
+  pub fn bindings(&self) -> &Bindings {
+    &self.bindings
+  }
+
+  pub fn bindings_mut(&mut self) -> &mut Bindings {
+    &mut self.bindings
+  }
+
+  pub fn comparison_mut(&mut self) -> &mut Comparison {
+    &mut self.comparison
+  }
+
+  pub fn update(&mut self, event_manager: &EventManager) {
+    self.world.update(event_manager, &self.bindings);
+
+    if self.world.camera().moved() {
+      self.last_moved = Instant::now();
+    }
+
+    if let Some(position) = event_manager.clicked() {
+      self.pick(position);
+    }
+  }
   
   pub fn draw(&mut self, target: &mut Frame, display: &Display) {
-    let renderer = &mut self.renderer;
+    let settings = *self.world.camera().dynamic_resolution();
+    let navigating = settings.enabled && self.last_moved.elapsed() < SETTLE_TIME;
 
-    let data = renderer.render(&mut self.world);
+    if let Some(mut frame) = self.render_thread.poll() {
+      let stats = frame.stats.take();
 
-    let image_raw = glium::texture::RawImage2d {
-      data: Cow::Borrowed(data.image_buffer.flatten()),
-      format: ClientFormat::U8U8U8,
-      width: data.image_width,
-      height: data.image_height,
-    };
+      if frame.complete {
+        self.rendered_complete = true;
+        self.comparison.set_live(Graphics::frame_image(&frame));
 
-    let rect = Rect { 
-      left: 0, 
-      bottom: 0, 
-      width: data.image_width,
-      height: data.image_height,
-    };
+        if navigating {
+          self.adapt_navigation_scale(&frame);
+        }
+      }
 
-    if self.texture.dimensions() == (data.image_width, data.image_height) {
-      self.texture.write(rect, image_raw);
+      self.frame = Some(frame);
+
+      let instant = Instant::now();
+      self.upload(display);
+
+      if let Some(mut stats) = stats {
+        stats.texture_upload = instant.elapsed();
+        self.stats = Some(stats);
+      }
+    } else if self.world.selection() != self.outlined || self.comparison_state() != self.compared {
+      self.upload(display);
+    }
+
+    let scale = if navigating {
+      self.navigation_scale
+    } else if self.rendered_complete {
+      (self.rendered_scale * 2.0).min(1.0)
     } else {
-      self.texture = glium::texture::Texture2d::new(display, image_raw).unwrap();
+      self.rendered_scale
+    };
+
+    if !self.world.same_scene(&self.rendered_world) || scale != self.rendered_scale {
+      self.rendered_world = self.world.clone();
+      self.rendered_scale = scale;
+      self.rendered_complete = false;
+
+      self.render_thread.render(self.scaled_world(scale));
     }
 
+    let (image_width, image_height) = self.texture.dimensions();
     let (width, height) = display.get_framebuffer_dimensions();
 
-    let image_ratio = data.image_width as f64 / data.image_height as f64;
+    let image_ratio = image_width as f64 / image_height as f64;
     let dest_ratio = width as f64 / height as f64;
 
     let dest_rect = if image_ratio > dest_ratio {
-      let scale = width as f64 / data.image_width as f64;
-      let adjusted_height = (data.image_height as f64 * scale) as i32;
+      let scale = width as f64 / image_width as f64;
+      let adjusted_height = (image_height as f64 * scale) as i32;
 
       BlitTarget {
         left: 0,
@@ -80,8 +152,8 @@ impl Graphics {
         height: adjusted_height,
       }
     } else {
-      let scale = height as f64 / data.image_height as f64;
-      let adjusted_width = (data.image_width as f64 * scale) as i32;
+      let scale = height as f64 / image_height as f64;
+      let adjusted_width = (image_width as f64 * scale) as i32;
 
       BlitTarget {
         left: (width - adjusted_width as u32) / 2,
@@ -91,8 +163,122 @@ impl Graphics {
       }
     };
 
+    self.dest_rect = dest_rect;
+    self.framebuffer_height = height;
+
     target.clear_color(0_f32, 0_f32, 0_f32, 1_f32);
 
     self.texture.as_surface().blit_whole_color_to(target, &dest_rect, MagnifySamplerFilter::Linear);
   }
+
+  fn scaled_world(&self, scale: f64) -> World {
+    let mut world = self.rendered_world.clone();
+
+    if scale < 1.0 {
+      let navigation_depth = world.camera().dynamic_resolution().depth;
+      let depth = world.camera_mut().depth_mut();
+      *depth = (*depth).min(navigation_depth);
+
+      let camera_info = world.camera_mut().camera_info_mut();
+      camera_info.viewport_width = ((camera_info.viewport_width as f64 * scale) as u32).max(1);
+      camera_info.viewport_height = ((camera_info.viewport_height as f64 * scale) as u32).max(1);
+    }
+
+    world
+  }
+
+  fn adapt_navigation_scale(&mut self, frame: &RenderedFrame) {
+    let settings = self.world.camera().dynamic_resolution();
+    let full_width = self.world.camera().camera_info().viewport_width;
+
+    let frame_scale = frame.image_width as f64 / full_width as f64;
+    let target_time = 1.0 / settings.target_fps;
+    let factor = (target_time / frame.render_time.as_secs_f64()).sqrt().clamp(0.5, 2.0);
+
+    self.navigation_scale = (frame_scale * factor).clamp(settings.min_scale.min(1.0), 1.0);
+  }
+
+  // Window coordinates start at the top left, the blit target at the bottom left.
+  fn pick(&mut self, (x, y): (f64, f64)) {
+    let target = self.dest_rect;
+
+    let x = (x - target.left as f64) / target.width as f64;
+    let y = (self.framebuffer_height as f64 - y - target.bottom as f64) / target.height as f64;
+
+    if !(0.0..1.0).contains(&x) || !(0.0..1.0).contains(&y) {
+      return;
+    }
+
+    // Clicking a locked object clears the selection like clicking the background.
+    let object = Renderer::pick(&self.world, x as Float, y as Float)
+      .filter(|object| !self.world.objects()[*object].node().locked);
+
+    self.world.select(object.map(Selection::Object));
+
+    // The orbit profile turns around whatever was picked last.
+    if let Some([min, max]) = object.and_then(|object| self.world.objects()[object].bounds()) {
+      self.world.camera_mut().set_target(Some((min + max) / 2.0));
+    }
+  }
+
+  fn comparison_state(&self) -> (Option<usize>, CompareSettings) {
+    (self.comparison.active(), *self.comparison.settings())
+  }
+
+  fn frame_image(frame: &RenderedFrame) -> Image {
+    RenderedData {
+      image_buffer: &frame.image_buffer,
+      image_width: frame.image_width,
+      image_height: frame.image_height,
+    }.to_image()
+  }
+
+  fn upload(&mut self, display: &Display) {
+    let frame = match self.frame.take() {
+      Some(frame) => frame,
+      None => return,
+    };
+
+    self.outlined = self.world.selection();
+    self.compared = self.comparison_state();
+
+    let mut image_buffer = Cow::Borrowed(&frame.image_buffer);
+
+    if self.comparison.active().is_some() {
+      if let Some(image) = self.comparison.compose(&Graphics::frame_image(&frame)) {
+        // Images start at the top row, the texture at the bottom one.
+        let rows = image.pixels.chunks_exact(image.width as usize).rev();
+        image_buffer = Cow::Owned(rows.flat_map(|row| row.iter().copied()).collect());
+      }
+    }
+
+    // Partial frames have no object ids yet, so the outline appears once the frame is complete.
+    if let Some(Selection::Object(object)) = self.outlined {
+      if let Some(object_ids) = frame.object_ids.as_ref().filter(|ids| ids.len() == frame.image_buffer.len()) {
+        Renderer::draw_outline(image_buffer.to_mut(), object_ids, frame.image_width, object, OUTLINE_COLOR);
+      }
+    }
+
+    let image_raw = glium::texture::RawImage2d {
+      data: Cow::Borrowed(image_buffer.flatten()),
+      format: ClientFormat::U8U8U8,
+      width: frame.image_width,
+      height: frame.image_height,
+    };
+
+    let rect = Rect { 
+      left: 0, 
+      bottom: 0, 
+      width: frame.image_width,
+      height: frame.image_height,
+    };
+
+    if self.texture.dimensions() == (frame.image_width, frame.image_height) {
+      self.texture.write(rect, image_raw);
+    } else {
+      self.texture = glium::texture::Texture2d::new(display, image_raw).unwrap();
+    }
+
+    self.frame = Some(frame);
+  }
 }
@@ -0,0 +1,137 @@
+use std::path::Path;
+
+use egui::{Style, epaint::Shadow, Frame, Color32};
+
+use crate::bindings::{Bindings, Binding, Controls, Action, Profile, BINDINGS_FILE};
+
+use super::utils::{ShowableUI, combo};
+
+pub struct BindingsPanel {
+  showing: bool,
+  label: String,
+  // Text for every action, kept while it is being typed and rebuilt when the controls change elsewhere.
+  texts: Vec<String>,
+  synced: Option<(Profile, Controls)>,
+  error: Option<String>,
+}
+
+impl ShowableUI<&mut Bindings> for BindingsPanel {
+  fn ui(&mut self, ctx: &egui::Context, bindings: &mut Bindings) {
+    let current = (bindings.profile, bindings.controls().clone());
+
+    if self.synced.as_ref() != Some(&current) {
+      self.texts = Action::ALL.iter().map(|action| Binding::list_to_string(current.1.bindings(*action))).collect();
+      self.synced = Some(current);
+    }
+
+    let mut showing = self.showing;
+
+    egui::Window::new(self.label.as_str())
+      .open(&mut showing)
+      .frame(
+        Frame::window(&Style::default())
+          .shadow(Shadow::NONE)
+      )
+      .show(ctx, |ui| {
+        egui::Grid::new("bindings_grid")
+          .num_columns(2)
+          .spacing([40.0, 4.0])
+          .striped(true)
+          .show(ui, |ui| {
+            ui.label("Profile");
+            combo(ui, &mut bindings.profile, vec![Profile::Fly, Profile::Orbit]);
+            ui.end_row();
+
+            ui.label("Wheel Speed");
+            ui.add(egui::DragValue::new(&mut bindings.wheel_speed).clamp_range(1.0..=4.0).speed(0.01));
+            ui.end_row();
+
+            ui.label("Wheel Zoom");
+            ui.add(egui::DragValue::new(&mut bindings.wheel_zoom).clamp_range(0.0..=0.9).speed(0.01));
+            ui.end_row();
+
+            ui.label("Fast Factor");
+            ui.add(egui::DragValue::new(&mut bindings.fast_factor).clamp_range(1.0..=100.0).speed(0.1));
+            ui.end_row();
+
+            ui.label("Slow Factor");
+            ui.add(egui::DragValue::new(&mut bindings.slow_factor).clamp_range(0.01..=1.0).speed(0.01));
+            ui.end_row();
+
+            for (action, text) in Action::ALL.iter().zip(&mut self.texts) {
+              ui.label(action.to_string());
+
+              if ui.text_edit_singleline(text).changed() {
+                match Binding::parse_list(text) {
+                  Ok(parsed) => {
+                    *bindings.controls_mut().bindings_mut(*action) = parsed;
+                    self.synced = Some((bindings.profile, bindings.controls().clone()));
+                    self.error = None;
+                  },
+                  Err(err) => self.error = Some(err.to_string()),
+                }
+              }
+              ui.end_row();
+            }
+          });
+
+        if let Some(error) = &self.error {
+          ui.colored_label(Color32::RED, error);
+        }
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+          if ui.button("Save").clicked() {
+            self.error = bindings.save(Path::new(BINDINGS_FILE)).err().map(|err| err.to_string());
+          }
+
+          if ui.button("Reload").clicked() {
+            self.load(bindings);
+          }
+
+          if ui.button("Defaults").clicked() {
+            *bindings = Bindings::new();
+            self.error = None;
+          }
+        });
+
+        ui.label(format!("Names are comma separated, like \"W, Up\" or \"MiddleMouse\". Saved to {}.", BINDINGS_FILE));
+      });
+
+    self.showing = showing;
+  }
+
+  fn label(&self) -> &str {
+    self.label.as_str()
+  }
+
+  fn show(&mut self) {
+    self.showing = true;
+  }
+}
+
+impl BindingsPanel {
+  pub fn new() -> BindingsPanel {
+    BindingsPanel {
+      label: String::from("Key Bindings"),
+      showing: false,
+      texts: Vec::new(),
+      synced: None,
+      error: None,
+    }
+  }
+
+  // A file that can't be read keeps the current bindings and says why.
+  fn load(&mut self, bindings: &mut Bindings) {
+    match Bindings::load(Path::new(BINDINGS_FILE)) {
+      Ok(loaded) => {
+        *bindings = loaded;
+        self.error = None;
+      },
+      Err(err) => {
+        self.error = Some(format!("{}: {}", BINDINGS_FILE, err));
+      },
+    }
+  }
+}
\ No newline at end of file
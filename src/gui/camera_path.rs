@@ -0,0 +1,211 @@
+use std::time::Instant;
+
+use egui::{Style, epaint::Shadow, Frame, Color32};
+use rfd::FileDialog;
+
+use crate::{world::World, camera_path::CameraPath, animation::Track, vector::Float, scene_file::SceneError, history::Target};
+
+use super::utils::{ShowableUI, Editor};
+
+#[derive(PartialEq, Clone, Copy)]
+enum Mode {
+  Idle,
+  Recording,
+  Playing,
+}
+
+pub struct CameraRecorder {
+  showing: bool,
+  label: String,
+  path: CameraPath,
+  mode: Mode,
+  started: Instant,
+  next_sample: usize,
+  real_time: bool,
+  tolerance: Float,
+  error: Option<String>,
+}
+
+impl ShowableUI<Editor<'_>> for CameraRecorder {
+  fn ui(&mut self, ctx: &egui::Context, editor: Editor) {
+    let Editor { world, history } = editor;
+
+    let time = self.started.elapsed().as_secs_f64() as Float;
+
+    match self.mode {
+      Mode::Recording => {
+        self.path.record(time, *world.camera().camera_info());
+        ctx.request_repaint();
+      },
+      Mode::Playing => {
+        // Stepping one sample per frame shows every recorded view regardless of
+        // how fast frames are drawn, which keeps benchmark runs comparable.
+        let camera_info = if self.real_time {
+          self.path.sample(time).filter(|_| time <= self.path.duration())
+        } else {
+          self.next_sample += 1;
+          self.path.samples.get(self.next_sample - 1).map(|sample| sample.camera_info)
+        };
+
+        match camera_info {
+          Some(camera_info) => *world.camera_mut().camera_info_mut() = camera_info,
+          None => self.mode = Mode::Idle,
+        }
+
+        ctx.request_repaint();
+      },
+      Mode::Idle => (),
+    }
+
+    let mut showing = self.showing;
+
+    egui::Window::new(self.label.as_str())
+      .open(&mut showing)
+      .frame(
+        Frame::window(&Style::default())
+          .shadow(Shadow::NONE)
+      )
+      .show(ctx, |ui| {
+        egui::Grid::new("camera_path_grid")
+          .num_columns(2)
+          .spacing([40.0, 4.0])
+          .striped(true)
+          .show(ui, |ui| {
+            ui.label("Samples");
+            ui.label(self.path.samples.len().to_string());
+            ui.end_row();
+
+            ui.label("Duration");
+            ui.label(format!("{:.2} s", self.path.duration()));
+            ui.end_row();
+
+            ui.label("Record");
+            let recording = self.mode == Mode::Recording;
+            if ui.selectable_label(recording, if recording { "Stop" } else { "Record" }).clicked() {
+              if recording {
+                self.mode = Mode::Idle;
+              } else {
+                self.path = CameraPath::new();
+                self.start(Mode::Recording);
+              }
+            }
+            ui.end_row();
+
+            ui.label("Playback");
+            ui.horizontal(|ui| {
+              let playing = self.mode == Mode::Playing;
+
+              ui.add_enabled_ui(!self.path.samples.is_empty(), |ui| {
+                if ui.selectable_label(playing, if playing { "Stop" } else { "Play" }).clicked() {
+                  if playing {
+                    self.mode = Mode::Idle;
+                  } else {
+                    self.start(Mode::Playing);
+                  }
+                }
+              });
+
+              let real_time_label = if self.real_time { "real time" } else { "every sample" };
+              ui.toggle_value(&mut self.real_time, real_time_label);
+            });
+            ui.end_row();
+
+            ui.label("File");
+            ui.horizontal(|ui| {
+              if ui.button("Open").clicked() {
+                self.error = self.open().err().map(|err| err.to_string());
+              }
+
+              if ui.button("Save").clicked() {
+                self.error = self.save().err().map(|err| err.to_string());
+              }
+            });
+            ui.end_row();
+
+            ui.label("Tolerance");
+            ui.add(
+              egui::DragValue::new(&mut self.tolerance)
+                .clamp_range(0.0..=1.0)
+                .speed(0.001)
+            );
+            ui.end_row();
+
+            ui.label("Keyframes");
+            ui.add_enabled_ui(!self.path.samples.is_empty(), |ui| {
+              if ui.button("Convert").clicked() {
+                history.change(world, &[Target::Animation], |world| self.convert(world));
+              }
+            });
+            ui.end_row();
+          });
+
+        if let Some(error) = &self.error {
+          ui.colored_label(Color32::LIGHT_RED, error);
+        }
+      });
+
+    self.showing = showing;
+  }
+
+  fn label(&self) -> &str {
+    self.label.as_str()
+  }
+
+  fn show(&mut self) {
+    self.showing = true;
+  }
+}
+
+impl CameraRecorder {
+  pub fn new() -> CameraRecorder {
+    CameraRecorder {
+      label: String::from("Camera Path"),
+      showing: false,
+      path: CameraPath::new(),
+      mode: Mode::Idle,
+      started: Instant::now(),
+      next_sample: 0,
+      real_time: true,
+      tolerance: 0.01,
+      error: None,
+    }
+  }
+
+  fn start(&mut self, mode: Mode) {
+    self.mode = mode;
+    self.started = Instant::now();
+    self.next_sample = 0;
+  }
+
+  fn open(&mut self) -> Result<(), SceneError> {
+    if let Some(file) = CameraRecorder::dialog().pick_file() {
+      self.path = CameraPath::load(file.as_path())?;
+      self.mode = Mode::Idle;
+    }
+
+    Ok(())
+  }
+
+  fn save(&self) -> Result<(), SceneError> {
+    match CameraRecorder::dialog().save_file() {
+      Some(file) => self.path.save(file.as_path()),
+      None => Ok(()),
+    }
+  }
+
+  // Replaces the camera tracks, other tracks are left alone.
+  fn convert(&self, world: &mut World) {
+    let animation = world.animation_mut();
+    let tracks = self.path.to_tracks(animation.frame_rate, self.tolerance);
+
+    animation.tracks.retain(|track| !matches!(track, Track::CameraPosition(_) | Track::CameraForward(_) | Track::CameraFov(_)));
+    animation.tracks.extend(tracks);
+    animation.end = animation.end.max((self.path.duration() * animation.frame_rate).ceil() as u32);
+  }
+
+  fn dialog() -> FileDialog {
+    FileDialog::new()
+      .add_filter("Camera Path", &["json"])
+      .set_directory("/")
+  }
+}
\ No newline at end of file
@@ -1,16 +1,27 @@
 use egui::{Style, epaint::Shadow, Frame};
 
-use crate::world::{World, CameraType};
+use crate::{world::{CameraType, Selection}, history::Target};
 
-use super::utils::{ShowableUI, combo};
+use super::utils::{ShowableUI, Editor, combo};
 
 pub struct CameraSettings {
   showing: bool,
+  selection: Option<Selection>,
   label: String,
 }
 
-impl ShowableUI<&mut World> for CameraSettings {
-  fn ui(&mut self, ctx: &egui::Context, world: &mut World) {
+impl ShowableUI<Editor<'_>> for CameraSettings {
+  fn ui(&mut self, ctx: &egui::Context, editor: Editor) {
+    let Editor { world, history } = editor;
+
+    if world.selection() != self.selection {
+      self.selection = world.selection();
+
+      if self.selection == Some(Selection::Camera) {
+        self.showing = true;
+      }
+    }
+
     egui::Window::new(self.label())
       .open(&mut self.showing)
       .frame(
@@ -18,6 +29,9 @@ impl ShowableUI<&mut World> for CameraSettings {
           .shadow(Shadow::NONE)
       )
       .show(ctx, |ui| {
+        history.watch(world, Target::Camera);
+        let before = (*world.camera().camera_info(), *world.camera().depth());
+
         egui::Grid::new("my_grid")
           .num_columns(2)
           .spacing([40.0, 4.0])
@@ -87,13 +101,69 @@ impl ShowableUI<&mut World> for CameraSettings {
 
             ui.end_row(); 
 
+            ui.label("Orbit Target");
+            ui.horizontal(|ui| {
+              match world.camera().target() {
+                Some(target) => {
+                  ui.label(format!("{:.2}, {:.2}, {:.2}", target.x, target.y, target.z));
+
+                  if ui.button("Clear").clicked() {
+                    world.camera_mut().set_target(None);
+                  }
+                },
+                None => {
+                  ui.label("in front of the camera");
+                },
+              }
+            });
+
+            ui.end_row(); 
+
             ui.label("Ray Depth");
             ui.add(
               egui::DragValue::new(world.camera_mut().depth_mut())
                 .clamp_range(0..=100)
                 .speed(1)
             );
-          })
+
+            ui.end_row(); 
+
+            let dynamic_resolution = world.camera_mut().dynamic_resolution_mut();
+
+            ui.label("Dynamic Resolution");
+            let dynamic_label = if dynamic_resolution.enabled { "on" } else { "off" };
+            ui.toggle_value(&mut dynamic_resolution.enabled, dynamic_label);
+            ui.end_row(); 
+
+            if dynamic_resolution.enabled {
+              ui.label("Target FPS");
+              ui.add(
+                egui::DragValue::new(&mut dynamic_resolution.target_fps)
+                  .clamp_range(1..=240)
+                  .speed(1)
+              );
+              ui.end_row(); 
+
+              ui.label("Minimum Scale");
+              ui.add(
+                egui::DragValue::new(&mut dynamic_resolution.min_scale)
+                  .clamp_range(0.05..=1.0)
+                  .speed(0.01)
+              );
+              ui.end_row(); 
+
+              ui.label("Moving Ray Depth");
+              ui.add(
+                egui::DragValue::new(&mut dynamic_resolution.depth)
+                  .clamp_range(0..=100)
+                  .speed(1)
+              );
+            }
+          });
+
+        if (*world.camera().camera_info(), *world.camera().depth()) != before {
+          history.edited(Target::Camera);
+        }
       });
   }
 
@@ -110,6 +180,7 @@ impl CameraSettings {
   pub fn new() -> CameraSettings {
     CameraSettings {
       label: String::from("Camera Settings"),
+      selection: None,
       showing: false,
     }
   }
@@ -0,0 +1,178 @@
+use egui::{Style, epaint::Shadow, Frame, Color32, Stroke, Align2, FontId, Area, Order, Id, Sense, pos2, vec2};
+
+use crate::comparison::{Comparison, CompareMode};
+
+use super::utils::{ShowableUI, combo, viewport_rect};
+
+// Width of the grab area around the split line, in points.
+const HANDLE_WIDTH: f32 = 12.0;
+
+pub struct ComparePanel {
+  showing: bool,
+  label: String,
+  name: String,
+}
+
+impl ShowableUI<&mut Comparison> for ComparePanel {
+  fn ui(&mut self, ctx: &egui::Context, comparison: &mut Comparison) {
+    let mut showing = self.showing;
+
+    egui::Window::new(self.label.as_str())
+      .open(&mut showing)
+      .frame(
+        Frame::window(&Style::default())
+          .shadow(Shadow::NONE)
+      )
+      .show(ctx, |ui| {
+        egui::Grid::new("compare_grid")
+          .num_columns(2)
+          .spacing([40.0, 4.0])
+          .striped(true)
+          .show(ui, |ui| {
+            ui.label("Snapshot");
+            ui.horizontal(|ui| {
+              ui.add(egui::TextEdit::singleline(&mut self.name).desired_width(120.0).hint_text("Name"));
+
+              if ui.add_enabled(comparison.live().is_some(), egui::Button::new("Take")).clicked() {
+                let name = match self.name.trim() {
+                  "" => format!("Snapshot {}", comparison.snapshots().len() + 1),
+                  name => name.to_string(),
+                };
+
+                if comparison.take_snapshot(&name) {
+                  comparison.select(Some(comparison.snapshots().len() - 1));
+                  self.name.clear();
+                }
+              }
+            });
+            ui.end_row();
+
+            ui.label("Compare With");
+            let mut active = comparison.active();
+            let selected_text = active.map_or("None", |index| comparison.snapshots()[index].name.as_str());
+
+            egui::ComboBox::from_id_source("compare_snapshot")
+              .selected_text(selected_text)
+              .show_ui(ui, |ui| {
+                ui.selectable_value(&mut active, None, "None");
+
+                for (index, snapshot) in comparison.snapshots().iter().enumerate() {
+                  ui.selectable_value(&mut active, Some(index), snapshot.name.as_str());
+                }
+              });
+
+            if active != comparison.active() {
+              comparison.select(active);
+            }
+            ui.end_row();
+
+            let index = match comparison.active() {
+              Some(index) => index,
+              None => return,
+            };
+
+            let settings = comparison.settings_mut();
+
+            ui.label("Mode");
+            combo(ui, &mut settings.mode, vec![CompareMode::Split, CompareMode::Difference]);
+            ui.end_row();
+
+            match settings.mode {
+              CompareMode::Split => {
+                ui.label("Split");
+                ui.add(egui::Slider::new(&mut settings.split, 0.0..=1.0));
+              },
+              CompareMode::Difference => {
+                ui.label("Gain");
+                ui.add(egui::Slider::new(&mut settings.gain, 1.0..=32.0).logarithmic(true));
+              },
+            }
+            ui.end_row();
+
+            let metrics = comparison.metrics();
+
+            ui.label("RMSE");
+            ui.label(metrics.map_or(String::from("-"), |metrics| format!("{:.3}", metrics.rmse)));
+            ui.end_row();
+
+            ui.label("PSNR");
+            ui.label(metrics.map_or(String::from("-"), |metrics| {
+              if metrics.psnr.is_finite() {
+                format!("{:.2} dB", metrics.psnr)
+              } else {
+                String::from("identical")
+              }
+            }));
+            ui.end_row();
+
+            ui.label("Remove Snapshot");
+            if ui.button("Remove").clicked() {
+              comparison.remove_snapshot(index);
+            }
+            ui.end_row();
+          });
+      });
+
+    self.split_handle(ctx, comparison);
+
+    self.showing = showing;
+  }
+
+  fn label(&self) -> &str {
+    self.label.as_str()
+  }
+
+  fn show(&mut self) {
+    self.showing = true;
+  }
+}
+
+impl ComparePanel {
+  pub fn new() -> ComparePanel {
+    ComparePanel {
+      label: String::from("Compare"),
+      showing: false,
+      name: String::new(),
+    }
+  }
+
+  // The split line can be dragged in the viewport, the snapshot is on its left.
+  fn split_handle(&self, ctx: &egui::Context, comparison: &mut Comparison) {
+    if comparison.active().is_none() || comparison.settings().mode != CompareMode::Split {
+      return;
+    }
+
+    let (width, height) = match comparison.live() {
+      Some(live) => (live.width, live.height),
+      None => return,
+    };
+
+    let viewport = viewport_rect(ctx.input().screen_rect(), width, height);
+    let x = viewport.left() + comparison.settings().split * viewport.width();
+
+    let response = Area::new(Id::new("compare_split"))
+      .order(Order::Background)
+      .movable(false)
+      .fixed_pos(pos2(x - HANDLE_WIDTH / 2.0, viewport.top()))
+      .show(ctx, |ui| {
+        let (rect, response) = ui.allocate_exact_size(vec2(HANDLE_WIDTH, viewport.height()), Sense::drag());
+        let painter = ui.painter();
+        let color = if response.dragged() { Color32::YELLOW } else { Color32::WHITE };
+
+        painter.vline(rect.center().x, rect.y_range(), Stroke::new(2.0, color));
+        painter.circle_filled(rect.center(), HANDLE_WIDTH / 2.0, color);
+
+        let font = FontId::proportional(16.0);
+        painter.text(rect.center_top() + vec2(-8.0, 8.0), Align2::RIGHT_TOP, "A", font.clone(), Color32::WHITE);
+        painter.text(rect.center_top() + vec2(8.0, 8.0), Align2::LEFT_TOP, "B", font, Color32::WHITE);
+
+        response
+      })
+      .inner;
+
+    if response.dragged() && viewport.width() > 0.0 {
+      let split = &mut comparison.settings_mut().split;
+      *split = (*split + response.drag_delta().x / viewport.width()).clamp(0.0, 1.0);
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,477 @@
+use std::f32::consts::TAU;
+
+use egui::{Style, epaint::Shadow, Frame, Color32, Stroke, Shape, Pos2, Vec2, Rect, Area, Order, Id, LayerId, Sense, pos2, vec2};
+
+use crate::{world::{World, Object, Light, Selection}, vector::{Vector3, Float}, quaternion::Quaternion, history::{History, Target as HistoryTarget}};
+
+use super::utils::{ShowableUI, Editor, combo, viewport_rect};
+
+// Arm length and handle size in points, the gizmo keeps its size on screen.
+const SIZE: f32 = 80.0;
+const HANDLE_SIZE: f32 = 14.0;
+
+const COLORS: [Color32; 3] = [
+  Color32::from_rgb(230, 80, 80),
+  Color32::from_rgb(80, 200, 80),
+  Color32::from_rgb(80, 130, 240),
+];
+
+const ACTIVE_COLOR: Color32 = Color32::from_rgb(255, 210, 0);
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum GizmoMode {
+  Translate,
+  Rotate,
+  Scale,
+}
+
+impl ToString for GizmoMode {
+  fn to_string(&self) -> String {
+    match *self {
+      GizmoMode::Translate => String::from("Translate"),
+      GizmoMode::Rotate => String::from("Rotate"),
+      GizmoMode::Scale => String::from("Scale"),
+    }
+  }
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+enum Handle {
+  Axis(usize),
+  // Moves in the plane of the other two axes.
+  Plane(usize),
+  Ring(usize),
+  Uniform,
+}
+
+#[derive(Clone)]
+enum Target {
+  Object(Object),
+  Light(Light),
+}
+
+// The selection as it is in the world, only cloned into a Target when a drag starts.
+#[derive(Clone, Copy)]
+enum Selected<'a> {
+  Object(&'a Object),
+  Light(&'a Light),
+}
+
+impl Selected<'_> {
+  fn to_target(self) -> Target {
+    match self {
+      Selected::Object(object) => Target::Object(object.clone()),
+      Selected::Light(light) => Target::Light(light.clone()),
+    }
+  }
+}
+
+struct Drag {
+  handle: Handle,
+  selection: Selection,
+  original: Target,
+  center: Pos2,
+  start: Pos2,
+  delta: Vec2,
+  axes: [Vec2; 3],
+  length: f32,
+}
+
+pub struct Gizmo {
+  showing: bool,
+  label: String,
+  mode: GizmoMode,
+  snapping: bool,
+  translate_step: Float,
+  rotate_step: Float,
+  scale_step: Float,
+  drag: Option<Drag>,
+}
+
+impl ShowableUI<Editor<'_>> for Gizmo {
+  fn ui(&mut self, ctx: &egui::Context, editor: Editor) {
+    let Editor { world, history } = editor;
+
+    let mode = &mut self.mode;
+    let snapping = &mut self.snapping;
+    let (translate_step, rotate_step, scale_step) = (&mut self.translate_step, &mut self.rotate_step, &mut self.scale_step);
+
+    egui::Window::new(self.label.as_str())
+      .open(&mut self.showing)
+      .frame(
+        Frame::window(&Style::default())
+          .shadow(Shadow::NONE)
+      )
+      .show(ctx, |ui| {
+        egui::Grid::new("gizmo_grid")
+          .num_columns(2)
+          .spacing([40.0, 4.0])
+          .striped(true)
+          .show(ui, |ui| {
+            ui.label("Mode");
+            combo(ui, mode, vec![
+              GizmoMode::Translate,
+              GizmoMode::Rotate,
+              GizmoMode::Scale,
+            ]);
+            ui.end_row();
+
+            ui.label("Snapping");
+            let snapping_label = if *snapping { "on" } else { "off" };
+            ui.toggle_value(snapping, snapping_label);
+            ui.end_row();
+
+            ui.label("Translate Step");
+            ui.add(egui::DragValue::new(translate_step).clamp_range(0.01..=100.0).speed(0.05));
+            ui.end_row();
+
+            ui.label("Rotate Step");
+            ui.add(egui::DragValue::new(rotate_step).clamp_range(1.0..=180.0).speed(1.0).suffix("°"));
+            ui.end_row();
+
+            ui.label("Scale Step");
+            ui.add(egui::DragValue::new(scale_step).clamp_range(0.01..=10.0).speed(0.01));
+            ui.end_row();
+          });
+      });
+
+    self.manipulate(ctx, world, history);
+  }
+
+  fn label(&self) -> &str {
+    self.label.as_str()
+  }
+
+  fn show(&mut self) {
+    self.showing = true;
+  }
+}
+
+impl Gizmo {
+  pub fn new() -> Gizmo {
+    Gizmo {
+      label: String::from("Gizmo"),
+      showing: false,
+      mode: GizmoMode::Translate,
+      snapping: false,
+      translate_step: 0.5,
+      rotate_step: 15.0,
+      scale_step: 0.1,
+      drag: None,
+    }
+  }
+
+  fn manipulate(&mut self, ctx: &egui::Context, world: &mut World, history: &mut History) {
+    let selection = world.selection();
+
+    if self.drag.as_ref().is_some_and(|drag| Some(drag.selection) != selection) {
+      self.drag = None;
+    }
+
+    let (selection, selected) = match (selection, selected(world)) {
+      (Some(selection), Some(selected)) if supports(selected, self.mode) => (selection, selected),
+      _ => return,
+    };
+
+    let origin = match selected {
+      Selected::Object(object) => *object.position(),
+      Selected::Light(Light::Point(light)) => light.position,
+      // Directional lights have no position, so their gizmo floats in front of the camera.
+      Selected::Light(_) => {
+        let camera_info = world.camera().camera_info();
+        camera_info.position + camera_info.forward.normalise() * 10.0
+      },
+    };
+
+    let camera_info = world.camera().camera_info();
+    let viewport = viewport_rect(ctx.input().screen_rect(), camera_info.viewport_width, camera_info.viewport_height);
+
+    let to_screen = |point: Vector3| world.camera().project(point)
+      .map(|(x, y)| pos2(viewport.left() + x as f32 * viewport.width(), viewport.bottom() - y as f32 * viewport.height()));
+
+    let center = match to_screen(origin) {
+      Some(center) => center,
+      None => return,
+    };
+
+    // Screen movement per world unit along each axis, linearised around the origin.
+    let epsilon = 0.001 * (origin - camera_info.position).mag().max(1.0);
+    let mut axes = [Vec2::ZERO; 3];
+
+    for (k, axis) in unit_axes().into_iter().enumerate() {
+      match to_screen(origin + axis * epsilon) {
+        Some(point) => axes[k] = (point - center) / epsilon as f32,
+        None => return,
+      }
+    }
+
+    let longest = axes.iter().map(|axis| axis.length()).fold(0.0, f32::max);
+
+    if longest <= 0.0 || !longest.is_finite() {
+      return;
+    }
+
+    let length = SIZE / longest;
+    let painter = ctx.layer_painter(LayerId::new(Order::Background, Id::new("gizmo")));
+    let active = self.drag.as_ref().map(|drag| drag.handle);
+
+    let color = |handle: Handle, k: usize| if active == Some(handle) { ACTIVE_COLOR } else { COLORS[k] };
+    let mut handles = Vec::new();
+
+    match self.mode {
+      GizmoMode::Translate | GizmoMode::Scale => {
+        for (k, axis) in axes.iter().enumerate() {
+          let end = center + *axis * length;
+          painter.line_segment([center, end], Stroke::new(2.0, color(Handle::Axis(k), k)));
+
+          if self.mode == GizmoMode::Translate {
+            painter.circle_filled(end, 5.0, color(Handle::Axis(k), k));
+          } else {
+            painter.rect_filled(Rect::from_center_size(end, vec2(9.0, 9.0)), 0.0, color(Handle::Axis(k), k));
+          }
+
+          handles.push((Handle::Axis(k), end));
+        }
+
+        if self.mode == GizmoMode::Translate {
+          for k in 0..3 {
+            let (i, j) = ((k + 1) % 3, (k + 2) % 3);
+            let corner = |u: f32, v: f32| center + (axes[i] * u + axes[j] * v) * length;
+
+            let quad = vec![corner(0.2, 0.2), corner(0.4, 0.2), corner(0.4, 0.4), corner(0.2, 0.4)];
+            let fill = color(Handle::Plane(k), k).linear_multiply(0.5);
+            painter.add(Shape::convex_polygon(quad, fill, Stroke::new(1.0, color(Handle::Plane(k), k))));
+
+            handles.push((Handle::Plane(k), corner(0.3, 0.3)));
+          }
+        } else {
+          let fill = if active == Some(Handle::Uniform) { ACTIVE_COLOR } else { Color32::WHITE };
+          painter.rect_filled(Rect::from_center_size(center, vec2(10.0, 10.0)), 0.0, fill);
+
+          handles.push((Handle::Uniform, center));
+        }
+      },
+      GizmoMode::Rotate => {
+        for k in 0..3 {
+          let (i, j) = ((k + 1) % 3, (k + 2) % 3);
+          let on_ring = |angle: f32| center + (axes[i] * angle.cos() + axes[j] * angle.sin()) * length;
+
+          let ring = (0..48).map(|n| on_ring(n as f32 / 48.0 * TAU)).collect();
+          painter.add(Shape::closed_line(ring, Stroke::new(2.0, color(Handle::Ring(k), k))));
+
+          for n in 0..4 {
+            let knob = on_ring(n as f32 / 4.0 * TAU);
+            painter.circle_filled(knob, 4.0, color(Handle::Ring(k), k));
+
+            handles.push((Handle::Ring(k), knob));
+          }
+        }
+      },
+    }
+
+    for (index, (handle, position)) in handles.into_iter().enumerate() {
+      let response = Area::new(Id::new(("gizmo_handle", index)))
+        .order(Order::Background)
+        .movable(false)
+        .fixed_pos(position - vec2(HANDLE_SIZE, HANDLE_SIZE) / 2.0)
+        .show(ctx, |ui| ui.allocate_exact_size(vec2(HANDLE_SIZE, HANDLE_SIZE), Sense::drag()).1)
+        .inner;
+
+      if response.drag_started() {
+        self.drag = Some(Drag {
+          handle,
+          selection,
+          original: selected.to_target(),
+          center,
+          start: position,
+          delta: Vec2::ZERO,
+          axes,
+          length,
+        });
+      }
+
+      if let Some(drag) = self.drag.as_mut().filter(|drag| drag.handle == handle && response.dragged()) {
+        drag.delta += response.drag_delta();
+      }
+
+      if response.drag_released() && active == Some(handle) {
+        self.drag = None;
+      }
+    }
+
+    if let Some(drag) = &self.drag {
+      let edited = match drag.selection {
+        Selection::Object(index) => HistoryTarget::Object(index),
+        Selection::Light(index) => HistoryTarget::Light(index),
+        Selection::Camera => return,
+      };
+
+      history.change(world, &[edited], |world| self.apply(drag, world));
+    }
+  }
+
+  // Works from the state at the start of the drag so snapping doesn't accumulate rounding.
+  fn apply(&self, drag: &Drag, world: &mut World) {
+    let mut target = drag.original.clone();
+    let axes = unit_axes();
+
+    match (self.mode, drag.handle) {
+      (GizmoMode::Translate, Handle::Axis(k)) => {
+        let distance = self.snap(along(drag.delta, drag.axes[k]), self.translate_step);
+        translate(&mut target, axes[k] * distance);
+      },
+      (GizmoMode::Translate, Handle::Plane(k)) => {
+        let (i, j) = ((k + 1) % 3, (k + 2) % 3);
+
+        if let Some((u, v)) = solve(drag.delta, drag.axes[i], drag.axes[j]) {
+          let offset = axes[i] * self.snap(u, self.translate_step) + axes[j] * self.snap(v, self.translate_step);
+          translate(&mut target, offset);
+        }
+      },
+      (GizmoMode::Rotate, Handle::Ring(k)) => {
+        let (from, to) = (drag.start - drag.center, drag.start + drag.delta - drag.center);
+        let screen_angle = cross(from, to).atan2(from.dot(to)) as Float;
+
+        // Which way a positive rotation turns on screen depends on which side of the ring we see.
+        let (i, j) = ((k + 1) % 3, (k + 2) % 3);
+        let sign = cross(drag.axes[i], drag.axes[j]).signum() as Float;
+
+        let angle = self.snap(screen_angle.to_degrees() * sign, self.rotate_step).to_radians();
+        rotate(&mut target, Quaternion::from_angle_axis(angle, axes[k]));
+      },
+      (GizmoMode::Scale, Handle::Axis(k)) => {
+        let factor = 1.0 + along(drag.delta, drag.axes[k]) / drag.length as Float;
+        scale(&mut target, Some(k), self.snap(factor, self.scale_step).max(0.01));
+      },
+      (GizmoMode::Scale, Handle::Uniform) => {
+        let factor = 1.0 + (drag.delta.x - drag.delta.y) as Float / SIZE as Float;
+        scale(&mut target, None, self.snap(factor, self.scale_step).max(0.01));
+      },
+      _ => return,
+    }
+
+    match (drag.selection, target) {
+      (Selection::Object(index), Target::Object(object)) => if let Some(existing) = world.objects_mut().get_mut(index) {
+        *existing = object;
+      },
+      (Selection::Light(index), Target::Light(light)) => if let Some(existing) = world.lights_mut().get_mut(index) {
+        *existing = light;
+      },
+      _ => (),
+    }
+  }
+
+  fn snap(&self, value: Float, step: Float) -> Float {
+    if self.snapping && step > 0.0 {
+      (value / step).round() * step
+    } else {
+      value
+    }
+  }
+}
+
+fn selected(world: &World) -> Option<Selected<'_>> {
+  match world.selection()? {
+    Selection::Object(index) => world.objects().get(index).filter(|object| !object.node().locked).map(Selected::Object),
+    Selection::Light(index) => world.lights().get(index).filter(|light| !light.node().locked).map(Selected::Light),
+    Selection::Camera => None,
+  }
+}
+
+fn supports(selected: Selected, mode: GizmoMode) -> bool {
+  matches!(
+    (mode, selected),
+    (GizmoMode::Translate, Selected::Object(_) | Selected::Light(Light::Point(_)))
+      | (GizmoMode::Rotate, Selected::Object(Object::Mesh(_)) | Selected::Light(Light::Directional(_)))
+      | (GizmoMode::Scale, Selected::Object(_))
+  )
+}
+
+fn translate(target: &mut Target, offset: Vector3) {
+  match target {
+    Target::Object(object) => *object.position_mut() = *object.position() + offset,
+    Target::Light(Light::Point(light)) => light.position = light.position + offset,
+    Target::Light(_) => (),
+  }
+}
+
+fn rotate(target: &mut Target, rotation: Quaternion) {
+  match target {
+    Target::Object(Object::Mesh(mesh)) => {
+      for vertex in mesh.triangles.iter_mut().flat_map(|triangle| triangle.iter_mut()) {
+        *vertex = rotation.rotate(*vertex);
+      }
+
+      mesh.update_bounds();
+    },
+    Target::Light(Light::Directional(light)) => light.direction = rotation.rotate(light.direction),
+    _ => (),
+  }
+}
+
+// Spheres only scale uniformly, planes only along their width (x) and height (z).
+fn scale(target: &mut Target, axis: Option<usize>, factor: Float) {
+  let object = match target {
+    Target::Object(object) => object,
+    Target::Light(_) => return,
+  };
+
+  match object {
+    Object::Sphere(sphere) => sphere.radius *= factor,
+    Object::Plane(plane) => {
+      if matches!(axis, None | Some(0)) {
+        plane.width *= factor;
+      }
+
+      if matches!(axis, None | Some(2)) {
+        plane.height *= factor;
+      }
+    },
+    Object::Mesh(mesh) => {
+      let scale = match axis {
+        Some(k) => unit_axes()[k] * (factor - 1.0) + Vector3::new(1.0, 1.0, 1.0),
+        None => Vector3::new(factor, factor, factor),
+      };
+
+      for vertex in mesh.triangles.iter_mut().flat_map(|triangle| triangle.iter_mut()) {
+        *vertex = *vertex * scale;
+      }
+
+      mesh.update_bounds();
+    },
+  }
+}
+
+fn unit_axes() -> [Vector3; 3] {
+  [
+    Vector3::new(1.0, 0.0, 0.0),
+    Vector3::new(0.0, 1.0, 0.0),
+    Vector3::new(0.0, 0.0, 1.0),
+  ]
+}
+
+// World units moved along an axis whose unit length is the given screen vector.
+fn along(delta: Vec2, axis: Vec2) -> Float {
+  let length = axis.length_sq();
+
+  if length > 0.0 {
+    (delta.dot(axis) / length) as Float
+  } else {
+    0.0
+  }
+}
+
+// Splits a screen movement into world units along two axes.
+fn solve(delta: Vec2, a: Vec2, b: Vec2) -> Option<(Float, Float)> {
+  let determinant = cross(a, b);
+
+  if determinant.abs() < 1.0 {
+    return None;
+  }
+
+  Some(((cross(delta, b) / determinant) as Float, (cross(a, delta) / determinant) as Float))
+}
+
+fn cross(a: Vec2, b: Vec2) -> f32 {
+  a.x * b.y - a.y * b.x
+}
\ No newline at end of file
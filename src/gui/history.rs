@@ -0,0 +1,110 @@
+use egui::{Style, epaint::Shadow, Frame, RichText};
+
+use crate::{world::World, history::History};
+
+use super::utils::ShowableUI;
+
+pub struct HistoryPanel {
+  showing: bool,
+  label: String,
+  history: History,
+}
+
+impl ShowableUI<&mut World> for HistoryPanel {
+  fn ui(&mut self, ctx: &egui::Context, world: &mut World) {
+    let mut jump = None;
+    let history = &self.history;
+
+    egui::Window::new(self.label.as_str())
+      .open(&mut self.showing)
+      .frame(
+        Frame::window(&Style::default())
+          .shadow(Shadow::NONE)
+      )
+      .show(ctx, |ui| {
+        let applied = history.undo_steps().len();
+
+        ui.horizontal(|ui| {
+          if ui.add_enabled(history.can_undo(), egui::Button::new("Undo")).clicked() {
+            jump = Some(applied - 1);
+          }
+
+          if ui.add_enabled(history.can_redo(), egui::Button::new("Redo")).clicked() {
+            jump = Some(applied + 1);
+          }
+        });
+
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+          .max_height(300.0)
+          .show(ui, |ui| {
+            if ui.selectable_label(applied == 0, "Start").clicked() {
+              jump = Some(0);
+            }
+
+            for (index, step) in history.undo_steps().iter().enumerate() {
+              if ui.selectable_label(applied == index + 1, step.label.as_str()).clicked() {
+                jump = Some(index + 1);
+              }
+            }
+
+            // Undone steps stay listed until a new edit replaces them.
+            for (index, step) in history.redo_steps().iter().rev().enumerate() {
+              if ui.selectable_label(false, RichText::new(step.label.as_str()).weak()).clicked() {
+                jump = Some(applied + index + 1);
+              }
+            }
+          });
+      });
+
+    if let Some(steps) = jump {
+      self.history.jump(world, steps);
+    }
+
+    // Edits made while dragging or typing into a field are merged into one step.
+    self.history.update(world, ctx.is_using_pointer() || ctx.wants_keyboard_input());
+  }
+
+  fn label(&self) -> &str {
+    self.label.as_str()
+  }
+
+  fn show(&mut self) {
+    self.showing = true;
+  }
+}
+
+impl HistoryPanel {
+  pub fn new(world: &World) -> HistoryPanel {
+    HistoryPanel {
+      label: String::from("History"),
+      showing: false,
+      history: History::new(world),
+    }
+  }
+
+  pub fn undo(&mut self, world: &mut World) {
+    self.history.undo(world);
+  }
+
+  pub fn redo(&mut self, world: &mut World) {
+    self.history.redo(world);
+  }
+
+  pub fn can_undo(&self) -> bool {
+    self.history.can_undo()
+  }
+
+  pub fn can_redo(&self) -> bool {
+    self.history.can_redo()
+  }
+
+  pub fn history_mut(&mut self) -> &mut History {
+    &mut self.history
+  }
+
+  pub fn replaced(&mut self, world: &World, before: World, label: &str) {
+    self.history.replaced(world, before, label);
+  }
+}
\ No newline at end of file
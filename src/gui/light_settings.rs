@@ -2,18 +2,30 @@ use std::borrow::BorrowMut;
 
 use egui::{Style, epaint::Shadow, Frame};
 
-use crate::world::{World, Light, PointLight, DirectionalLight, AmbientLight};
+use crate::{world::{Light, PointLight, DirectionalLight, AmbientLight, Selection}, history::{Target, Command}};
 
-use super::utils::{ShowableUI, combo};
+use super::utils::{ShowableUI, Editor, combo};
 
 pub struct LightSettings {
   showing: bool,
   light: usize,
+  selection: Option<Selection>,
   label: String,
 }
 
-impl ShowableUI<&mut World> for LightSettings {
-  fn ui(&mut self, ctx: &egui::Context, world: &mut World) {
+impl ShowableUI<Editor<'_>> for LightSettings {
+  fn ui(&mut self, ctx: &egui::Context, editor: Editor) {
+    let Editor { world, history } = editor;
+
+    if world.selection() != self.selection {
+      self.selection = world.selection();
+
+      if let Some(Selection::Light(light)) = self.selection {
+        self.light = light;
+        self.showing = true;
+      }
+    }
+
     egui::Window::new(self.label())
       .open(&mut self.showing)
       .frame(
@@ -26,77 +38,95 @@ impl ShowableUI<&mut World> for LightSettings {
           .spacing([40.0, 4.0])
           .striped(true)
           .show(ui, |ui| {
-            let lights = world.lights_mut();
-
-            if self.light >= lights.len() {
+            if self.light >= world.lights().len() {
               self.light = 0;
             }
 
+            history.watch(world, Target::Light(self.light));
+
+            let lights = world.lights_mut();
+            let mut changed = false;
+
             if lights.len() > 0 {
               ui.label("Selected Light");
 
+              let light = self.light;
+
               combo(ui, &mut self.light, 
                 (0..lights.len()).collect::<Vec<usize>>()
               );
 
+              if self.light != light {
+                self.selection = Some(Selection::Light(self.light));
+              }
+
               ui.end_row(); 
 
               match lights[self.light].borrow_mut() {
                 Light::Ambient(light) => {
                   ui.label("Color");
-                  ui.color_edit_button_rgb(&mut light.color);
+                  changed |= ui.color_edit_button_rgb(&mut light.color).changed();
                   ui.end_row(); 
                 },
                 Light::Directional(light) => {
                   ui.label("Color");
-                  ui.color_edit_button_rgb(&mut light.color);
+                  changed |= ui.color_edit_button_rgb(&mut light.color).changed();
                   ui.end_row(); 
                   ui.label("Direction");
                   ui.horizontal(|ui| {
-                    ui.add(egui::DragValue::new(light.direction.x_mut()));
-                    ui.add(egui::DragValue::new(light.direction.y_mut()));
-                    ui.add(egui::DragValue::new(light.direction.z_mut()));
+                    changed |= ui.add(egui::DragValue::new(light.direction.x_mut())).changed();
+                    changed |= ui.add(egui::DragValue::new(light.direction.y_mut())).changed();
+                    changed |= ui.add(egui::DragValue::new(light.direction.z_mut())).changed();
                   });
                   ui.end_row();
                 },
                 Light::Point(light) => {
                   ui.label("Color");
-                  ui.color_edit_button_rgb(&mut light.color);
+                  changed |= ui.color_edit_button_rgb(&mut light.color).changed();
 
                   ui.end_row();
 
                   ui.label("Position");
                   ui.horizontal(|ui| {
-                    ui.add(egui::DragValue::new(light.position.x_mut()));
-                    ui.add(egui::DragValue::new(light.position.y_mut()));
-                    ui.add(egui::DragValue::new(light.position.z_mut()));
+                    changed |= ui.add(egui::DragValue::new(light.position.x_mut())).changed();
+                    changed |= ui.add(egui::DragValue::new(light.position.y_mut())).changed();
+                    changed |= ui.add(egui::DragValue::new(light.position.z_mut())).changed();
                   });
                   ui.end_row();
                 }
               }
 
+              if changed {
+                history.edited(Target::Light(self.light));
+              }
+
               ui.label("Remove Light");
               if ui.button("Remove").clicked() {
-                world.lights_mut().remove(self.light);
+                history.execute(world, Command::RemoveLight(self.light));
+                self.selection = world.selection();
               }
               ui.end_row(); 
             }
 
+            if world.selection() != self.selection {
+              world.select(self.selection);
+            }
+
             ui.label("Add Directional");
             if ui.button("Add").clicked() {
-              world.lights_mut().push(Light::Directional(DirectionalLight::new()));
+              history.execute(world, Command::AddLight(Light::Directional(DirectionalLight::new())));
             }
             ui.end_row(); 
 
             ui.label("Add Point");
             if ui.button("Add").clicked() {
-              world.lights_mut().push(Light::Point(PointLight::new()));
+              history.execute(world, Command::AddLight(Light::Point(PointLight::new())));
             }
             ui.end_row(); 
 
             ui.label("Add Ambient");
             if ui.button("Add").clicked() {
-              world.lights_mut().push(Light::Ambient(AmbientLight::new()));
+              history.execute(world, Command::AddLight(Light::Ambient(AmbientLight::new())));
             }
             ui.end_row(); 
           })
@@ -117,6 +147,7 @@ impl LightSettings {
     LightSettings {
       label: String::from("Light Settings"),
       light: 0,
+      selection: None,
       showing: false,
     }
   }
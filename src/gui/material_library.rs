@@ -0,0 +1,235 @@
+use egui::{Style, epaint::Shadow, Frame, ColorImage, TextureHandle, TextureOptions, Vec2};
+
+use crate::{world::{World, Material, Selection}, renderer::Renderer, history::Target};
+
+use super::utils::{ShowableUI, Editor, material_rows};
+
+const PREVIEW_SIZE: u32 = 96;
+
+pub struct MaterialLibrary {
+  showing: bool,
+  label: String,
+  selected: Option<String>,
+  name: String,
+  preview: Option<(Material, TextureHandle)>,
+}
+
+impl ShowableUI<Editor<'_>> for MaterialLibrary {
+  fn ui(&mut self, ctx: &egui::Context, editor: Editor) {
+    let Editor { world, history } = editor;
+
+    if self.selected.as_ref().is_none_or(|name| !world.materials().contains_key(name)) {
+      let first = world.materials().keys().next().cloned();
+      self.select(first);
+    }
+
+    let material = self.selected.as_ref().and_then(|name| world.materials().get(name)).copied();
+
+    if let Some(material) = material.filter(|_| self.showing) {
+      self.update_preview(ctx, material);
+    }
+
+    let mut showing = self.showing;
+    let mut edited = false;
+
+    egui::Window::new(self.label.as_str())
+      .open(&mut showing)
+      .frame(
+        Frame::window(&Style::default())
+          .shadow(Shadow::NONE)
+      )
+      .show(ctx, |ui| {
+        history.watch(world, Target::Materials);
+
+        egui::Grid::new("material_library_grid")
+          .num_columns(2)
+          .spacing([40.0, 4.0])
+          .striped(true)
+          .show(ui, |ui| {
+            ui.label("Material");
+            let mut selected = self.selected.clone();
+
+            egui::ComboBox::from_id_source("material_library_combo")
+              .selected_text(selected.as_deref().unwrap_or("None"))
+              .show_ui(ui, |ui| {
+                for name in world.materials().keys() {
+                  ui.selectable_value(&mut selected, Some(name.clone()), name);
+                }
+              });
+
+            if selected != self.selected {
+              self.select(selected);
+            }
+            ui.end_row();
+
+            ui.label("Add");
+            ui.horizontal(|ui| {
+              if ui.button("New").clicked() {
+                self.add(world, "material", Material::new());
+                history.edited(Target::Materials);
+              }
+
+              ui.menu_button("Preset", |ui| {
+                for preset in Material::PRESETS {
+                  if ui.button(preset).clicked() {
+                    ui.close_menu();
+                    self.add(world, preset, Material::preset(preset).unwrap_or_default());
+                    history.edited(Target::Materials);
+                  }
+                }
+              });
+            });
+            ui.end_row();
+
+            let name = match self.selected.clone() {
+              Some(name) if world.materials().contains_key(&name) => name,
+              _ => return,
+            };
+
+            ui.label("Name");
+            ui.horizontal(|ui| {
+              ui.add(egui::TextEdit::singleline(&mut self.name).desired_width(120.0));
+
+              let renamed = self.name.trim();
+              let valid = !renamed.is_empty() && !world.materials().contains_key(renamed);
+
+              if ui.add_enabled(valid, egui::Button::new("Rename")).clicked() {
+                let renamed = renamed.to_string();
+                world.rename_material(&name, &renamed);
+                history.edited(Target::Materials);
+                self.select(Some(renamed));
+              }
+            });
+            ui.end_row();
+
+            // The rest waits a frame after a rename.
+            if self.selected.as_ref() != Some(&name) {
+              return;
+            }
+
+            ui.label("Preview");
+            if let Some((_, texture)) = &self.preview {
+              ui.image(texture.id(), Vec2::splat(PREVIEW_SIZE as f32));
+            }
+            ui.end_row();
+
+            if let Some(material) = world.materials_mut().get_mut(&name) {
+              let before = *material;
+              material_rows(ui, material);
+              edited = *material != before;
+            }
+
+            if edited {
+              history.edited(Target::Materials);
+            }
+
+            let users = world.objects().iter().filter(|object| object.material_name() == Some(name.as_str())).count();
+
+            ui.label("Used By");
+            ui.label(format!("{} objects", users));
+            ui.end_row();
+
+            ui.label("Selected Object");
+            ui.horizontal(|ui| {
+              let index = match world.selection() {
+                Some(Selection::Object(index)) if index < world.objects().len() => Some(index),
+                _ => None,
+              };
+
+              if let Some(index) = index {
+                history.watch(world, Target::Object(index));
+              }
+
+              ui.add_enabled_ui(index.is_some(), |ui| {
+                let assigned = ui.button("Assign").clicked();
+                let detached = ui.button("Detach").clicked();
+
+                if let Some(index) = index {
+                  let object = &mut world.objects_mut()[index];
+
+                  if assigned {
+                    object.set_material_name(Some(name.clone()));
+                    history.edited(Target::Object(index));
+                    edited = true;
+                  } else if detached && object.material_name() == Some(name.as_str()) {
+                    object.set_material_name(None);
+                    history.edited(Target::Object(index));
+                  }
+                }
+              });
+            });
+            ui.end_row();
+
+            ui.label("Remove Material");
+            if ui.button("Remove").clicked() {
+              world.remove_material(&name);
+              history.edited(Target::Materials);
+            }
+            ui.end_row();
+          });
+      });
+
+    // Objects have to follow before the object settings see them differ from the library.
+    if edited {
+      world.apply_materials();
+    }
+
+    self.showing = showing;
+  }
+
+  fn label(&self) -> &str {
+    self.label.as_str()
+  }
+
+  fn show(&mut self) {
+    self.showing = true;
+  }
+}
+
+impl MaterialLibrary {
+  pub fn new() -> MaterialLibrary {
+    MaterialLibrary {
+      label: String::from("Materials"),
+      showing: false,
+      selected: None,
+      name: String::new(),
+      preview: None,
+    }
+  }
+
+  fn select(&mut self, name: Option<String>) {
+    self.name = name.clone().unwrap_or_default();
+    self.selected = name;
+  }
+
+  // Names are made unique with a number, so adding "glass" twice gives "glass 2".
+  fn add(&mut self, world: &mut World, name: &str, material: Material) {
+    let mut unique = String::from(name);
+    let mut number = 2;
+
+    while world.materials().contains_key(&unique) {
+      unique = format!("{} {}", name, number);
+      number += 1;
+    }
+
+    world.materials_mut().insert(unique.clone(), material);
+    self.select(Some(unique));
+  }
+
+  fn update_preview(&mut self, ctx: &egui::Context, material: Material) {
+    if matches!(&self.preview, Some((rendered, _)) if *rendered == material) {
+      return;
+    }
+
+    let image = Renderer::material_preview(&material, PREVIEW_SIZE);
+    let pixels: Vec<u8> = image.pixels.iter().flat_map(|pixel| *pixel).collect();
+
+    let texture = ctx.load_texture(
+      "material_preview",
+      ColorImage::from_rgb([image.width as usize, image.height as usize], &pixels),
+      TextureOptions::LINEAR,
+    );
+
+    self.preview = Some((material, texture));
+  }
+}
\ No newline at end of file
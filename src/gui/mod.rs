@@ -2,58 +2,203 @@ pub mod utils;
 pub mod camera_settings;
 pub mod light_settings;
 pub mod object_settings;
+pub mod problems;
+pub mod timeline;
+pub mod camera_path;
+pub mod gizmo;
+pub mod history;
+pub mod outliner;
+pub mod material_library;
+pub mod stats;
+pub mod comparison;
+pub mod bindings;
 
-use std::{time::Instant};
+use std::{time::Instant, path::Path};
 
 use egui::{Context};
+use glium::glutin::event::VirtualKeyCode;
 
-use crate::{world::{World}};
+use crate::{world::{World}, event_manager::EventManager, stats::RenderStats, graphics::Graphics, bindings::{Bindings, Binding, Action, BINDINGS_FILE}};
 
-use self::{utils::{ShowableUI}, camera_settings::CameraSettings, object_settings::ObjectSettings, light_settings::LightSettings};
+use self::{utils::{ShowableUI, Editor}, camera_settings::CameraSettings, object_settings::ObjectSettings, light_settings::LightSettings, problems::Problems, timeline::Timeline, camera_path::CameraRecorder, gizmo::Gizmo, history::HistoryPanel, outliner::Outliner, material_library::MaterialLibrary, stats::StatsPanel, comparison::ComparePanel, bindings::BindingsPanel};
 
 pub struct GUI {
-  windows: Vec<Box<dyn for<'a> ShowableUI<&'a mut World>>>,
+  windows: Vec<Box<dyn for<'a> ShowableUI<Editor<'a>>>>,
+  history: HistoryPanel,
+  stats: StatsPanel,
+  compare: ComparePanel,
+  bindings: BindingsPanel,
+  keyboard_focus: bool,
   instant: Instant,
+  error: Option<String>,
 }
 
 impl GUI {
-  pub fn new() -> GUI {
+  pub fn new(world: &World) -> GUI {
     GUI {
       windows: vec![
         Box::new(CameraSettings::new()),
         Box::new(ObjectSettings::new()),
         Box::new(LightSettings::new()),
+        Box::new(Problems::new()),
+        Box::new(Timeline::new()),
+        Box::new(CameraRecorder::new()),
+        Box::new(Gizmo::new()),
+        Box::new(Outliner::new()),
+        Box::new(MaterialLibrary::new()),
       ],
+      history: HistoryPanel::new(world),
+      stats: StatsPanel::new(),
+      compare: ComparePanel::new(),
+      bindings: BindingsPanel::new(),
+      keyboard_focus: false,
       instant: Instant::now(),
+      error: None,
     }
   }
 
-  pub fn ui(&mut self, ctx: &Context, world: &mut World) {
-    self.menu_bar(ctx, world);
-    self.windows(ctx, world);
+  // Called once at start, a file that can't be read keeps the defaults and says why.
+  pub fn load_bindings(&mut self, bindings: &mut Bindings) {
+    if !Path::new(BINDINGS_FILE).exists() {
+      return;
+    }
+
+    match Bindings::load(Path::new(BINDINGS_FILE)) {
+      Ok(loaded) => *bindings = loaded,
+      Err(err) => self.error = Some(format!("{}: {}", BINDINGS_FILE, err)),
+    }
+  }
+
+  pub fn ui(&mut self, ctx: &Context, graphics: &mut Graphics) {
+    self.menu_bar(ctx, graphics);
+    self.windows(ctx, graphics.world());
+    self.stats.ui(ctx, ());
+    self.compare.ui(ctx, graphics.comparison_mut());
+    self.bindings.ui(ctx, graphics.bindings_mut());
+    self.error_dialog(ctx);
+
+    // The history window goes last so it sees every edit made this frame.
+    self.history.ui(ctx, graphics.world());
+    self.keyboard_focus = ctx.wants_keyboard_input();
   }
 
-  fn menu_bar(&mut self, ctx: &Context, world: &mut World) {
+  pub fn update_stats(&mut self, stats: RenderStats) {
+    self.stats.update(stats);
+  }
+
+  // Text fields keep their keys, like Ctrl+Z for their own undo, while they have focus.
+  pub fn update(&mut self, event_manager: &EventManager, graphics: &mut Graphics) {
+    if self.keyboard_focus {
+      return;
+    }
+
+    let modifiers = event_manager.modifiers();
+    let controls = graphics.bindings().controls();
+    let frame_selected = controls.is_pressed(Action::FrameSelected, event_manager);
+    let frame_all = controls.is_pressed(Action::FrameAll, event_manager);
+    let world = graphics.world();
+
+    match event_manager.key_pressed() {
+      Some(VirtualKeyCode::Z) if modifiers.ctrl() => {
+        if modifiers.shift() {
+          self.history.redo(world);
+        } else {
+          self.history.undo(world);
+        }
+      },
+      _ if frame_selected && modifiers.is_empty() => {
+        world.frame_selected();
+      },
+      _ if frame_all && modifiers.is_empty() => {
+        world.frame_all();
+      },
+      _ => (),
+    }
+  }
+
+  fn menu_bar(&mut self, ctx: &Context, graphics: &mut Graphics) {
+    let controls = graphics.bindings().controls();
+    let frame_selected = Binding::list_to_string(controls.bindings(Action::FrameSelected));
+    let frame_all = Binding::list_to_string(controls.bindings(Action::FrameAll));
+    let world = graphics.world();
+
     let fps = 1.0 / self.instant.elapsed().as_secs_f64();
     self.instant = Instant::now();
 
     egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
       egui::menu::bar(ui, |ui| {
         ui.menu_button("File", |ui| {
-          if ui.button("Open").clicked() {
-            world.open_world();
+          let result = if ui.button("Open").clicked() {
+            ui.close_menu();
+            let before = world.clone();
+            world.open_world().map(|_| self.history.replaced(world, before, "Open Scene"))
           } else if ui.button("Save").clicked() {
-            world.save_world();
+            ui.close_menu();
+            world.save_world()
+          } else if ui.button("Import glTF").clicked() {
+            ui.close_menu();
+            let before = world.clone();
+            world.import_gltf().map(|_| self.history.replaced(world, before, "Import glTF"))
+          } else {
+            Ok(())
+          };
+
+          if let Err(err) = result {
+            self.error = Some(err.to_string());
+          }
+        });
+
+        ui.menu_button("Edit", |ui| {
+          if ui.add_enabled(self.history.can_undo(), egui::Button::new("Undo").shortcut_text("Ctrl+Z")).clicked() {
+            ui.close_menu();
+            self.history.undo(world);
+          }
+
+          if ui.add_enabled(self.history.can_redo(), egui::Button::new("Redo").shortcut_text("Ctrl+Shift+Z")).clicked() {
+            ui.close_menu();
+            self.history.redo(world);
           }
         });
 
         ui.menu_button("View", |ui| {
+          if ui.add_enabled(world.selection_bounds().is_some(), egui::Button::new("Frame Selected").shortcut_text(frame_selected.as_str())).clicked() {
+            ui.close_menu();
+            world.frame_selected();
+          }
+
+          if ui.add_enabled(world.scene_bounds().is_some(), egui::Button::new("Frame All").shortcut_text(frame_all.as_str())).clicked() {
+            ui.close_menu();
+            world.frame_all();
+          }
+
+          ui.separator();
+
           for window in &mut self.windows {
             if ui.button(window.label()).clicked() {
               window.show();
               ui.close_menu();
             }
           }
+
+          if ui.button(self.history.label()).clicked() {
+            self.history.show();
+            ui.close_menu();
+          }
+
+          if ui.button(self.stats.label()).clicked() {
+            self.stats.show();
+            ui.close_menu();
+          }
+
+          if ui.button(self.compare.label()).clicked() {
+            self.compare.show();
+            ui.close_menu();
+          }
+
+          if ui.button(self.bindings.label()).clicked() {
+            self.bindings.show();
+            ui.close_menu();
+          }
         });
 
         ui.label(format!("fps: {}", fps.round()));
@@ -61,9 +206,28 @@ impl GUI {
     });
   }
 
+  fn error_dialog(&mut self, ctx: &Context) {
+    let mut dismissed = false;
+
+    if let Some(error) = &self.error {
+      egui::Window::new("Error")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+          ui.label(error);
+          dismissed = ui.button("OK").clicked();
+        });
+    }
+
+    if dismissed {
+      self.error = None;
+    }
+  }
+
   fn windows(&mut self, ctx: &Context, world: &mut World) {
     for window in &mut self.windows {
-      window.ui(ctx, world);
+      window.ui(ctx, Editor { world: &mut *world, history: self.history.history_mut() });
     }
   }
 }
\ No newline at end of file
@@ -1,17 +1,29 @@
 use egui::{Style, epaint::Shadow, Frame};
 
-use crate::world::{World, Object, Plane, Sphere};
+use crate::{world::{Object, Plane, Sphere, Selection}, history::{Target, Command}};
 
-use super::utils::{ShowableUI, combo};
+use super::utils::{ShowableUI, Editor, combo, material_rows};
 
 pub struct ObjectSettings {
   showing: bool,
   object: usize,
+  selection: Option<Selection>,
   label: String,
 }
 
-impl ShowableUI<&mut World> for ObjectSettings {
-  fn ui(&mut self, ctx: &egui::Context, world: &mut World) {
+impl ShowableUI<Editor<'_>> for ObjectSettings {
+  fn ui(&mut self, ctx: &egui::Context, editor: Editor) {
+    let Editor { world, history } = editor;
+
+    if world.selection() != self.selection {
+      self.selection = world.selection();
+
+      if let Some(Selection::Object(object)) = self.selection {
+        self.object = object;
+        self.showing = true;
+      }
+    }
+
     egui::Window::new(self.label())
       .open(&mut self.showing)
       .frame(
@@ -24,139 +36,116 @@ impl ShowableUI<&mut World> for ObjectSettings {
           .spacing([40.0, 4.0])
           .striped(true)
           .show(ui, |ui| {
-            let objects = world.objects_mut();
-
-            if self.object >= objects.len() {
+            if self.object >= world.objects().len() {
               self.object = 0;
             }
 
+            history.watch(world, Target::Object(self.object));
+
+            let objects = world.objects_mut();
+            let mut changed = false;
+
             if objects.len() > 0 {
               ui.label("Selected Object");
 
+              let object = self.object;
+
               combo(ui, &mut self.object, 
                 (0..objects.len()).collect::<Vec<usize>>()
               );
 
+              if self.object != object {
+                self.selection = Some(Selection::Object(self.object));
+              }
+
               ui.end_row(); 
 
               let material = match &mut objects[self.object] {
                 Object::Sphere(sphere) => {
                   ui.label("Radius");
-                  ui.add(egui::Slider::new(&mut sphere.radius, 0_f64..=500_f64).step_by(0.1));
+                  changed |= ui.add(egui::Slider::new(&mut sphere.radius, 0.0..=500.0).step_by(0.1)).changed();
                   ui.end_row(); 
                   ui.label("Position");
                   ui.horizontal(|ui| {
-                    ui.add(egui::DragValue::new(sphere.position.x_mut()));
-                    ui.add(egui::DragValue::new(sphere.position.y_mut()));
-                    ui.add(egui::DragValue::new(sphere.position.z_mut()));
+                    changed |= ui.add(egui::DragValue::new(sphere.position.x_mut())).changed();
+                    changed |= ui.add(egui::DragValue::new(sphere.position.y_mut())).changed();
+                    changed |= ui.add(egui::DragValue::new(sphere.position.z_mut())).changed();
                   });
 
                   &mut sphere.material
                 },
                 Object::Plane(plane) => {
                   ui.label("Height");
-                  ui.add(egui::Slider::new(&mut plane.height, 0_f64..=500_f64).step_by(0.1));
+                  changed |= ui.add(egui::Slider::new(&mut plane.height, 0.0..=500.0).step_by(0.1)).changed();
                   ui.end_row(); 
                   ui.label("Width");
-                  ui.add(egui::Slider::new(&mut plane.width, 0_f64..=500_f64).step_by(0.1));
+                  changed |= ui.add(egui::Slider::new(&mut plane.width, 0.0..=500.0).step_by(0.1)).changed();
                   ui.end_row(); 
                   ui.label("Position");
                   ui.horizontal(|ui| {
-                    ui.add(egui::DragValue::new(plane.position.x_mut()));
-                    ui.add(egui::DragValue::new(plane.position.y_mut()));
-                    ui.add(egui::DragValue::new(plane.position.z_mut()));
+                    changed |= ui.add(egui::DragValue::new(plane.position.x_mut())).changed();
+                    changed |= ui.add(egui::DragValue::new(plane.position.y_mut())).changed();
+                    changed |= ui.add(egui::DragValue::new(plane.position.z_mut())).changed();
                   });
 
                   &mut plane.material
                 },
-              };
-
-              ui.end_row();
-
-              ui.label("Color");
-              ui.color_edit_button_rgb(&mut material.color);
-
-              ui.end_row();
-              ui.label("Reflectivity");
-              ui.add(
-                egui::DragValue::new(&mut material.reflectivity)
-                  .clamp_range(0..=1)
-                  .speed(0.05)
-              );
-
-              ui.end_row();
-              ui.label("Refracive Index");
-              ui.add(
-                egui::DragValue::new(&mut material.refractive_index)
-                  .clamp_range(0..=3)
-                  .speed(0.05)
-              );
-
-              ui.end_row();
-              ui.label("Transparency");
-              ui.add(
-                egui::DragValue::new(&mut material.transparency)
-                  .clamp_range(0..=1)
-                  .speed(0.05)
-              );
-
-              ui.end_row();
-              ui.label("Ambient Reflection");
-              ui.add(
-                egui::DragValue::new(&mut material.ambient_reflection)
-                  .clamp_range(0..=100)
-                  .speed(0.05)
-              );
+                Object::Mesh(mesh) => {
+                  ui.label("Triangles");
+                  ui.label(mesh.triangles.len().to_string());
+                  ui.end_row(); 
+                  ui.label("Position");
+                  ui.horizontal(|ui| {
+                    changed |= ui.add(egui::DragValue::new(mesh.position.x_mut())).changed();
+                    changed |= ui.add(egui::DragValue::new(mesh.position.y_mut())).changed();
+                    changed |= ui.add(egui::DragValue::new(mesh.position.z_mut())).changed();
+                  });
 
-              ui.end_row();
-              ui.label("Diffuse Reflection");
-              ui.add(
-                egui::DragValue::new(&mut material.diffuse_reflection)
-                  .clamp_range(0..=100)
-                  .speed(0.05)
-              );
+                  &mut mesh.material
+                },
+              };
 
-              ui.end_row();
-              ui.label("Specular Reflection");
-              let specular_label = if material.has_specular { "on" } else {"off"};
-              ui.toggle_value(&mut material.has_specular, specular_label);
               ui.end_row();
 
-              if material.has_specular {
-                ui.label("Specular Amount");
-                ui.add(
-                  egui::DragValue::new(&mut material.specular_reflection)
-                    .clamp_range(0..=100)
-                    .speed(1.0)
-                );
-              } 
+              let before = *material;
+              material_rows(ui, material);
+              changed |= *material != before;
 
-              ui.end_row(); 
+              if changed {
+                history.edited(Target::Object(self.object));
+              }
 
               ui.label("Remove Object");
               if ui.button("Remove").clicked() {
-                objects.remove(self.object);
+                history.execute(world, Command::RemoveObject(self.object));
+                self.selection = world.selection();
               }
               ui.end_row(); 
             }
 
+            if world.selection() != self.selection {
+              world.select(self.selection);
+            }
+
             ui.label("Add Sphere");
             if ui.button("Add").clicked() {
-              world.objects_mut().push(
+              history.execute(world, Command::AddObject(
                 Object::Sphere(Sphere::new())
-              );
+              ));
             }
             ui.end_row(); 
 
             ui.label("Add Plane");
             if ui.button("Add").clicked() {
-              world.objects_mut().push(
+              history.execute(world, Command::AddObject(
                 Object::Plane(Plane::new())
-              );
+              ));
             }
             ui.end_row();
           })
       });
+
+    world.detach_edited_materials();
   }
 
   fn label(&self) -> &str {
@@ -173,6 +162,7 @@ impl ObjectSettings {
     ObjectSettings {
       label: String::from("Object Settings"),
       object: 0,
+      selection: None,
       showing: false,
     }
   }
@@ -0,0 +1,326 @@
+use std::collections::BTreeMap;
+
+use egui::{Style, epaint::Shadow, Frame, Color32, Stroke, Pos2, Rect, Sense, RichText, Ui};
+
+use crate::{world::{World, Selection, Node}, history::{History, Target, Command}, outliner::{Checked, exists}};
+
+use super::utils::{ShowableUI, Editor};
+
+struct Entry {
+  selection: Selection,
+  name: String,
+  node: Node,
+}
+
+enum Action {
+  Select(Selection),
+  Check(Selection, bool),
+  Hide(Selection, bool),
+  Lock(Selection, bool),
+  Drop(Selection),
+}
+
+pub struct Outliner {
+  showing: bool,
+  label: String,
+  checked: Checked,
+  group: String,
+  dragging: Option<(Selection, Pos2)>,
+  rows: Vec<(Selection, Rect)>,
+}
+
+impl ShowableUI<Editor<'_>> for Outliner {
+  fn ui(&mut self, ctx: &egui::Context, editor: Editor) {
+    let Editor { world, history } = editor;
+
+    self.checked.sync(world, history);
+
+    let objects: Vec<Entry> = world.objects().iter().enumerate()
+      .map(|(index, object)| Entry {
+        selection: Selection::Object(index),
+        name: object.node().name.clone().unwrap_or_else(|| format!("{} {}", object.to_string(), index)),
+        node: object.node().clone(),
+      })
+      .collect();
+
+    let lights: Vec<Entry> = world.lights().iter().enumerate()
+      .map(|(index, light)| Entry {
+        selection: Selection::Light(index),
+        name: light.node().name.clone().unwrap_or_else(|| format!("{} {}", light.to_string(), index)),
+        node: light.node().clone(),
+      })
+      .collect();
+
+    let mut showing = self.showing;
+    let mut actions = Vec::new();
+
+    egui::Window::new(self.label.as_str())
+      .open(&mut showing)
+      .frame(
+        Frame::window(&Style::default())
+          .shadow(Shadow::NONE)
+      )
+      .show(ctx, |ui| {
+        self.rows.clear();
+
+        egui::ScrollArea::vertical()
+          .max_height(400.0)
+          .show(ui, |ui| {
+            egui::CollapsingHeader::new(format!("Objects ({})", objects.len()))
+              .id_source("outliner_objects")
+              .default_open(true)
+              .show(ui, |ui| self.tree(ui, world.selection(), &objects, &mut actions));
+
+            egui::CollapsingHeader::new(format!("Lights ({})", lights.len()))
+              .id_source("outliner_lights")
+              .default_open(true)
+              .show(ui, |ui| self.tree(ui, world.selection(), &lights, &mut actions));
+          });
+
+        if let Some(((from, _), to)) = self.dragging.zip(self.drop_target()) {
+          let rect = self.rows.iter().find(|(selection, _)| *selection == to).map(|(_, rect)| *rect);
+
+          if let Some(rect) = rect {
+            let y = if index(from) < index(to) { rect.bottom() } else { rect.top() };
+            ui.painter().hline(rect.x_range(), y, Stroke::new(2.0, Color32::YELLOW));
+          }
+        }
+
+        ui.separator();
+
+        egui::Grid::new("outliner_grid")
+          .num_columns(2)
+          .spacing([40.0, 4.0])
+          .striped(true)
+          .show(ui, |ui| {
+            if let Some(selection) = world.selection().filter(|selection| exists(world, *selection)) {
+              history.watch(world, target(selection));
+
+              if let Some(node) = node_mut(world, selection) {
+                ui.label("Name");
+                let mut name = node.name.clone().unwrap_or_default();
+
+                if ui.text_edit_singleline(&mut name).changed() {
+                  node.name = Some(name).filter(|name| !name.is_empty());
+                  history.edited(target(selection));
+                }
+                ui.end_row();
+              }
+            }
+
+            let targets = self.checked.targets(world);
+
+            ui.label("Group");
+            ui.horizontal(|ui| {
+              ui.add(egui::TextEdit::singleline(&mut self.group).desired_width(100.0));
+
+              ui.add_enabled_ui(!targets.is_empty(), |ui| {
+                if ui.button("Group").clicked() {
+                  let group = Some(self.group.trim().to_string()).filter(|group| !group.is_empty());
+                  set(world, history, &targets, |node| node.group = group.clone());
+                }
+
+                if ui.button("Ungroup").clicked() {
+                  set(world, history, &targets, |node| node.group = None);
+                }
+              });
+            });
+            ui.end_row();
+
+            ui.label(format!("Selected ({})", targets.len()));
+            ui.horizontal(|ui| {
+              ui.add_enabled_ui(!targets.is_empty(), |ui| {
+                if ui.button("Duplicate").clicked() {
+                  self.checked.duplicate(world, history, &targets);
+                }
+              });
+
+              ui.add_enabled_ui(!self.checked.is_empty(), |ui| {
+                if ui.button("Clear").clicked() {
+                  self.checked.clear();
+                }
+              });
+            });
+            ui.end_row();
+          });
+      });
+
+    for action in actions {
+      self.apply(world, history, action);
+    }
+
+    self.showing = showing;
+  }
+
+  fn label(&self) -> &str {
+    self.label.as_str()
+  }
+
+  fn show(&mut self) {
+    self.showing = true;
+  }
+}
+
+impl Outliner {
+  pub fn new() -> Outliner {
+    Outliner {
+      label: String::from("Outliner"),
+      showing: false,
+      checked: Checked::new(),
+      group: String::new(),
+      dragging: None,
+      rows: Vec::new(),
+    }
+  }
+
+  // Ungrouped entries come first, then one collapsible branch per group.
+  fn tree(&mut self, ui: &mut Ui, selected: Option<Selection>, entries: &[Entry], actions: &mut Vec<Action>) {
+    let mut groups: BTreeMap<&str, Vec<&Entry>> = BTreeMap::new();
+
+    for entry in entries {
+      match &entry.node.group {
+        Some(group) => groups.entry(group.as_str()).or_default().push(entry),
+        None => self.row(ui, selected, entry, actions),
+      }
+    }
+
+    for (group, entries) in groups {
+      let kind = entries[0].selection;
+
+      egui::CollapsingHeader::new(group)
+        .id_source(("outliner_group", std::mem::discriminant(&kind), group))
+        .default_open(true)
+        .show(ui, |ui| {
+          for entry in entries {
+            self.row(ui, selected, entry, actions);
+          }
+        });
+    }
+  }
+
+  fn row(&mut self, ui: &mut Ui, selected: Option<Selection>, entry: &Entry, actions: &mut Vec<Action>) {
+    let selection = entry.selection;
+
+    let row = ui.horizontal(|ui| {
+      let mut checked = self.checked.contains(selection);
+      if ui.checkbox(&mut checked, "").changed() {
+        actions.push(Action::Check(selection, checked));
+      }
+
+      let mut visible = !entry.node.hidden;
+      if ui.toggle_value(&mut visible, "👁").on_hover_text("Render").changed() {
+        actions.push(Action::Hide(selection, !visible));
+      }
+
+      let mut locked = entry.node.locked;
+      if ui.toggle_value(&mut locked, "🔒").on_hover_text("Lock").changed() {
+        actions.push(Action::Lock(selection, locked));
+      }
+
+      let text = if entry.node.hidden {
+        RichText::new(entry.name.as_str()).weak()
+      } else {
+        RichText::new(entry.name.as_str())
+      };
+
+      let response = ui.selectable_label(selected == Some(selection), text).interact(Sense::click_and_drag());
+
+      if response.clicked() {
+        actions.push(Action::Select(selection));
+      }
+
+      if response.drag_started() {
+        self.dragging = Some((selection, response.rect.center()));
+      }
+
+      if let Some((dragged, position)) = &mut self.dragging {
+        if *dragged == selection && response.dragged() {
+          *position += response.drag_delta();
+        }
+      }
+
+      if response.drag_released() {
+        actions.push(Action::Drop(selection));
+      }
+    });
+
+    self.rows.push((selection, row.response.rect));
+  }
+
+  // The row under the dragged entry, only rows of the same kind accept it.
+  fn drop_target(&self) -> Option<Selection> {
+    let (dragged, position) = self.dragging?;
+
+    self.rows.iter()
+      .find(|(selection, rect)| {
+        std::mem::discriminant(selection) == std::mem::discriminant(&dragged) &&
+        rect.y_range().contains(&position.y)
+      })
+      .map(|(selection, _)| *selection)
+  }
+
+  fn apply(&mut self, world: &mut World, history: &mut History, action: Action) {
+    match action {
+      Action::Select(selection) => world.select(Some(selection)),
+      Action::Check(selection, checked) => self.checked.set(selection, checked),
+      Action::Hide(selection, hidden) => set(world, history, &self.checked.batch(world, selection), |node| node.hidden = hidden),
+      Action::Lock(selection, locked) => set(world, history, &self.checked.batch(world, selection), |node| node.locked = locked),
+      Action::Drop(selection) => {
+        let target = self.drop_target();
+        self.dragging = None;
+
+        let target = match target {
+          Some(target) if target != selection => target,
+          _ => return,
+        };
+
+        // Dropping into another group moves the entry into that group.
+        let group = node_mut(world, target).and_then(|node| node.group.clone());
+        set(world, history, &[selection], |node| node.group = group.clone());
+
+        match (selection, target) {
+          (Selection::Object(from), Selection::Object(to)) => history.execute(world, Command::MoveObject(from, to)),
+          (Selection::Light(from), Selection::Light(to)) => history.execute(world, Command::MoveLight(from, to)),
+          _ => (),
+        }
+
+        self.checked.clear();
+      },
+    }
+  }
+}
+
+fn index(selection: Selection) -> usize {
+  match selection {
+    Selection::Object(index) | Selection::Light(index) => index,
+    Selection::Camera => 0,
+  }
+}
+
+fn node_mut(world: &mut World, selection: Selection) -> Option<&mut Node> {
+  match selection {
+    Selection::Object(index) => world.objects_mut().get_mut(index).map(|object| object.node_mut()),
+    Selection::Light(index) => world.lights_mut().get_mut(index).map(|light| light.node_mut()),
+    Selection::Camera => None,
+  }
+}
+
+fn set(world: &mut World, history: &mut History, selections: &[Selection], change: impl Fn(&mut Node)) {
+  let targets: Vec<Target> = selections.iter().map(|selection| target(*selection)).collect();
+
+  history.change(world, &targets, |world| {
+    for selection in selections {
+      if let Some(node) = node_mut(world, *selection) {
+        change(node);
+      }
+    }
+  });
+}
+
+fn target(selection: Selection) -> Target {
+  match selection {
+    Selection::Object(index) => Target::Object(index),
+    Selection::Light(index) => Target::Light(index),
+    Selection::Camera => Target::Camera,
+  }
+}
\ No newline at end of file
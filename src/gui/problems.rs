@@ -0,0 +1,88 @@
+use egui::{Style, epaint::Shadow, Frame, Color32};
+
+use crate::validation::{self, Problem, Severity};
+
+use super::utils::{ShowableUI, Editor};
+
+pub struct Problems {
+  showing: bool,
+  label: String,
+  problems: Vec<Problem>,
+  errors: usize,
+}
+
+impl ShowableUI<Editor<'_>> for Problems {
+  fn ui(&mut self, ctx: &egui::Context, editor: Editor) {
+    let world = editor.world;
+
+    self.problems = validation::validate(world);
+
+    let errors = self.problems.iter()
+      .filter(|problem| problem.severity == Severity::Error)
+      .count();
+
+    if errors > self.errors {
+      self.showing = true;
+    }
+
+    self.errors = errors;
+
+    let problems = &self.problems;
+
+    egui::Window::new(self.label.as_str())
+      .open(&mut self.showing)
+      .frame(
+        Frame::window(&Style::default())
+          .shadow(Shadow::NONE)
+      )
+      .show(ctx, |ui| {
+        if problems.is_empty() {
+          ui.label("No problems found");
+          return;
+        }
+
+        egui::Grid::new("problems_grid")
+          .num_columns(3)
+          .spacing([20.0, 4.0])
+          .striped(true)
+          .show(ui, |ui| {
+            for problem in problems {
+              let color = match problem.severity {
+                Severity::Error => Color32::LIGHT_RED,
+                Severity::Warning => Color32::YELLOW,
+              };
+
+              ui.colored_label(color, problem.severity.to_string());
+
+              let selected = world.selection() == Some(problem.subject);
+
+              if ui.selectable_label(selected, problem.subject.to_string()).clicked() {
+                world.select(Some(problem.subject));
+              }
+
+              ui.label(format!("{} {}", problem.field, problem.message));
+              ui.end_row();
+            }
+          });
+      });
+  }
+
+  fn label(&self) -> &str {
+    self.label.as_str()
+  }
+
+  fn show(&mut self) {
+    self.showing = true;
+  }
+}
+
+impl Problems {
+  pub fn new() -> Problems {
+    Problems {
+      label: String::from("Problems"),
+      showing: false,
+      problems: Vec::new(),
+      errors: 0,
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,146 @@
+use std::time::Duration;
+
+use egui::{Style, epaint::Shadow, Frame, ColorImage, TextureHandle, TextureOptions, Vec2};
+
+use crate::stats::RenderStats;
+
+use super::utils::ShowableUI;
+
+const HEATMAP_WIDTH: f32 = 240.0;
+
+pub struct StatsPanel {
+  showing: bool,
+  label: String,
+  stats: Option<RenderStats>,
+  heatmap: bool,
+  texture: Option<TextureHandle>,
+}
+
+impl ShowableUI<()> for StatsPanel {
+  fn ui(&mut self, ctx: &egui::Context, _: ()) {
+    if self.showing && self.heatmap && self.texture.is_none() {
+      self.update_texture(ctx);
+    }
+
+    let stats = &self.stats;
+    let texture = &self.texture;
+    let mut heatmap = self.heatmap;
+
+    egui::Window::new(self.label.as_str())
+      .open(&mut self.showing)
+      .frame(
+        Frame::window(&Style::default())
+          .shadow(Shadow::NONE)
+      )
+      .show(ctx, |ui| {
+        let stats = match stats {
+          Some(stats) => stats,
+          None => {
+            ui.label("Waiting for a complete frame");
+            return;
+          },
+        };
+
+        egui::Grid::new("stats_grid")
+          .num_columns(2)
+          .spacing([40.0, 4.0])
+          .striped(true)
+          .show(ui, |ui| {
+            ui.label("Resolution");
+            ui.label(format!("{} x {}", stats.width, stats.height));
+            ui.end_row();
+
+            ui.label("Primary Rays");
+            ui.label(stats.primary_rays.to_string());
+            ui.end_row();
+
+            ui.label("Shadow Rays");
+            ui.label(stats.shadow_rays.to_string());
+            ui.end_row();
+
+            ui.label("Secondary Rays");
+            ui.label(stats.secondary_rays.to_string());
+            ui.end_row();
+
+            ui.label("Intersection Tests");
+            ui.label(format!("{} ({:.1} per pixel)", stats.intersection_tests, stats.tests_per_pixel()));
+            ui.end_row();
+
+            ui.label("Average Path Depth");
+            ui.label(format!("{:.2}", stats.average_depth()));
+            ui.end_row();
+
+            ui.label("Ray Generation");
+            ui.label(milliseconds(stats.ray_generation));
+            ui.end_row();
+
+            ui.label("Tracing");
+            ui.label(milliseconds(stats.tracing));
+            ui.end_row();
+
+            ui.label("Texture Upload");
+            ui.label(milliseconds(stats.texture_upload));
+            ui.end_row();
+
+            ui.label("Heatmap");
+            ui.checkbox(&mut heatmap, "Show");
+            ui.end_row();
+          });
+
+        if let Some(texture) = texture.as_ref().filter(|_| heatmap) {
+          let aspect = stats.height as f32 / stats.width.max(1) as f32;
+
+          ui.image(texture.id(), Vec2::new(HEATMAP_WIDTH, HEATMAP_WIDTH * aspect));
+          ui.label(format!("Intersection tests per pixel, up to {}", stats.max_cost()));
+        }
+      });
+
+    self.heatmap = heatmap;
+  }
+
+  fn label(&self) -> &str {
+    self.label.as_str()
+  }
+
+  fn show(&mut self) {
+    self.showing = true;
+  }
+}
+
+impl StatsPanel {
+  pub fn new() -> StatsPanel {
+    StatsPanel {
+      label: String::from("Render Stats"),
+      showing: false,
+      stats: None,
+      heatmap: false,
+      texture: None,
+    }
+  }
+
+  pub fn update(&mut self, stats: RenderStats) {
+    self.stats = Some(stats);
+    self.texture = None;
+  }
+
+  // The heatmap is only turned into a texture while it is being looked at.
+  fn update_texture(&mut self, ctx: &egui::Context) {
+    let stats = match &self.stats {
+      Some(stats) => stats,
+      None => return,
+    };
+
+    let image = stats.heatmap();
+    let pixels: Vec<u8> = image.pixels.iter().flat_map(|pixel| *pixel).collect();
+
+    self.texture = Some(ctx.load_texture(
+      "stats_heatmap",
+      ColorImage::from_rgb([image.width as usize, image.height as usize], &pixels),
+      TextureOptions::NEAREST,
+    ));
+  }
+}
+
+fn milliseconds(duration: Duration) -> String {
+  format!("{:.2} ms", duration.as_secs_f64() * 1000.0)
+}
\ No newline at end of file
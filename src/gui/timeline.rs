@@ -0,0 +1,265 @@
+use std::time::Instant;
+
+use egui::{Style, epaint::Shadow, Frame, Stroke, Color32, pos2};
+
+use crate::{world::{World, Light, Selection}, animation::{Track, Keyframe, Interpolation, insert_keyframe}, vector::Float, history::Target};
+
+use super::utils::{ShowableUI, Editor, combo};
+
+pub struct Timeline {
+  showing: bool,
+  label: String,
+  playing: bool,
+  instant: Instant,
+  interpolation: Interpolation,
+}
+
+impl ShowableUI<Editor<'_>> for Timeline {
+  fn ui(&mut self, ctx: &egui::Context, editor: Editor) {
+    let Editor { world, history } = editor;
+
+    let elapsed = self.instant.elapsed().as_secs_f64() as Float;
+    self.instant = Instant::now();
+
+    if self.playing {
+      let animation = world.animation();
+      let (start, end) = (animation.start as Float, animation.end as Float);
+
+      let mut frame = world.frame() + elapsed * animation.frame_rate;
+
+      if frame > end {
+        frame = start;
+      }
+
+      world.set_frame(frame);
+      ctx.request_repaint();
+    }
+
+    let playing = &mut self.playing;
+    let interpolation = &mut self.interpolation;
+
+    egui::Window::new(self.label.as_str())
+      .open(&mut self.showing)
+      .frame(
+        Frame::window(&Style::default())
+          .shadow(Shadow::NONE)
+      )
+      .show(ctx, |ui| {
+        history.watch(world, Target::Animation);
+        let mut changed = false;
+
+        ui.horizontal(|ui| {
+          let play_label = if *playing { "Pause" } else { "Play" };
+          ui.toggle_value(playing, play_label);
+
+          let (start, end) = (world.animation().start, world.animation().end);
+          let mut frame = world.frame();
+
+          let slider = ui.add(
+            egui::Slider::new(&mut frame, start as Float..=end as Float)
+              .step_by(1.0)
+          );
+
+          if slider.changed() {
+            world.set_frame(frame);
+          }
+
+          keyframe_markers(ui, world, slider.rect);
+        });
+
+        egui::Grid::new("timeline_grid")
+          .num_columns(2)
+          .spacing([40.0, 4.0])
+          .striped(true)
+          .show(ui, |ui| {
+            let animation = world.animation_mut();
+
+            ui.label("Frames");
+            ui.horizontal(|ui| {
+              changed |= ui.add(egui::DragValue::new(&mut animation.start).clamp_range(0..=animation.end)).changed();
+              changed |= ui.add(egui::DragValue::new(&mut animation.end).clamp_range(animation.start..=100000)).changed();
+            });
+            ui.end_row();
+
+            ui.label("Frame Rate");
+            changed |= ui.add(
+              egui::DragValue::new(&mut animation.frame_rate)
+                .clamp_range(1..=240)
+                .speed(1)
+            ).changed();
+            ui.end_row();
+
+            ui.label("Interpolation");
+            combo(ui, interpolation, vec![
+              Interpolation::Linear,
+              Interpolation::Bezier,
+            ]);
+            ui.end_row();
+
+            ui.label("Camera");
+            if ui.button("Key").clicked() {
+              key_camera(world, *interpolation);
+              changed = true;
+            }
+            ui.end_row();
+
+            ui.label("Selection");
+            ui.add_enabled_ui(matches!(world.selection(), Some(Selection::Object(_) | Selection::Light(_))), |ui| {
+              if ui.button("Key").clicked() {
+                key_selection(world, *interpolation);
+                changed = true;
+              }
+            });
+            ui.end_row();
+
+            ui.label("Tracks");
+            ui.label(world.animation().tracks.len().to_string());
+            ui.end_row();
+          });
+
+        let mut removed = None;
+
+        for (index, track) in world.animation().tracks.iter().enumerate() {
+          ui.horizontal(|ui| {
+            ui.label(format!("{} ({} keys)", track.to_string(), track.frames().len()));
+
+            if ui.small_button("Remove").clicked() {
+              removed = Some(index);
+            }
+          });
+        }
+
+        if let Some(index) = removed {
+          world.animation_mut().tracks.remove(index);
+          changed = true;
+        }
+
+        if changed {
+          history.edited(Target::Animation);
+        }
+      });
+  }
+
+  fn label(&self) -> &str {
+    self.label.as_str()
+  }
+
+  fn show(&mut self) {
+    self.showing = true;
+  }
+}
+
+impl Timeline {
+  pub fn new() -> Timeline {
+    Timeline {
+      label: String::from("Timeline"),
+      showing: false,
+      playing: false,
+      instant: Instant::now(),
+      interpolation: Interpolation::Linear,
+    }
+  }
+}
+
+fn keyframe_markers(ui: &egui::Ui, world: &World, slider: egui::Rect) {
+  let (start, end) = (world.animation().start as Float, world.animation().end as Float);
+
+  if end <= start {
+    return;
+  }
+
+  // The handle stops half its height short of either end of the rail.
+  let inset = slider.height() / 2.0;
+  let width = ui.spacing().slider_width - inset * 2.0;
+  let painter = ui.painter_at(slider.expand(2.0));
+
+  for frame in world.animation().tracks.iter().flat_map(|track| track.frames()) {
+    let x = slider.left() + inset + ((frame - start) / (end - start)) as f32 * width;
+
+    painter.line_segment(
+      [pos2(x, slider.bottom() - 3.0), pos2(x, slider.bottom() + 1.0)],
+      Stroke::new(2.0, Color32::GOLD),
+    );
+  }
+}
+
+fn key_camera(world: &mut World, interpolation: Interpolation) {
+  let frame = world.frame();
+  let camera_info = *world.camera().camera_info();
+
+  key(world, Track::CameraPosition(Vec::new()), |track| if let Track::CameraPosition(keyframes) = track {
+    insert_keyframe(keyframes, Keyframe::new(frame, camera_info.position, interpolation));
+  });
+
+  key(world, Track::CameraForward(Vec::new()), |track| if let Track::CameraForward(keyframes) = track {
+    insert_keyframe(keyframes, Keyframe::new(frame, camera_info.forward, interpolation));
+  });
+
+  key(world, Track::CameraFov(Vec::new()), |track| if let Track::CameraFov(keyframes) = track {
+    insert_keyframe(keyframes, Keyframe::new(frame, camera_info.vertical_fov, interpolation));
+  });
+}
+
+fn key_selection(world: &mut World, interpolation: Interpolation) {
+  let frame = world.frame();
+
+  match world.selection() {
+    Some(Selection::Object(object)) => {
+      let (position, color) = match world.objects().get(object) {
+        Some(target) => (*target.position(), target.material().color),
+        None => return,
+      };
+
+      key(world, Track::ObjectPosition { object, keyframes: Vec::new() }, |track| if let Track::ObjectPosition { keyframes, .. } = track {
+        insert_keyframe(keyframes, Keyframe::new(frame, position, interpolation));
+      });
+
+      key(world, Track::ObjectColor { object, keyframes: Vec::new() }, |track| if let Track::ObjectColor { keyframes, .. } = track {
+        insert_keyframe(keyframes, Keyframe::new(frame, color, interpolation));
+      });
+    },
+    Some(Selection::Light(light)) => {
+      let target = match world.lights().get(light) {
+        Some(target) => target.clone(),
+        None => return,
+      };
+
+      let color = match &target {
+        Light::Ambient(target) => target.color,
+        Light::Directional(target) => target.color,
+        Light::Point(target) => target.color,
+      };
+
+      key(world, Track::LightColor { light, keyframes: Vec::new() }, |track| if let Track::LightColor { keyframes, .. } = track {
+        insert_keyframe(keyframes, Keyframe::new(frame, color, interpolation));
+      });
+
+      match target {
+        Light::Directional(target) => key(world, Track::LightDirection { light, keyframes: Vec::new() }, |track| if let Track::LightDirection { keyframes, .. } = track {
+          insert_keyframe(keyframes, Keyframe::new(frame, target.direction, interpolation));
+        }),
+        Light::Point(target) => key(world, Track::LightPosition { light, keyframes: Vec::new() }, |track| if let Track::LightPosition { keyframes, .. } = track {
+          insert_keyframe(keyframes, Keyframe::new(frame, target.position, interpolation));
+        }),
+        Light::Ambient(_) => (),
+      }
+    },
+    _ => (),
+  }
+}
+
+// Finds the track with the same kind and target as the empty one, adding it if missing.
+fn key(world: &mut World, empty: Track, insert: impl FnOnce(&mut Track)) {
+  let tracks = &mut world.animation_mut().tracks;
+  let label = empty.to_string();
+
+  let index = match tracks.iter().position(|track| track.to_string() == label) {
+    Some(index) => index,
+    None => {
+      tracks.push(empty);
+      tracks.len() - 1
+    },
+  };
+
+  insert(&mut tracks[index]);
+}
\ No newline at end of file
@@ -1,4 +1,6 @@
-use egui::{Ui, Context};
+use egui::{Ui, Context, Rect, vec2};
+
+use crate::{world::{World, Material}, history::History};
 
 pub fn combo<T>(ui: &mut Ui, value: &mut T, options: Vec<T>) 
 where
@@ -14,6 +16,89 @@ where
     });
 }
 
+// The image is letterboxed into the window the same way Graphics draws it.
+pub fn viewport_rect(screen: Rect, width: u32, height: u32) -> Rect {
+  let image_ratio = width as f32 / height as f32;
+  let screen_ratio = screen.width() / screen.height();
+
+  let size = if image_ratio > screen_ratio {
+    vec2(screen.width(), screen.width() / image_ratio)
+  } else {
+    vec2(screen.height() * image_ratio, screen.height())
+  };
+
+  Rect::from_center_size(screen.center(), size)
+}
+
+// Rows for a two column grid, shared by the object settings and the material library.
+pub fn material_rows(ui: &mut Ui, material: &mut Material) {
+  ui.label("Color");
+  ui.color_edit_button_rgb(&mut material.color);
+
+  ui.end_row();
+  ui.label("Reflectivity");
+  ui.add(
+    egui::DragValue::new(&mut material.reflectivity)
+      .clamp_range(0..=1)
+      .speed(0.05)
+  );
+
+  ui.end_row();
+  ui.label("Refractive Index");
+  ui.add(
+    egui::DragValue::new(&mut material.refractive_index)
+      .clamp_range(0..=3)
+      .speed(0.05)
+  );
+
+  ui.end_row();
+  ui.label("Transparency");
+  ui.add(
+    egui::DragValue::new(&mut material.transparency)
+      .clamp_range(0..=1)
+      .speed(0.05)
+  );
+
+  ui.end_row();
+  ui.label("Ambient Reflection");
+  ui.add(
+    egui::DragValue::new(&mut material.ambient_reflection)
+      .clamp_range(0..=100)
+      .speed(0.05)
+  );
+
+  ui.end_row();
+  ui.label("Diffuse Reflection");
+  ui.add(
+    egui::DragValue::new(&mut material.diffuse_reflection)
+      .clamp_range(0..=100)
+      .speed(0.05)
+  );
+
+  ui.end_row();
+  ui.label("Specular Reflection");
+  let specular_label = if material.has_specular { "on" } else {"off"};
+  ui.toggle_value(&mut material.has_specular, specular_label);
+  ui.end_row();
+
+  if material.has_specular {
+    ui.label("Specular Amount");
+    ui.add(
+      egui::DragValue::new(&mut material.specular_reflection)
+        .clamp_range(0..=100)
+        .speed(1.0)
+    );
+  }
+
+  ui.end_row();
+}
+
+// What the windows edit, with the history their edits are recorded in.
+pub struct Editor<'a> {
+  pub world: &'a mut World,
+  pub history: &'a mut History,
+}
+
 pub trait ShowableUI<T> {
   fn ui(&mut self, ctx: &Context, data: T);
   fn label(&self) -> &str;
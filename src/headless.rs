@@ -0,0 +1,100 @@
+use std::{error::Error, path::{Path, PathBuf}, ops::RangeInclusive, time::{Instant, Duration}};
+
+use crate::{scene_file::{self, SceneError}, gltf_import, renderer::Renderer, world::World, vector::Float, camera_path::CameraPath, video::{VideoFormat, VideoWriter}};
+
+pub fn load(scene: &Path) -> Result<World, SceneError> {
+  if gltf_import::is_gltf(scene) {
+    gltf_import::import(scene)
+  } else {
+    scene_file::load(scene)
+  }
+}
+
+pub fn render(world: &mut World, output: &Path) -> Result<(), Box<dyn Error>> {
+  let image = Renderer::new().render(world).to_image();
+  image.save_png(output)?;
+
+  Ok(())
+}
+
+// Video outputs get every frame streamed into one file, anything else is
+// written as numbered images.
+pub fn render_frames(world: &mut World, output: &Path, frames: RangeInclusive<u32>) -> Result<(), Box<dyn Error>> {
+  if let Some(format) = VideoFormat::from_path(output) {
+    let camera_info = world.camera().camera_info();
+    let frame_count = frames.end() - frames.start() + 1;
+
+    let mut video = VideoWriter::create(
+      output,
+      format,
+      camera_info.viewport_width,
+      camera_info.viewport_height,
+      world.animation().frame_rate,
+      frame_count,
+    )?;
+
+    for frame in frames {
+      world.set_frame(frame as Float);
+      video.write_frame(&Renderer::new().render(world).to_image())?;
+    }
+
+    video.finish()?;
+
+    return Ok(());
+  }
+
+  for frame in frames {
+    world.set_frame(frame as Float);
+
+    let image = Renderer::new().render(world).to_image();
+    image.save_png(&frame_path(output, frame))?;
+  }
+
+  Ok(())
+}
+
+// Renders every recorded camera in order and returns how long each frame took.
+pub fn replay(world: &mut World, path: &Path) -> Result<Vec<Duration>, Box<dyn Error>> {
+  let path = CameraPath::load(path)?;
+
+  let mut times = Vec::new();
+
+  for sample in &path.samples {
+    *world.camera_mut().camera_info_mut() = sample.camera_info;
+
+    let instant = Instant::now();
+    Renderer::new().render(world);
+    times.push(instant.elapsed());
+  }
+
+  Ok(times)
+}
+
+// A run of '#' in the file name is replaced by the zero padded frame number,
+// without one the number is appended to the file stem.
+pub fn frame_path(output: &Path, frame: u32) -> PathBuf {
+  let name = output.file_name().map_or(String::new(), |name| name.to_string_lossy().into_owned());
+
+  let name = match name.find('#') {
+    Some(start) => {
+      let width = name[start..].chars().take_while(|c| *c == '#').count();
+      format!("{}{:0width$}{}", &name[..start], frame, &name[start + width..], width = width)
+    },
+    None => {
+      let stem = output.file_stem().map_or(String::new(), |stem| stem.to_string_lossy().into_owned());
+
+      match output.extension() {
+        Some(extension) => format!("{}_{:04}.{}", stem, frame, extension.to_string_lossy()),
+        None => format!("{}_{:04}", stem, frame),
+      }
+    },
+  };
+
+  output.with_file_name(name)
+}
+
+pub fn convert(scene: &Path, output: &Path) -> Result<(), Box<dyn Error>> {
+  scene_file::save(output, &load(scene)?)?;
+
+  Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,456 @@
+use std::collections::BTreeMap;
+
+use crate::{world::{World, Object, Light, Material, CameraInfo, Selection}, animation::{Animation, Track}, vector::{Vector3, Float}};
+
+const HISTORY_LIMIT: usize = 100;
+
+// A part of the scene an edit can change. Steps keep a copy of just the parts
+// they changed, so editing one object doesn't copy every mesh in the scene.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Target {
+  Object(usize),
+  Light(usize),
+  Camera,
+  Materials,
+  Animation,
+}
+
+// Edits that add, remove or reorder things, moving the indices of what comes after.
+#[derive(Clone, Debug)]
+pub enum Command {
+  AddObject(Object),
+  RemoveObject(usize),
+  AddLight(Light),
+  RemoveLight(usize),
+  MoveObject(usize, usize),
+  MoveLight(usize, usize),
+}
+
+#[derive(PartialEq, Clone, Debug)]
+enum Value {
+  Object(Object),
+  Light(Light),
+  // Position and direction are left out, flying and framing move the camera
+  // without being recorded.
+  Camera(CameraInfo, u32),
+  // The material names objects use are kept with the library, renaming or
+  // removing a material changes them.
+  Materials(BTreeMap<String, Material>, Vec<Option<String>>),
+  Animation(Animation),
+}
+
+impl Value {
+  fn of(world: &World, target: Target) -> Option<Value> {
+    match target {
+      Target::Object(index) => world.objects().get(index).cloned().map(Value::Object),
+      Target::Light(index) => world.lights().get(index).cloned().map(Value::Light),
+      Target::Camera => Some(Value::Camera(camera_settings(world.camera().camera_info()), *world.camera().depth())),
+      Target::Materials => Some(Value::Materials(world.materials().clone(), material_names(world))),
+      Target::Animation => Some(Value::Animation(world.animation().clone())),
+    }
+  }
+
+  // Compares without copying, meshes can be large.
+  fn matches(&self, world: &World, target: Target) -> bool {
+    match (self, target) {
+      (Value::Object(object), Target::Object(index)) => world.objects().get(index) == Some(object),
+      (Value::Light(light), Target::Light(index)) => world.lights().get(index) == Some(light),
+      (Value::Camera(camera_info, depth), Target::Camera) => {
+        *camera_info == camera_settings(world.camera().camera_info()) && depth == world.camera().depth()
+      },
+      (Value::Materials(materials, names), Target::Materials) => {
+        materials == world.materials() &&
+        names.len() == world.objects().len() &&
+        names.iter().zip(world.objects()).all(|(name, object)| name.as_deref() == object.material_name())
+      },
+      (Value::Animation(animation), Target::Animation) => animation == world.animation(),
+      _ => false,
+    }
+  }
+
+  fn put(self, world: &mut World, target: Target) {
+    match (self, target) {
+      (Value::Object(object), Target::Object(index)) => if let Some(existing) = world.objects_mut().get_mut(index) {
+        *existing = object;
+      },
+      (Value::Light(light), Target::Light(index)) => if let Some(existing) = world.lights_mut().get_mut(index) {
+        *existing = light;
+      },
+      (Value::Camera(camera_info, depth), Target::Camera) => {
+        let current = world.camera_mut().camera_info_mut();
+        *current = CameraInfo { position: current.position, forward: current.forward, ..camera_info };
+
+        *world.camera_mut().depth_mut() = depth;
+      },
+      (Value::Materials(materials, names), Target::Materials) => {
+        *world.materials_mut() = materials;
+
+        for (object, name) in world.objects_mut().iter_mut().zip(names) {
+          object.set_material_name(name);
+        }
+
+        world.apply_materials();
+      },
+      (Value::Animation(animation), Target::Animation) => *world.animation_mut() = animation,
+      _ => (),
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+enum Change {
+  // What a part of the scene is on the other side of the step.
+  Edit(Target, Value),
+  // An object or light held here while it is out of the scene, with the
+  // animation from when it was in it.
+  Object(usize, Option<(Object, Animation)>),
+  Light(usize, Option<(Light, Animation)>),
+  MoveObject(usize, usize),
+  MoveLight(usize, usize),
+  Scene(Box<World>),
+}
+
+impl Change {
+  // Changes hold the side of the step the scene isn't on, so undoing and
+  // redoing both swap it with the scene.
+  fn swap(&mut self, world: &mut World) {
+    match self {
+      Change::Edit(target, value) => if let Some(current) = Value::of(world, *target) {
+        std::mem::replace(value, current).put(world, *target);
+      },
+      Change::Object(index, held) => match held.take() {
+        Some((object, animation)) => {
+          world.insert_object(*index, object);
+          *world.animation_mut() = animation;
+        },
+        None => {
+          let animation = world.animation().clone();
+          *held = world.remove_object(*index).map(|object| (object, animation));
+        },
+      },
+      Change::Light(index, held) => match held.take() {
+        Some((light, animation)) => {
+          world.insert_light(*index, light);
+          *world.animation_mut() = animation;
+        },
+        None => {
+          let animation = world.animation().clone();
+          *held = world.remove_light(*index).map(|light| (light, animation));
+        },
+      },
+      Change::MoveObject(from, to) => {
+        world.move_object(*from, *to);
+        std::mem::swap(from, to);
+      },
+      Change::MoveLight(from, to) => {
+        world.move_light(*from, *to);
+        std::mem::swap(from, to);
+      },
+      Change::Scene(scene) => std::mem::swap(world, &mut **scene),
+    }
+  }
+
+  // Named while the change is applied.
+  fn label(&self) -> String {
+    match self {
+      Change::Edit(Target::Object(index), _) => format!("Edit Object {}", index),
+      Change::Edit(Target::Light(index), _) => format!("Edit Light {}", index),
+      Change::Edit(Target::Camera, _) => String::from("Edit Camera"),
+      Change::Edit(Target::Materials, _) => String::from("Edit Materials"),
+      Change::Edit(Target::Animation, _) => String::from("Edit Animation"),
+      Change::Object(_, None) => String::from("Add Object"),
+      Change::Object(_, Some(_)) => String::from("Remove Object"),
+      Change::Light(_, None) => String::from("Add Light"),
+      Change::Light(_, Some(_)) => String::from("Remove Light"),
+      Change::MoveObject(..) => String::from("Move Object"),
+      Change::MoveLight(..) => String::from("Move Light"),
+      Change::Scene(_) => String::from("Replace Scene"),
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct Step {
+  pub label: String,
+  changes: Vec<Change>,
+}
+
+// Windows watch what they show and say when they edited it. A copy is taken
+// when something is first watched, and compared once the edit ends, so
+// nothing is copied or compared while nothing is edited.
+pub struct History {
+  undo: Vec<Step>,
+  redo: Vec<Step>,
+  watched: Vec<(Target, Value)>,
+  edited: Vec<Target>,
+  // Changes made since the last step, in the order they were made.
+  pending: Vec<Change>,
+  frame: Float,
+}
+
+impl History {
+  pub fn new(world: &World) -> History {
+    History {
+      undo: Vec::new(),
+      redo: Vec::new(),
+      watched: Vec::new(),
+      edited: Vec::new(),
+      pending: Vec::new(),
+      frame: world.frame(),
+    }
+  }
+
+  // Steps that can be undone, oldest first.
+  pub fn undo_steps(&self) -> &[Step] {
+    &self.undo
+  }
+
+  // Steps that can be redone, the next one to redo last.
+  pub fn redo_steps(&self) -> &[Step] {
+    &self.redo
+  }
+
+  pub fn can_undo(&self) -> bool {
+    !self.undo.is_empty()
+  }
+
+  pub fn can_redo(&self) -> bool {
+    !self.redo.is_empty()
+  }
+
+  // Keeps a copy of the target from before it is edited, it has to be called
+  // before the edit for `edited` to record it.
+  pub fn watch(&mut self, world: &World, target: Target) {
+    if self.watched.iter().any(|(watched, _)| *watched == target) {
+      return;
+    }
+
+    if let Some(value) = Value::of(world, target) {
+      self.watched.push((target, value));
+    }
+  }
+
+  pub fn edited(&mut self, target: Target) {
+    if !self.edited.contains(&target) && self.watched.iter().any(|(watched, _)| *watched == target) {
+      self.edited.push(target);
+    }
+  }
+
+  // Records a one off edit, like a button press, to the given targets.
+  pub fn change(&mut self, world: &mut World, targets: &[Target], edit: impl FnOnce(&mut World)) {
+    for target in targets {
+      self.watch(world, *target);
+    }
+
+    edit(world);
+
+    for target in targets {
+      self.edited(*target);
+    }
+  }
+
+  pub fn execute(&mut self, world: &mut World, command: Command) {
+    // Edits so far are recorded first, as the indices they use may move.
+    self.capture(world);
+    self.watched.clear();
+
+    let change = match command {
+      Command::AddObject(object) => {
+        world.objects_mut().push(object);
+        Change::Object(world.objects().len() - 1, None)
+      },
+      Command::RemoveObject(index) => {
+        let animation = world.animation().clone();
+
+        match world.remove_object(index) {
+          Some(object) => Change::Object(index, Some((object, animation))),
+          None => return,
+        }
+      },
+      Command::AddLight(light) => {
+        world.lights_mut().push(light);
+        Change::Light(world.lights().len() - 1, None)
+      },
+      Command::RemoveLight(index) => {
+        let animation = world.animation().clone();
+
+        match world.remove_light(index) {
+          Some(light) => Change::Light(index, Some((light, animation))),
+          None => return,
+        }
+      },
+      Command::MoveObject(from, to) if from != to && from.max(to) < world.objects().len() => {
+        world.move_object(from, to);
+        Change::MoveObject(to, from)
+      },
+      Command::MoveLight(from, to) if from != to && from.max(to) < world.lights().len() => {
+        world.move_light(from, to);
+        Change::MoveLight(to, from)
+      },
+      Command::MoveObject(..) | Command::MoveLight(..) => return,
+    };
+
+    self.pending.push(change);
+  }
+
+  // Records the edits made since the last call as one step once `continuing`
+  // is unset, so a drag or typing into a field is one step however many
+  // frames it takes.
+  pub fn update(&mut self, world: &World, continuing: bool) {
+    let frame_changed = world.frame() != self.frame;
+    self.frame = world.frame();
+
+    // Playback moves animated things and the camera without being recorded,
+    // so copies of those are taken again unless they are being edited.
+    let edited = &self.edited;
+
+    self.watched.retain(|(target, value)| {
+      edited.contains(target) || match target {
+        Target::Camera => value.matches(world, *target),
+        _ => !frame_changed || !animated(world, *target),
+      }
+    });
+
+    if !continuing {
+      self.commit(world);
+    }
+  }
+
+  // Records the scene being replaced, by opening a file for example. `before`
+  // is the scene as it was, edits still open in it are recorded first.
+  pub fn replaced(&mut self, world: &World, before: World, label: &str) {
+    self.commit(&before);
+    self.frame = world.frame();
+
+    let unchanged = world.same_scene(&before) &&
+      world.materials() == before.materials() &&
+      world.animation() == before.animation();
+
+    if !unchanged {
+      self.push(String::from(label), vec![Change::Scene(Box::new(before))]);
+    }
+  }
+
+  pub fn undo(&mut self, world: &mut World) -> bool {
+    self.commit(world);
+
+    match self.undo.pop() {
+      Some(mut step) => {
+        for change in step.changes.iter_mut().rev() {
+          change.swap(world);
+        }
+
+        self.redo.push(step);
+        self.restored(world);
+        true
+      },
+      None => false,
+    }
+  }
+
+  pub fn redo(&mut self, world: &mut World) -> bool {
+    self.commit(world);
+
+    match self.redo.pop() {
+      Some(mut step) => {
+        for change in step.changes.iter_mut() {
+          change.swap(world);
+        }
+
+        self.undo.push(step);
+        self.restored(world);
+        true
+      },
+      None => false,
+    }
+  }
+
+  // Undoes or redoes until `steps` steps are applied.
+  pub fn jump(&mut self, world: &mut World, steps: usize) {
+    while self.undo.len() > steps && self.undo(world) {}
+    while self.undo.len() < steps && self.redo(world) {}
+  }
+
+  // Turns the watched targets that were edited into changes.
+  fn capture(&mut self, world: &World) {
+    for target in std::mem::take(&mut self.edited) {
+      let index = match self.watched.iter().position(|(watched, _)| *watched == target) {
+        Some(index) => index,
+        None => continue,
+      };
+
+      let (_, before) = self.watched.remove(index);
+
+      if !before.matches(world, target) {
+        self.pending.push(Change::Edit(target, before));
+      }
+    }
+  }
+
+  fn commit(&mut self, world: &World) {
+    self.capture(world);
+
+    if !self.pending.is_empty() {
+      let changes = std::mem::take(&mut self.pending);
+      self.push(describe(&changes), changes);
+    }
+  }
+
+  fn push(&mut self, label: String, changes: Vec<Change>) {
+    self.undo.push(Step { label, changes });
+    self.redo.clear();
+
+    // The step may have changed what other windows were watching.
+    self.watched.clear();
+
+    if self.undo.len() > HISTORY_LIMIT {
+      self.undo.remove(0);
+    }
+  }
+
+  fn restored(&mut self, world: &mut World) {
+    self.watched.clear();
+    self.frame = world.frame();
+
+    let selected = match world.selection() {
+      Some(Selection::Object(index)) => index < world.objects().len(),
+      Some(Selection::Light(index)) => index < world.lights().len(),
+      _ => true,
+    };
+
+    if !selected {
+      world.select(None);
+    }
+  }
+}
+
+fn camera_settings(camera_info: &CameraInfo) -> CameraInfo {
+  CameraInfo { position: Vector3::new(0.0, 0.0, 0.0), forward: Vector3::new(0.0, 0.0, 0.0), ..*camera_info }
+}
+
+fn material_names(world: &World) -> Vec<Option<String>> {
+  world.objects().iter().map(|object| object.material_name().map(String::from)).collect()
+}
+
+// Whether playing the animation changes the target.
+fn animated(world: &World, target: Target) -> bool {
+  let tracks = &world.animation().tracks;
+
+  match target {
+    Target::Object(index) => tracks.iter().any(|track| track.object() == Some(index)),
+    Target::Light(index) => tracks.iter().any(|track| track.light() == Some(index)),
+    // Animated colors detach objects from their named materials.
+    Target::Materials => tracks.iter().any(|track| matches!(track, Track::ObjectColor { .. })),
+    Target::Camera | Target::Animation => false,
+  }
+}
+
+fn describe(changes: &[Change]) -> String {
+  let mut labels: Vec<String> = changes.iter().map(Change::label).collect();
+  labels.dedup();
+
+  match labels.len() {
+    1 => labels.remove(0),
+    _ => String::from("Edit Scene"),
+  }
+}
\ No newline at end of file
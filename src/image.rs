@@ -0,0 +1,53 @@
+use std::{fs::File, io::{self, BufReader, BufWriter}, path::Path};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+  pub width: u32,
+  pub height: u32,
+  pub pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+  pub fn new(width: u32, height: u32, pixels: Vec<[u8; 3]>) -> Image {
+    Image {
+      width,
+      height,
+      pixels,
+    }
+  }
+
+  pub fn load_png(path: &Path) -> io::Result<Image> {
+    let decoder = png::Decoder::new(BufReader::new(File::open(path)?));
+    let mut reader = decoder.read_info().map_err(to_io_error)?;
+
+    let (width, height) = (reader.info().width, reader.info().height);
+
+    if reader.info().color_type != png::ColorType::Rgb || reader.info().bit_depth != png::BitDepth::Eight {
+      return Err(io::Error::new(io::ErrorKind::InvalidData, "expected an 8-bit RGB png"));
+    }
+
+    let mut data = vec![0; width as usize * height as usize * 3];
+    reader.next_frame(&mut data).map_err(to_io_error)?;
+
+    let pixels = data.chunks_exact(3).map(|pixel| [pixel[0], pixel[1], pixel[2]]).collect();
+
+    Ok(Image::new(width, height, pixels))
+  }
+
+  pub fn save_png(&self, path: &Path) -> io::Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+
+    let mut encoder = png::Encoder::new(file, self.width, self.height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header().map_err(to_io_error)?;
+    let data: Vec<u8> = self.pixels.iter().flat_map(|pixel| *pixel).collect();
+
+    writer.write_image_data(&data).map_err(to_io_error)
+  }
+}
+
+pub(crate) fn to_io_error(err: impl std::error::Error + Send + Sync + 'static) -> io::Error {
+  io::Error::other(err)
+}
\ No newline at end of file
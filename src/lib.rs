@@ -8,11 +8,27 @@ use gui::GUI;
 
 mod gui;
 mod graphics;
-mod world;
-mod renderer;
-mod vector;
+pub mod world;
+pub mod renderer;
+mod render_thread;
+pub mod packet;
+pub mod vector;
+pub mod image;
+pub mod scene_file;
+mod scene_text;
+pub mod gltf_import;
+pub mod validation;
+pub mod headless;
 mod event_manager;
-mod quaternion;
+pub mod quaternion;
+pub mod animation;
+pub mod camera_path;
+pub mod video;
+pub mod history;
+pub mod stats;
+pub mod comparison;
+pub mod bindings;
+pub mod outliner;
 
 pub fn run() {
   let event_loop = EventLoopBuilder::with_user_event().build();
@@ -20,8 +36,9 @@ pub fn run() {
 
   let mut egui_glium = EguiGlium::new(&display, &event_loop);
 
-  let mut gui = GUI::new();
   let mut graphics = Graphics::new(&display);
+  let mut gui = GUI::new(graphics.world());
+  gui.load_bindings(graphics.bindings_mut());
 
   let mut event_manager = EventManager::new();
 
@@ -29,16 +46,21 @@ pub fn run() {
 
   event_loop.run(move |event, _, control_flow| {
     event_manager.update(&event, consumed);
-    graphics.world().update(&event_manager);
+    graphics.update(&event_manager);
+    gui.update(&event_manager, &mut graphics);
 
     let mut redraw = || {
       let mut target = display.draw();
 
-      egui_glium.run(&display, |ctx| gui.ui(ctx, graphics.world()));
+      egui_glium.run(&display, |ctx| gui.ui(ctx, &mut graphics));
 
-      graphics.world().update(&event_manager);
+      graphics.update(&event_manager);
       graphics.draw(&mut target, &display);
 
+      if let Some(stats) = graphics.take_stats() {
+        gui.update_stats(stats);
+      }
+
       egui_glium.paint(&display, &mut target);
 
       display.gl_window().window().request_redraw();
@@ -1,3 +1,68 @@
+use std::{env, fmt::Display, path::Path, process, time::Duration};
+
+use ray_tracer::{headless, validation, world::World};
+
 fn main() {
-  ray_tracer::run();
+  let args: Vec<String> = env::args().skip(1).collect();
+
+  match args.as_slice() {
+    [command, scene, output] if command == "render" => {
+      let mut world = load_valid(scene);
+      exit_on_error(headless::render(&mut world, Path::new(output)));
+    },
+    [command, scene, output] if command == "convert" => {
+      exit_on_error(headless::convert(Path::new(scene), Path::new(output)));
+    },
+    [command, scene, output, flag, frames] if command == "render" && flag == "--frames" => {
+      let frames = match frames.split_once('-').map(|(start, end)| (start.parse::<u32>(), end.parse::<u32>())) {
+        Some((Ok(start), Ok(end))) if start <= end => start..=end,
+        _ => {
+          eprintln!("invalid frame range \"{}\", expected START-END", frames);
+          process::exit(1);
+        },
+      };
+
+      let mut world = load_valid(scene);
+      exit_on_error(headless::render_frames(&mut world, Path::new(output), frames));
+    },
+    [command, scene, path] if command == "replay" => {
+      let mut world = load_valid(scene);
+      let times = exit_on_error(headless::replay(&mut world, Path::new(path)));
+
+      for (index, time) in times.iter().enumerate() {
+        println!("frame {}: {:.2} ms", index, time.as_secs_f64() * 1000.0);
+      }
+
+      let total: Duration = times.iter().sum();
+
+      if !times.is_empty() {
+        println!("{} frames in {:.2} s, {:.2} ms per frame", times.len(), total.as_secs_f64(), total.as_secs_f64() * 1000.0 / times.len() as f64);
+      }
+    },
+    _ => ray_tracer::run(),
+  }
+}
+
+// Prints the scene's problems and stops before rendering if any is an error.
+fn load_valid(scene: &str) -> World {
+  let world = exit_on_error(headless::load(Path::new(scene)));
+  let problems = validation::validate(&world);
+
+  for problem in &problems {
+    eprintln!("{}: {}", problem.severity.to_string(), problem.to_string());
+  }
+
+  if validation::has_errors(&problems) {
+    eprintln!("{} has errors, not rendering", scene);
+    process::exit(1);
+  }
+
+  world
+}
+
+fn exit_on_error<T, E: Display>(result: Result<T, E>) -> T {
+  result.unwrap_or_else(|err| {
+    eprintln!("{}", err);
+    process::exit(1);
+  })
 }
\ No newline at end of file
@@ -0,0 +1,125 @@
+use crate::{world::{World, Selection, Node}, history::{History, Command}};
+
+// The entries checked in the outliner, kept by index. Adding or removing
+// anything shifts the indices, and so can undo or redo, so both clear them.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Checked {
+  selections: Vec<Selection>,
+  synced: (usize, usize, usize),
+}
+
+impl Checked {
+  pub fn new() -> Checked {
+    Checked::default()
+  }
+
+  // Called before the checks are used each frame.
+  pub fn sync(&mut self, world: &World, history: &History) {
+    let synced = (world.objects().len(), world.lights().len(), history.redo_steps().len());
+
+    if synced != self.synced {
+      self.selections.clear();
+      self.synced = synced;
+    }
+  }
+
+  pub fn contains(&self, selection: Selection) -> bool {
+    self.selections.contains(&selection)
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.selections.is_empty()
+  }
+
+  pub fn set(&mut self, selection: Selection, checked: bool) {
+    self.selections.retain(|existing| *existing != selection);
+
+    if checked {
+      self.selections.push(selection);
+    }
+  }
+
+  pub fn clear(&mut self) {
+    self.selections.clear();
+  }
+
+  // Toggling a checked entry changes every checked entry.
+  pub fn batch(&self, world: &World, selection: Selection) -> Vec<Selection> {
+    if self.contains(selection) {
+      self.existing(world)
+    } else {
+      vec![selection]
+    }
+  }
+
+  // Checked entries, or the selected one when nothing is checked.
+  pub fn targets(&self, world: &World) -> Vec<Selection> {
+    if !self.is_empty() {
+      return self.existing(world);
+    }
+
+    match world.selection() {
+      Some(selection) if exists(world, selection) => vec![selection],
+      _ => Vec::new(),
+    }
+  }
+
+  // Copies go to the end of the lists so animation tracks keep their indices.
+  // Several copies stay checked, a single one is only selected.
+  pub fn duplicate(&mut self, world: &mut World, history: &mut History, targets: &[Selection]) {
+    let mut copies = Vec::new();
+
+    for selection in targets {
+      if !exists(world, *selection) {
+        continue;
+      }
+
+      let copy = match *selection {
+        Selection::Object(index) => {
+          let mut object = world.objects()[index].clone();
+          copy_name(object.node_mut());
+          history.execute(world, Command::AddObject(object));
+
+          Selection::Object(world.objects().len() - 1)
+        },
+        Selection::Light(index) => {
+          let mut light = world.lights()[index].clone();
+          copy_name(light.node_mut());
+          history.execute(world, Command::AddLight(light));
+
+          Selection::Light(world.lights().len() - 1)
+        },
+        Selection::Camera => continue,
+      };
+
+      copies.push(copy);
+    }
+
+    if copies.is_empty() {
+      return;
+    }
+
+    world.select(copies.last().copied());
+
+    self.selections = if copies.len() > 1 { copies } else { Vec::new() };
+    self.synced = (world.objects().len(), world.lights().len(), history.redo_steps().len());
+  }
+
+  fn existing(&self, world: &World) -> Vec<Selection> {
+    self.selections.iter().copied().filter(|selection| exists(world, *selection)).collect()
+  }
+}
+
+pub fn exists(world: &World, selection: Selection) -> bool {
+  match selection {
+    Selection::Object(index) => index < world.objects().len(),
+    Selection::Light(index) => index < world.lights().len(),
+    Selection::Camera => false,
+  }
+}
+
+fn copy_name(node: &mut Node) {
+  if let Some(name) = &node.name {
+    node.name = Some(format!("{} Copy", name));
+  }
+}
\ No newline at end of file
@@ -0,0 +1,81 @@
+use crate::{world::{Ray, Sphere}, vector::Float};
+
+pub const LANES: usize = 8;
+
+type Lanes = [Float; LANES];
+
+pub struct RayPacket {
+  position: [Lanes; 3],
+  direction: [Lanes; 3],
+  direction_dot: Lanes,
+}
+
+impl RayPacket {
+  pub fn new(rays: &[Ray]) -> RayPacket {
+    let mut packet = RayPacket {
+      position: [[0.0; LANES]; 3],
+      direction: [[0.0; LANES]; 3],
+      direction_dot: [0.0; LANES],
+    };
+
+    for (lane, ray) in rays.iter().take(LANES).enumerate() {
+      packet.position[0][lane] = ray.position.x;
+      packet.position[1][lane] = ray.position.y;
+      packet.position[2][lane] = ray.position.z;
+
+      packet.direction[0][lane] = ray.direction.x;
+      packet.direction[1][lane] = ray.direction.y;
+      packet.direction[2][lane] = ray.direction.z;
+
+      packet.direction_dot[lane] = ray.direction.dot(&ray.direction);
+    }
+
+    packet
+  }
+
+  pub fn trace_sphere(&self, sphere: &Sphere) -> [[Option<Float>; 2]; LANES] {
+    let [px, py, pz] = &self.position;
+    let [dx, dy, dz] = &self.direction;
+    let a = &self.direction_dot;
+
+    let radius_squared = sphere.radius.powi(2);
+
+    let mut b = [0.0; LANES];
+    let mut discriminant = [0.0; LANES];
+    let mut any_hit = false;
+
+    for i in 0..LANES {
+      let ox = px[i] - sphere.position.x;
+      let oy = py[i] - sphere.position.y;
+      let oz = pz[i] - sphere.position.z;
+
+      b[i] = (ox * dx[i] + oy * dy[i] + oz * dz[i]) * 2.0;
+      let c = (ox * ox + oy * oy + oz * oz) - radius_squared;
+
+      discriminant[i] = b[i] * b[i] - 4.0 * a[i] * c;
+      any_hit |= discriminant[i] >= 0.0 || discriminant[i].is_nan();
+    }
+
+    if !any_hit {
+      return [[None, None]; LANES];
+    }
+
+    std::array::from_fn(|i| {
+      if discriminant[i] < 0.0 {
+        return [None, None];
+      }
+
+      let root = discriminant[i].sqrt();
+      let t1 = (-b[i] + root) / (2.0 * a[i]);
+      let t2 = (-b[i] - root) / (2.0 * a[i]);
+
+      if t1 < 0.01 {
+        [None, None]
+      } else if t2 < 0.01 {
+        [Some(t1), None]
+      } else {
+        [Some(t1), Some(t2)]
+      }
+    })
+  }
+}
\ No newline at end of file
@@ -1,13 +1,13 @@
-use std::{ops::Mul, f64::consts::PI};
+use std::ops::Mul;
 
-use crate::vector::Vector3;
+use crate::vector::{Vector3, Float, consts::PI};
 
 #[derive(Clone, Copy)]
 pub struct Quaternion {
-  w: f64,
-  x: f64,
-  y: f64,
-  z: f64,
+  w: Float,
+  x: Float,
+  y: Float,
+  z: Float,
 }
 
 impl Mul for Quaternion {
@@ -24,7 +24,7 @@ impl Mul for Quaternion {
 }
 
 impl Quaternion {
-  pub fn new(w: f64, x: f64, y: f64, z: f64) -> Quaternion {
+  pub fn new(w: Float, x: Float, y: Float, z: Float) -> Quaternion {
     Quaternion { w, x, y, z }
   }
 
@@ -37,15 +37,16 @@ impl Quaternion {
     )
   }
 
-  pub fn from_angle_axis(mut angle: f64, axis: Vector3) -> Quaternion {
-    while angle < 0.0 {
-      angle += PI * 2.0;
-    }
+  pub fn from_angle_axis(angle: Float, axis: Vector3) -> Quaternion {
+    let mag = axis.mag();
 
-    while angle >= PI * 2.0 {
-      angle -= PI * 2.0;
+    if mag == 0.0 {
+      return Quaternion::new(1.0, 0.0, 0.0, 0.0);
     }
 
+    let axis = axis / mag;
+    let angle = angle.rem_euclid(PI * 2.0);
+
     Quaternion::new(
       (angle/2.0).cos(),
       axis.x * (angle/2.0).sin(),
@@ -61,4 +62,8 @@ impl Quaternion {
   pub fn to_vector_3(&self) -> Vector3 {
     Vector3::new(self.x, self.y, self.z)
   }
+
+  pub fn rotate(&self, vector: Vector3) -> Vector3 {
+    (*self * Quaternion::from_vector_3(vector) * self.inverse()).to_vector_3()
+  }
 }
\ No newline at end of file
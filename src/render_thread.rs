@@ -0,0 +1,111 @@
+use std::{sync::mpsc::{self, Sender, Receiver, TryRecvError}, thread, time::{Duration, Instant}};
+
+use crate::{renderer::Renderer, world::World, stats::RenderStats};
+
+const ROWS_PER_BAND: u32 = 16;
+const PARTIAL_FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+pub struct RenderedFrame {
+  pub image_buffer: Vec<[u8; 3]>,
+  pub image_width: u32,
+  pub image_height: u32,
+  pub complete: bool,
+  pub render_time: Duration,
+  pub stats: Option<RenderStats>,
+  // Which object every pixel shows, sent with complete frames for the selection outline.
+  pub object_ids: Option<Vec<Option<usize>>>,
+}
+
+pub struct RenderThread {
+  world_sender: Sender<World>,
+  frame_receiver: Receiver<RenderedFrame>,
+}
+
+impl RenderThread {
+  pub fn new() -> RenderThread {
+    let (world_sender, world_receiver) = mpsc::channel();
+    let (frame_sender, frame_receiver) = mpsc::channel();
+
+    thread::Builder::new()
+      .name(String::from("renderer"))
+      .spawn(move || RenderThread::run(world_receiver, frame_sender))
+      .unwrap();
+
+    RenderThread {
+      world_sender,
+      frame_receiver,
+    }
+  }
+
+  pub fn render(&self, world: World) {
+    self.world_sender.send(world).ok();
+  }
+
+  pub fn poll(&self) -> Option<RenderedFrame> {
+    self.frame_receiver.try_iter().last()
+  }
+
+  fn run(world_receiver: Receiver<World>, frame_sender: Sender<RenderedFrame>) {
+    let mut renderer = Renderer::new();
+
+    let mut next = match world_receiver.recv() {
+      Ok(world) => Some(world),
+      Err(_) => return,
+    };
+
+    while let Some(mut world) = next.take() {
+      while let Ok(newer) = world_receiver.try_recv() {
+        world = newer;
+      }
+
+      let start = Instant::now();
+
+      renderer.prepare(&mut world);
+
+      let height = world.camera().camera_info().viewport_height;
+      let mut last_post = Instant::now();
+      let mut row = 0;
+
+      while row < height {
+        match world_receiver.try_recv() {
+          Ok(newer) => {
+            next = Some(newer);
+            break;
+          },
+          Err(TryRecvError::Disconnected) => return,
+          Err(TryRecvError::Empty) => {},
+        }
+
+        let band_end = (row + ROWS_PER_BAND).min(height);
+        renderer.render_rows(&world, row..band_end);
+        row = band_end;
+
+        let complete = row == height;
+
+        if complete || last_post.elapsed() >= PARTIAL_FRAME_INTERVAL {
+          let data = renderer.rendered_data();
+
+          let frame = RenderedFrame {
+            image_buffer: data.image_buffer.clone(),
+            image_width: data.image_width,
+            image_height: data.image_height,
+            complete,
+            render_time: start.elapsed(),
+            stats: complete.then(|| renderer.stats()),
+            object_ids: complete.then(|| renderer.rendered_object_ids().to_vec()),
+          };
+
+          if frame_sender.send(frame).is_err() {
+            return;
+          }
+
+          last_post = Instant::now();
+        }
+      }
+
+      if next.is_none() {
+        next = world_receiver.recv().ok();
+      }
+    }
+  }
+}
\ No newline at end of file
@@ -1,8 +1,8 @@
 
-use std::{ops::Neg};
+use std::{ops::{Neg, Range}, time::{Instant, Duration}};
 use rayon::prelude::*;
 
-use crate::{world::{World, Object, Ray, RGB, RayIntersection, Light}, vector::Vector3};
+use crate::{world::{World, Object, Ray, RGB, RayIntersection, Light, Sphere, Plane, Mesh, Material}, vector::{Vector3, Float}, packet::{RayPacket, LANES}, image::Image, stats::{PixelStats, RenderStats}};
 
 pub struct RenderedData<'a> {
   pub image_buffer: &'a Vec<[u8; 3]>,
@@ -10,10 +10,27 @@ pub struct RenderedData<'a> {
   pub image_height: u32,
 }
 
+impl<'a> RenderedData<'a> {
+  pub fn to_image(&self) -> Image {
+    let pixels = self.image_buffer
+      .chunks_exact(self.image_width as usize)
+      .rev()
+      .flat_map(|row| row.iter().copied())
+      .collect();
+
+    Image::new(self.image_width, self.image_height, pixels)
+  }
+}
+
 pub struct Renderer {
   image_buffer: Vec<[u8; 3]>,
   image_width: u32,
   image_height: u32,
+  packet_tracing: bool,
+  pixel_stats: Vec<PixelStats>,
+  object_ids: Vec<Option<usize>>,
+  ray_generation: Duration,
+  tracing: Duration,
 }
 
 impl Renderer {
@@ -22,30 +39,141 @@ impl Renderer {
       image_buffer: Vec::new(),
       image_width: 600,
       image_height: 400,
+      packet_tracing: true,
+      pixel_stats: Vec::new(),
+      object_ids: Vec::new(),
+      ray_generation: Duration::ZERO,
+      tracing: Duration::ZERO,
     }
   }
 
-  pub fn render(&mut self, world: &mut World) -> RenderedData {
+  pub fn packet_tracing_mut(&mut self) -> &mut bool {
+    &mut self.packet_tracing
+  }
+
+  pub fn render(&mut self, world: &mut World) -> RenderedData<'_> {
+    self.prepare(world);
+    self.render_rows(world, 0..self.image_height);
+
+    self.rendered_data()
+  }
+
+  pub fn prepare(&mut self, world: &mut World) {
     self.image_width = world.camera().camera_info().viewport_width;
     self.image_height = world.camera().camera_info().viewport_height;
 
-    let rays = world.camera().rays();
+    let instant = Instant::now();
+    world.camera_mut().calc_rays();
+
+    self.ray_generation = instant.elapsed();
+    self.tracing = Duration::ZERO;
+
     let buffer_size = (self.image_width * self.image_height) as usize;
 
     if self.image_buffer.len() != buffer_size {
       self.image_buffer.resize(buffer_size, [0, 0, 0]);
     }
 
+    if self.pixel_stats.len() != buffer_size {
+      self.pixel_stats.resize(buffer_size, PixelStats::new());
+    }
+
+    if self.object_ids.len() != buffer_size {
+      self.object_ids.resize(buffer_size, None);
+    }
+  }
+
+  pub fn render_rows(&mut self, world: &World, rows: Range<u32>) {
+    let start = (rows.start * self.image_width) as usize;
+    let end = ((rows.end * self.image_width) as usize).min(self.image_buffer.len());
+
+    if start >= end {
+      return;
+    }
+
+    let instant = Instant::now();
+
+    let rays = &world.camera().rays()[start..end];
     let depth = *world.camera().depth();
+    let pixels = &mut self.image_buffer[start..end];
+    let stats = &mut self.pixel_stats[start..end];
+    let object_ids = &mut self.object_ids[start..end];
+
+    if self.packet_tracing {
+      pixels.par_chunks_mut(LANES).zip(stats.par_chunks_mut(LANES)).zip(object_ids.par_chunks_mut(LANES)).zip(rays.par_chunks(LANES)).for_each(|(((pixels, stats), object_ids), rays)| {
+        if rays.len() < LANES {
+          for (((pixel, stats), object_id), ray) in pixels.iter_mut().zip(stats).zip(object_ids).zip(rays) {
+            Renderer::write_pixel(pixel, Renderer::trace_primary(ray, world, depth, stats, object_id));
+          }
 
-    self.image_buffer.par_iter_mut().zip(rays).for_each(|(pixel, ray)| {
-      let color = Renderer::trace_ray_color(ray, world, depth);
+          return;
+        }
+
+        stats.fill(PixelStats { primary_rays: 1, ..PixelStats::new() });
+
+        let hits = Renderer::trace_packet_counted(&RayPacket::new(rays), rays, world, stats);
 
-      *pixel.get_mut(0).unwrap() = (color[0] * 255.0) as u8;
-      *pixel.get_mut(1).unwrap() = (color[1] * 255.0) as u8;
-      *pixel.get_mut(2).unwrap() = (color[2] * 255.0) as u8;
-    });
+        for ((((pixel, stats), object_id), ray), closest) in pixels.iter_mut().zip(stats).zip(object_ids).zip(rays).zip(hits) {
+          *object_id = closest.as_ref().and_then(|hit| Renderer::object_index(world, hit));
+          Renderer::write_pixel(pixel, Renderer::shade(ray, closest, world, depth, stats));
+        }
+      });
+    } else {
+      pixels.par_iter_mut().zip(stats.par_iter_mut()).zip(object_ids.par_iter_mut()).zip(rays).for_each(|(((pixel, stats), object_id), ray)| {
+        Renderer::write_pixel(pixel, Renderer::trace_primary(ray, world, depth, stats, object_id));
+      });
+    }
 
+    self.tracing += instant.elapsed();
+  }
+
+  // Also notes which object the camera ray hit, for outlining the selection.
+  fn trace_primary(ray: &Ray, world: &World, depth: u32, stats: &mut PixelStats, object_id: &mut Option<usize>) -> RGB {
+    *stats = PixelStats { primary_rays: 1, ..PixelStats::new() };
+
+    let closest = Renderer::trace_ray_counted(ray, world, stats);
+    *object_id = closest.as_ref().and_then(|hit| Renderer::object_index(world, hit));
+
+    Renderer::shade(ray, closest, world, depth, stats)
+  }
+
+  fn write_pixel(pixel: &mut [u8; 3], color: RGB) {
+    pixel[0] = (color[0] * 255.0) as u8;
+    pixel[1] = (color[1] * 255.0) as u8;
+    pixel[2] = (color[2] * 255.0) as u8;
+  }
+
+  // A sphere with the material on a grey floor, seen up close.
+  pub fn material_preview(material: &Material, size: u32) -> Image {
+    let mut world = World::new();
+
+    *world.objects_mut()[0].material_mut() = *material;
+    world.objects_mut()[1].material_mut().color = [0.6, 0.6, 0.6];
+
+    let camera_info = world.camera_mut().camera_info_mut();
+    camera_info.position = Vector3::new(0.0, 0.0, 4.5);
+    camera_info.vertical_fov = 0.5;
+    camera_info.viewport_width = size;
+    camera_info.viewport_height = size;
+
+    Renderer::new().render(&mut world).to_image()
+  }
+
+  // Counts for the pixels rendered since the last prepare, the texture upload is left for the caller to time.
+  pub fn stats(&self) -> RenderStats {
+    let mut stats = RenderStats::new(self.image_width, self.image_height, &self.pixel_stats);
+    stats.ray_generation = self.ray_generation;
+    stats.tracing = self.tracing;
+
+    stats
+  }
+
+  // The object each pixel's camera ray hit first, in the order of the image buffer.
+  pub fn rendered_object_ids(&self) -> &[Option<usize>] {
+    &self.object_ids
+  }
+
+  pub fn rendered_data(&self) -> RenderedData<'_> {
     RenderedData { 
       image_buffer: &self.image_buffer,
       image_width: self.image_width, 
@@ -54,70 +182,107 @@ impl Renderer {
   }
 
   pub fn trace_ray<'a>(ray: &'a Ray, world: &'a World) -> Option<RayIntersection<'a>> {
+    Renderer::trace_ray_counted(ray, world, &mut PixelStats::new())
+  }
+
+  fn trace_ray_counted<'a>(ray: &'a Ray, world: &'a World, stats: &mut PixelStats) -> Option<RayIntersection<'a>> {
     let mut closest: Option<RayIntersection> = None;
 
-    for object in world.objects() {
+    for object in world.objects().iter().filter(|object| !object.node().hidden) {
+      let intersection = match object {
+        Object::Sphere(sphere_data) => {
+          stats.intersection_tests += 1;
+          Renderer::sphere_intersection(ray, sphere_data, sphere_data.trace_ray(ray))
+        },
+        Object::Plane(plane_data) => {
+          stats.intersection_tests += 1;
+          Renderer::plane_intersection(ray, plane_data)
+        },
+        Object::Mesh(mesh_data) => {
+          Renderer::mesh_intersection(ray, mesh_data, &mut stats.intersection_tests)
+        }
+      };
+
+      closest = Renderer::closer(closest, intersection);
+    }
+
+    closest
+  }
+
+  pub fn pick(world: &World, x: Float, y: Float) -> Option<usize> {
+    let ray = world.camera().ray(x, y);
+
+    Renderer::trace_ray(&ray, world).and_then(|hit| Renderer::object_index(world, &hit))
+  }
+
+  // Colors the pixels of the object that border anything else.
+  pub fn draw_outline(image_buffer: &mut [[u8; 3]], object_ids: &[Option<usize>], width: u32, object: usize, color: [u8; 3]) {
+    let width = width as usize;
+    let height = object_ids.len() / width.max(1);
+
+    for y in 0..height {
+      for x in 0..width {
+        let i = y * width + x;
+
+        if object_ids[i] != Some(object) {
+          continue;
+        }
+
+        let edge = x == 0 || y == 0 || x == width - 1 || y == height - 1 ||
+          [i - 1, i + 1, i - width, i + width].iter().any(|j| object_ids[*j] != Some(object));
+
+        if edge {
+          image_buffer[i] = color;
+        }
+      }
+    }
+  }
+
+  // Intersections only keep the material, which is borrowed from the object that was hit.
+  fn object_index(world: &World, hit: &RayIntersection) -> Option<usize> {
+    world.objects().iter().position(|object| std::ptr::eq(object.material(), hit.material()))
+  }
+
+  pub fn trace_packet<'a>(packet: &RayPacket, rays: &'a [Ray], world: &'a World) -> [Option<RayIntersection<'a>>; LANES] {
+    Renderer::trace_packet_counted(packet, rays, world, &mut [PixelStats::new(); LANES])
+  }
+
+  fn trace_packet_counted<'a>(packet: &RayPacket, rays: &'a [Ray], world: &'a World, stats: &mut [PixelStats]) -> [Option<RayIntersection<'a>>; LANES] {
+    let mut closest: [Option<RayIntersection>; LANES] = std::array::from_fn(|_| None);
+
+    for object in world.objects().iter().filter(|object| !object.node().hidden) {
       match object {
         Object::Sphere(sphere_data) => {
-          let [t1, t2] = sphere_data.trace_ray(ray);
-
-          let (t, n1, n2) = {
-              if let Some(t2) = t2 {
-                (t2, 1.0, sphere_data.material.refractive_index)
-              } else if let Some(t1) = t1 {
-                (t1, sphere_data.material.refractive_index, 1.0)
-              } else {
-                continue;
-              }
-          };
+          let hits = packet.trace_sphere(sphere_data);
 
-          let intersection = ray.position_from_distance(t);
+          for stats in stats.iter_mut() {
+            stats.intersection_tests += 1;
+          }
 
-          let normal = if t == t1.unwrap() {
-            (sphere_data.position - intersection).normalise()
-          } else {
-            (intersection - sphere_data.position).normalise()
-          };
+          for (lane, hit) in hits.into_iter().enumerate() {
+            let t = match hit {
+              [_, Some(t2)] => t2,
+              [Some(t1), None] => t1,
+              _ => continue,
+            };
 
-          match &closest {
-            Some(val) => {
-              if t < *val.distance() {
-                closest = Some(
-                  RayIntersection::new(ray, &sphere_data.material, t, normal, n1, n2)
-                )
-              }
-            },
-            None => {
-              closest = Some(
-                RayIntersection::new(ray, &sphere_data.material, t, normal, n1, n2)
-              );
+            if closest[lane].as_ref().is_none_or(|val| t < *val.distance()) {
+              closest[lane] = Renderer::sphere_intersection(&rays[lane], sphere_data, hit);
             }
           }
         },
         Object::Plane(plane_data) => {
-          let t = plane_data.trace_ray(ray);
+          for (lane, ray) in rays.iter().enumerate() {
+            stats[lane].intersection_tests += 1;
 
-          if t.is_none() {
-            continue;
+            let intersection = Renderer::plane_intersection(ray, plane_data);
+            closest[lane] = Renderer::closer(closest[lane].take(), intersection);
           }
-
-          let t = t.unwrap();
-
-          let up = Vector3::new(0.0, 1.0, 0.0);
-
-          match &closest {
-            Some(val) => {
-              if t < *val.distance() {
-                closest = Some(
-                  RayIntersection::new(ray, &plane_data.material, t, up, 1.0, 1.0)
-                )
-              }
-            },
-            None => {
-              closest = Some(
-                RayIntersection::new(ray, &plane_data.material, t, up, 1.0, 1.0)
-              );
-            }
+        },
+        Object::Mesh(mesh_data) => {
+          for (lane, ray) in rays.iter().enumerate() {
+            let intersection = Renderer::mesh_intersection(ray, mesh_data, &mut stats[lane].intersection_tests);
+            closest[lane] = Renderer::closer(closest[lane].take(), intersection);
           }
         }
       }
@@ -126,8 +291,75 @@ impl Renderer {
     closest
   }
 
-  pub fn trace_ray_color(ray: &Ray, world: &World, depth: u32) -> RGB {
-    let closest = Renderer::trace_ray(ray, world);
+  fn sphere_intersection<'a>(ray: &'a Ray, sphere_data: &'a Sphere, hit: [Option<Float>; 2]) -> Option<RayIntersection<'a>> {
+    let [t1, t2] = hit;
+
+    let (t, n1, n2) = {
+        if let Some(t2) = t2 {
+          (t2, 1.0, sphere_data.material.refractive_index)
+        } else if let Some(t1) = t1 {
+          (t1, sphere_data.material.refractive_index, 1.0)
+        } else {
+          return None;
+        }
+    };
+
+    let intersection = ray.position_from_distance(t);
+
+    let normal = if t == t1.unwrap() {
+      (sphere_data.position - intersection).normalise()
+    } else {
+      (intersection - sphere_data.position).normalise()
+    };
+
+    Some(RayIntersection::new(ray, &sphere_data.material, t, normal, n1, n2))
+  }
+
+  fn plane_intersection<'a>(ray: &'a Ray, plane_data: &'a Plane) -> Option<RayIntersection<'a>> {
+    let t = plane_data.trace_ray(ray)?;
+
+    let up = Vector3::new(0.0, 1.0, 0.0);
+
+    Some(RayIntersection::new(ray, &plane_data.material, t, up, 1.0, 1.0))
+  }
+
+  fn mesh_intersection<'a>(ray: &'a Ray, mesh_data: &'a Mesh, tests: &mut u32) -> Option<RayIntersection<'a>> {
+    let (t, normal) = mesh_data.trace_ray_counted(ray, tests)?;
+    let refractive_index = mesh_data.material.refractive_index;
+
+    // Meshes are treated as closed, so hitting a back face means the ray is leaving the mesh.
+    if normal.dot(&ray.direction) < 0.0 {
+      Some(RayIntersection::new(ray, &mesh_data.material, t, normal, 1.0, refractive_index))
+    } else {
+      Some(RayIntersection::new(ray, &mesh_data.material, t, -normal, refractive_index, 1.0))
+    }
+  }
+
+  fn closer<'a>(closest: Option<RayIntersection<'a>>, intersection: Option<RayIntersection<'a>>) -> Option<RayIntersection<'a>> {
+    match (closest, intersection) {
+      (Some(val), Some(intersection)) => {
+        if intersection.distance() < val.distance() {
+          Some(intersection)
+        } else {
+          Some(val)
+        }
+      },
+      (None, intersection) => intersection,
+      (closest, None) => closest,
+    }
+  }
+
+  pub fn trace_ray_color(ray: &Ray, world: &World, depth: u32, stats: &mut PixelStats) -> RGB {
+    let closest = Renderer::trace_ray_counted(ray, world, stats);
+
+    Renderer::shade(ray, closest, world, depth, stats)
+  }
+
+  pub fn shade(ray: &Ray, closest: Option<RayIntersection>, world: &World, depth: u32, stats: &mut PixelStats) -> RGB {
+    // Rays traced with no depth left don't add to the picture, so they don't make the path longer.
+    if depth > 0 {
+      stats.depth = stats.depth.max((*world.camera().depth() + 1).saturating_sub(depth));
+    }
 
     if depth == 0 || closest.is_none() {
       return world.camera().camera_info().miss_color;
@@ -143,7 +375,7 @@ impl Renderer {
     let mut specular = [0.0, 0.0, 0.0];
     let mut ambient = [0.0, 0.0, 0.0];
 
-    for light in world.lights() {
+    for light in world.lights().iter().filter(|light| !light.node().hidden) {
       match light {
         Light::Point(light) => {
           let light_ray = Ray {
@@ -151,7 +383,8 @@ impl Renderer {
             direction: (light.position - point).normalise(),
           };
 
-          let shadow_ray = Renderer::trace_ray(&light_ray, world);
+          stats.shadow_rays += 1;
+          let shadow_ray = Renderer::trace_ray_counted(&light_ray, world, stats);
 
           let has_shadow = shadow_ray.is_some();
 
@@ -183,7 +416,8 @@ impl Renderer {
             direction: light.direction.normalise(),
           };
 
-          let bounce = Renderer::trace_ray(&light_ray, world);
+          stats.shadow_rays += 1;
+          let bounce = Renderer::trace_ray_counted(&light_ray, world, stats);
 
           let shadow: f32 = if bounce.is_none() {
             0.3
@@ -243,7 +477,8 @@ impl Renderer {
         direction 
       };
 
-      let bounce_color = Renderer::trace_ray_color(&bounce_ray, world, depth-1);
+      stats.secondary_rays += 1;
+      let bounce_color = Renderer::trace_ray_color(&bounce_ray, world, depth-1, stats);
 
       color[0] = (1.0 - reflectivity) * color[0] + reflectivity * bounce_color[0];
       color[1] = (1.0 - reflectivity) * color[1] + reflectivity * bounce_color[1];
@@ -260,7 +495,8 @@ impl Renderer {
         direction
       };
 
-      let through_color = Renderer::trace_ray_color(&through_ray, world, depth-1);
+      stats.secondary_rays += 1;
+      let through_color = Renderer::trace_ray_color(&through_ray, world, depth-1, stats);
 
       color[0] = (1.0 - transparency) * color[0] + transparency * through_color[0];
       color[1] = (1.0 - transparency) * color[1] + transparency * through_color[1];
@@ -270,7 +506,7 @@ impl Renderer {
     color
   }
 
-  pub fn calc_snells_law(i: Vector3, normal: Vector3, n1: f64, n2: f64) -> Vector3 {
+  pub fn calc_snells_law(i: Vector3, normal: Vector3, n1: Float, n2: Float) -> Vector3 {
     let mu = n1/n2;
     let ni = normal.dot(&i);
 
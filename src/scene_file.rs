@@ -0,0 +1,425 @@
+use std::{fmt, fs::{self, File}, io::{self, BufWriter, Read, Write}, path::{Path, PathBuf}};
+
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Value, Map, error::Category};
+use serde_yaml::with::singleton_map_recursive;
+
+use crate::{world::{World, Object, Material}, gltf_import, scene_text};
+
+pub const FORMAT_VERSION: u64 = 1;
+
+const INCLUDE_KEY: &str = "include";
+
+const MIGRATIONS: [fn(&mut Map<String, Value>); FORMAT_VERSION as usize] = [
+  migrate_v0_to_v1,
+];
+
+#[derive(Debug)]
+pub enum SceneError {
+  Io(io::Error),
+  Parse {
+    line: usize,
+    column: usize,
+    message: String,
+  },
+  Validation(String),
+  Serialize(String),
+  Include {
+    path: PathBuf,
+    source: Box<SceneError>,
+  },
+}
+
+impl fmt::Display for SceneError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      SceneError::Io(err) => write!(f, "could not access scene file: {}", err),
+      SceneError::Parse { line: 0, message, .. } => write!(f, "invalid scene file: {}", message),
+      SceneError::Parse { line, column, message } => {
+        write!(f, "invalid scene file at line {}, column {}: {}", line, column, message)
+      },
+      SceneError::Validation(message) => write!(f, "invalid scene: {}", message),
+      SceneError::Serialize(message) => write!(f, "could not write scene: {}", message),
+      SceneError::Include { path, source } => write!(f, "{} (in {})", source, path.display()),
+    }
+  }
+}
+
+impl std::error::Error for SceneError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      SceneError::Io(err) => Some(err),
+      SceneError::Include { source, .. } => Some(source.as_ref()),
+      _ => None,
+    }
+  }
+}
+
+impl From<io::Error> for SceneError {
+  fn from(err: io::Error) -> Self {
+    SceneError::Io(err)
+  }
+}
+
+impl From<serde_json::Error> for SceneError {
+  fn from(err: serde_json::Error) -> Self {
+    if err.classify() == Category::Io {
+      return SceneError::Io(err.into());
+    }
+
+    SceneError::Parse {
+      line: err.line(),
+      column: err.column(),
+      message: without_position(err.to_string()),
+    }
+  }
+}
+
+impl From<serde_yaml::Error> for SceneError {
+  fn from(err: serde_yaml::Error) -> Self {
+    let (line, column) = match err.location() {
+      Some(location) => (location.line(), location.column()),
+      None => (0, 0),
+    };
+
+    SceneError::Parse {
+      line,
+      column,
+      message: without_position(err.to_string()),
+    }
+  }
+}
+
+fn without_position(message: String) -> String {
+  match message.rfind(" at line ") {
+    Some(index) => message[..index].to_string(),
+    None => message,
+  }
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum SceneFormat {
+  Json,
+  Toml,
+  Yaml,
+  Text,
+}
+
+impl ToString for SceneFormat {
+  fn to_string(&self) -> String {
+    match *self {
+      SceneFormat::Json => String::from("JSON"),
+      SceneFormat::Toml => String::from("TOML"),
+      SceneFormat::Yaml => String::from("YAML"),
+      SceneFormat::Text => String::from("Scene Text"),
+    }
+  }
+}
+
+impl SceneFormat {
+  pub const ALL: [SceneFormat; 4] = [SceneFormat::Json, SceneFormat::Toml, SceneFormat::Yaml, SceneFormat::Text];
+
+  pub fn extensions(&self) -> &'static [&'static str] {
+    match *self {
+      SceneFormat::Json => &["json"],
+      SceneFormat::Toml => &["toml"],
+      SceneFormat::Yaml => &["yaml", "yml"],
+      SceneFormat::Text => &["scene"],
+    }
+  }
+
+  // Unknown extensions fall back to JSON, the format scenes were always saved in.
+  pub fn from_path(path: &Path) -> SceneFormat {
+    let extension = path.extension()
+      .and_then(|extension| extension.to_str())
+      .map(|extension| extension.to_ascii_lowercase());
+
+    SceneFormat::ALL.into_iter()
+      .find(|format| extension.as_deref().is_some_and(|extension| format.extensions().contains(&extension)))
+      .unwrap_or(SceneFormat::Json)
+  }
+}
+
+#[derive(Serialize)]
+struct SceneFile<'a> {
+  version: u64,
+  #[serde(flatten)]
+  world: &'a World,
+}
+
+pub fn load(path: &Path) -> Result<World, SceneError> {
+  let text = fs::read_to_string(path)?;
+  let format = SceneFormat::from_path(path);
+
+  let document: Value = parse(&text, format)?;
+
+  if document.get(INCLUDE_KEY).is_none() {
+    return from_str_as(&text, format);
+  }
+
+  let document = load_included(path, &mut Vec::new())?;
+
+  let mut world = serde_json::from_value(document)?;
+  resolve(&mut world)?;
+
+  Ok(world)
+}
+
+pub fn save(path: &Path, world: &World) -> Result<(), SceneError> {
+  let text = to_string_as(world, SceneFormat::from_path(path))?;
+
+  let mut writer = BufWriter::new(File::create(path)?);
+  writer.write_all(text.as_bytes())?;
+  writer.flush()?;
+
+  Ok(())
+}
+
+pub fn to_writer(writer: impl Write, world: &World) -> Result<(), SceneError> {
+  serde_json::to_writer_pretty(writer, &SceneFile { version: FORMAT_VERSION, world })?;
+
+  Ok(())
+}
+
+pub fn to_string_as(world: &World, format: SceneFormat) -> Result<String, SceneError> {
+  let scene = SceneFile { version: FORMAT_VERSION, world };
+
+  match format {
+    SceneFormat::Json => Ok(serde_json::to_string_pretty(&scene)?),
+    SceneFormat::Toml => toml::to_string(&scene).map_err(|err| SceneError::Serialize(err.to_string())),
+    SceneFormat::Yaml => {
+      // Enums are written as single-key maps rather than YAML tags, matching the other formats.
+      let mut yaml = Vec::new();
+
+      singleton_map_recursive::serialize(&scene, &mut serde_yaml::Serializer::new(&mut yaml))
+        .map_err(|err| SceneError::Serialize(err.to_string()))?;
+
+      String::from_utf8(yaml).map_err(|err| SceneError::Serialize(err.to_string()))
+    },
+    SceneFormat::Text => match serde_json::to_value(&scene)? {
+      Value::Object(document) => Ok(scene_text::to_string(&document)),
+      _ => unreachable!("scenes serialize to tables"),
+    },
+  }
+}
+
+pub fn from_reader(mut reader: impl Read) -> Result<World, SceneError> {
+  let mut json = String::new();
+  reader.read_to_string(&mut json)?;
+
+  from_str(&json)
+}
+
+pub fn from_str(json: &str) -> Result<World, SceneError> {
+  from_str_as(json, SceneFormat::Json)
+}
+
+pub fn from_str_as(text: &str, format: SceneFormat) -> Result<World, SceneError> {
+  let mut document: Value = parse(text, format)?;
+
+  if document.get(INCLUDE_KEY).is_some() {
+    return Err(SceneError::Validation(String::from("includes can only be resolved when loading from a file")));
+  }
+
+  // Current documents are read from the original text so errors point into it.
+  let mut world = if migrate(&mut document)? == FORMAT_VERSION {
+    parse(text, format)?
+  } else {
+    serde_json::from_value(document)?
+  };
+
+  resolve(&mut world)?;
+
+  Ok(world)
+}
+
+pub fn from_value(mut document: Value) -> Result<World, SceneError> {
+  migrate(&mut document)?;
+
+  let mut world = serde_json::from_value(document)?;
+  resolve(&mut world)?;
+
+  Ok(world)
+}
+
+// Included files are merged first, in order, and the including file is merged on top.
+// Objects and lights are appended, tables such as the camera and materials are merged
+// key by key so a scene can override single fields, and any other value is replaced.
+fn load_included(path: &Path, stack: &mut Vec<PathBuf>) -> Result<Value, SceneError> {
+  let canonical = fs::canonicalize(path).map_err(|err| included_error(path, err.into()))?;
+
+  if stack.contains(&canonical) {
+    let cycle: Vec<String> = stack.iter()
+      .chain([&canonical])
+      .map(|path| path.display().to_string())
+      .collect();
+
+    return Err(SceneError::Validation(format!("scene includes itself: {}", cycle.join(" -> "))));
+  }
+
+  let mut document = read_document(path).map_err(|err| included_error(path, err))?;
+
+  let includes = match document.remove(INCLUDE_KEY) {
+    Some(Value::String(include)) => vec![include],
+    Some(Value::Array(includes)) => includes.into_iter()
+      .map(|include| match include {
+        Value::String(include) => Ok(include),
+        _ => Err(included_error(path, SceneError::Validation(String::from("includes must be file paths")))),
+      })
+      .collect::<Result<_, _>>()?,
+    Some(_) => return Err(included_error(path, SceneError::Validation(String::from("includes must be file paths")))),
+    None => Vec::new(),
+  };
+
+  stack.push(canonical);
+
+  let directory = path.parent().unwrap_or(Path::new(""));
+  let mut merged = Map::new();
+
+  for include in includes {
+    if let Value::Object(included) = load_included(&directory.join(include), stack)? {
+      merge(&mut merged, included);
+    }
+  }
+
+  merge(&mut merged, document);
+  stack.pop();
+
+  Ok(Value::Object(merged))
+}
+
+fn read_document(path: &Path) -> Result<Map<String, Value>, SceneError> {
+  if gltf_import::is_gltf(path) {
+    let world = gltf_import::import(path)?;
+
+    // Only the geometry of an included glTF file is used, the including scene keeps its own camera and lights.
+    let mut document = Map::new();
+    document.insert(String::from("objects"), serde_json::to_value(world.objects())?);
+    document.insert(String::from("materials"), serde_json::to_value(world.materials())?);
+
+    return Ok(document);
+  }
+
+  let mut document = parse(&fs::read_to_string(path)?, SceneFormat::from_path(path))?;
+  migrate(&mut document)?;
+
+  match document {
+    Value::Object(document) => Ok(document),
+    _ => unreachable!("migrate only accepts objects"),
+  }
+}
+
+fn merge(base: &mut Map<String, Value>, layer: Map<String, Value>) {
+  for (key, value) in layer {
+    match (key.as_str(), base.get_mut(&key), value) {
+      ("objects" | "lights", Some(Value::Array(existing)), Value::Array(items)) => existing.extend(items),
+      (_, Some(Value::Object(existing)), Value::Object(table)) => merge(existing, table),
+      (_, _, value) => {
+        base.insert(key, value);
+      },
+    }
+  }
+}
+
+fn included_error(path: &Path, err: SceneError) -> SceneError {
+  match err {
+    SceneError::Include { .. } => err,
+    err => SceneError::Include {
+      path: path.to_path_buf(),
+      source: Box::new(err),
+    },
+  }
+}
+
+pub(crate) fn parse<T: DeserializeOwned>(text: &str, format: SceneFormat) -> Result<T, SceneError> {
+  match format {
+    SceneFormat::Json => Ok(serde_json::from_str(text)?),
+    SceneFormat::Toml => toml::from_str(text).map_err(|err| {
+      let (line, column) = match err.span() {
+        Some(span) => line_and_column(text, span.start),
+        None => (0, 0),
+      };
+
+      SceneError::Parse {
+        line,
+        column,
+        message: err.message().to_string(),
+      }
+    }),
+    SceneFormat::Yaml => Ok(singleton_map_recursive::deserialize(serde_yaml::Deserializer::from_str(text))?),
+    SceneFormat::Text => Ok(serde_json::from_value(scene_text::parse(text)?)?),
+  }
+}
+
+fn line_and_column(text: &str, offset: usize) -> (usize, usize) {
+  let before = &text[..offset.min(text.len())];
+  let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+
+  (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
+
+// Built-in presets can be used by name without defining them, they are added
+// to the scene's materials so saving writes them out.
+fn resolve(world: &mut World) -> Result<(), SceneError> {
+  let presets: Vec<String> = world.objects().iter()
+    .filter_map(|object| object.material_name())
+    .filter(|name| !world.materials().contains_key(*name))
+    .map(String::from)
+    .collect();
+
+  for name in presets {
+    if let Some(material) = Material::preset(&name) {
+      world.materials_mut().insert(name, material);
+    }
+  }
+
+  let materials = world.materials().clone();
+
+  for (index, object) in world.objects_mut().iter_mut().enumerate() {
+    if let Object::Mesh(mesh) = object {
+      mesh.update_bounds();
+    }
+
+    let material = match object.material_name() {
+      Some(name) => *materials.get(name).ok_or_else(|| SceneError::Validation(format!(
+        "object {} uses the undefined material \"{}\"", index, name
+      )))?,
+      None => continue,
+    };
+
+    *object.material_mut() = material;
+  }
+
+  Ok(())
+}
+
+pub fn migrate(document: &mut Value) -> Result<u64, SceneError> {
+  let document = document.as_object_mut()
+    .ok_or_else(|| SceneError::Validation(String::from("scene file must contain a table of scene settings")))?;
+
+  let version = match document.remove("version") {
+    Some(version) => version.as_u64()
+      .ok_or_else(|| SceneError::Validation(String::from("scene file version must be a positive integer")))?,
+    None => 0,
+  };
+
+  if version > FORMAT_VERSION {
+    return Err(SceneError::Validation(format!(
+      "scene file version {} is newer than the supported version {}", version, FORMAT_VERSION
+    )));
+  }
+
+  for migration in &MIGRATIONS[version as usize..] {
+    migration(document);
+  }
+
+  Ok(version)
+}
+
+fn migrate_v0_to_v1(document: &mut Map<String, Value>) {
+  if let Some(Value::Object(camera)) = document.get_mut("camera") {
+    camera.remove("camera_info_old");
+    camera.remove("rays");
+    camera.remove("moved");
+  }
+}
\ No newline at end of file
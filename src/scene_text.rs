@@ -0,0 +1,340 @@
+use serde_json::{Value, Map};
+
+use crate::scene_file::SceneError;
+
+// Statement names for the object and light variants, in the order they are tagged in scene files.
+const OBJECTS: [(&str, &str); 3] = [("sphere", "Sphere"), ("plane", "Plane"), ("mesh", "Mesh")];
+const LIGHTS: [(&str, &str); 3] = [("ambient_light", "Ambient"), ("directional_light", "Directional"), ("point_light", "Point")];
+
+const MATERIAL: &str = "material";
+
+// One statement per line: a name followed by keys and their values.
+//
+//   version 1
+//   camera camera_info.position 0 1 -5 depth 5
+//   material glass transparency 0.9 refractive_index 1.5
+//   sphere position 0 0 10 radius 1 material_name "glass"
+//   point_light position 0 5 0 color 1 1 1
+//
+// Keys are bare words and dots reach into nested tables. Values are numbers,
+// true, false, quoted strings or inline JSON, and several values make a list.
+// Object and light statements each add one, any other name sets that field of
+// the scene, either to a value or to the keys that follow it.
+pub fn parse(text: &str) -> Result<Value, SceneError> {
+  let mut document = Map::new();
+
+  for (index, line) in text.lines().enumerate() {
+    let line_number = index + 1;
+    let tokens = tokenize(line, line_number)?;
+
+    let (&(column, name), rest) = match tokens.split_first() {
+      Some(first) => first,
+      None => continue,
+    };
+
+    if !is_key(name) {
+      return Err(parse_error(line_number, column, format!("expected a statement name, found `{}`", name)));
+    }
+
+    if let Some(tag) = tag(&OBJECTS, name) {
+      push_item(&mut document, "objects", tag, pairs(rest, line_number)?);
+    } else if let Some(tag) = tag(&LIGHTS, name) {
+      push_item(&mut document, "lights", tag, pairs(rest, line_number)?);
+    } else if name == MATERIAL {
+      let (&(column, material), rest) = rest.split_first()
+        .ok_or_else(|| parse_error(line_number, column + name.len(), String::from("expected a material name")))?;
+
+      let material = if is_key(material) {
+        material.to_string()
+      } else {
+        match value(&[(column, material)], line_number)? {
+          Value::String(material) => material,
+          _ => return Err(parse_error(line_number, column, format!("expected a material name, found `{}`", material))),
+        }
+      };
+
+      let materials = table(&mut document, "materials");
+      let definition = table(materials, &material);
+
+      for (key, value) in pairs(rest, line_number)? {
+        definition.insert(key, value);
+      }
+    } else if rest.first().is_some_and(|(_, token)| !is_key(token)) {
+      set_path(&mut document, name, value(rest, line_number)?);
+    } else {
+      for (key, value) in flatten_pairs(rest, line_number)? {
+        set_path(&mut document, &format!("{}.{}", name, key), value);
+      }
+    }
+  }
+
+  Ok(Value::Object(document))
+}
+
+// Writes the statements parse reads back to the same document.
+pub fn to_string(document: &Map<String, Value>) -> String {
+  let mut lines = Vec::new();
+
+  // The version goes first so it is the first thing a reader sees.
+  let version = document.iter().filter(|(key, _)| *key == "version");
+  let rest = document.iter().filter(|(key, _)| *key != "version");
+
+  for (key, value) in version.chain(rest) {
+    match (key.as_str(), value) {
+      ("objects", Value::Array(items)) if items.iter().all(|item| item_statement(&OBJECTS, item).is_some()) => {
+        lines.extend(items.iter().filter_map(|item| item_statement(&OBJECTS, item)));
+      },
+      ("lights", Value::Array(items)) if items.iter().all(|item| item_statement(&LIGHTS, item).is_some()) => {
+        lines.extend(items.iter().filter_map(|item| item_statement(&LIGHTS, item)));
+      },
+      ("materials", Value::Object(materials)) if materials.values().all(is_table) => {
+        for (name, material) in materials {
+          let name = if is_plain_key(name) { name.clone() } else { format_value(&Value::String(name.clone())) };
+          lines.push(statement(&format!("{} {}", MATERIAL, name), &flatten(material)));
+        }
+      },
+      (_, Value::Object(table)) if is_table(value) && !is_vector(value) && !table.is_empty() => {
+        // The table's own values go on one line and every nested table on its own line.
+        let (direct, nested): (Vec<_>, Vec<_>) = flatten(value).into_iter().partition(|(path, _)| !path.contains('.'));
+        let mut groups: Vec<Vec<(String, &Value)>> = Vec::new();
+
+        for (path, value) in nested {
+          let child = path.split('.').next();
+
+          match groups.last_mut() {
+            Some(group) if group[0].0.split('.').next() == child => group.push((path, value)),
+            _ => groups.push(vec![(path, value)]),
+          }
+        }
+
+        lines.extend([direct].into_iter().chain(groups).filter(|group| !group.is_empty()).map(|group| statement(key, &group)));
+      },
+      _ => lines.push(format!("{} {}", key, format_value(value))),
+    }
+  }
+
+  lines.push(String::new());
+  lines.join("\n")
+}
+
+fn tag(names: &[(&'static str, &'static str)], name: &str) -> Option<&'static str> {
+  names.iter().find(|(statement, _)| *statement == name).map(|(_, tag)| *tag)
+}
+
+fn push_item(document: &mut Map<String, Value>, list: &str, tag: &str, fields: Map<String, Value>) {
+  let mut item = Map::new();
+  item.insert(tag.to_string(), Value::Object(fields));
+
+  match document.entry(list).or_insert_with(|| Value::Array(Vec::new())) {
+    Value::Array(items) => items.push(Value::Object(item)),
+    other => *other = Value::Array(vec![Value::Object(item)]),
+  }
+}
+
+fn table<'a>(map: &'a mut Map<String, Value>, key: &str) -> &'a mut Map<String, Value> {
+  let entry = map.entry(key).or_insert_with(|| Value::Object(Map::new()));
+
+  if !entry.is_object() {
+    *entry = Value::Object(Map::new());
+  }
+
+  match entry {
+    Value::Object(table) => table,
+    _ => unreachable!("the entry was just made a table"),
+  }
+}
+
+fn set_path(map: &mut Map<String, Value>, path: &str, value: Value) {
+  match path.split_once('.') {
+    Some((key, rest)) => set_path(table(map, key), rest, value),
+    None => {
+      map.insert(path.to_string(), value);
+    },
+  }
+}
+
+// Dotted keys become nested tables.
+fn pairs(tokens: &[(usize, &str)], line: usize) -> Result<Map<String, Value>, SceneError> {
+  let mut fields = Map::new();
+
+  for (key, value) in flatten_pairs(tokens, line)? {
+    set_path(&mut fields, &key, value);
+  }
+
+  Ok(fields)
+}
+
+fn flatten_pairs(tokens: &[(usize, &str)], line: usize) -> Result<Vec<(String, Value)>, SceneError> {
+  let mut pairs = Vec::new();
+  let mut index = 0;
+
+  while index < tokens.len() {
+    let (column, key) = tokens[index];
+
+    if !is_key(key) {
+      return Err(parse_error(line, column, format!("expected a key, found `{}`", key)));
+    }
+
+    let end = tokens[index + 1..].iter()
+      .position(|(_, token)| is_key(token))
+      .map_or(tokens.len(), |offset| index + 1 + offset);
+
+    if end == index + 1 {
+      return Err(parse_error(line, column, format!("missing value for `{}`", key)));
+    }
+
+    pairs.push((key.to_string(), value(&tokens[index + 1..end], line)?));
+    index = end;
+  }
+
+  Ok(pairs)
+}
+
+fn value(tokens: &[(usize, &str)], line: usize) -> Result<Value, SceneError> {
+  let mut values = tokens.iter()
+    .map(|(column, token)| {
+      serde_json::from_str(token).map_err(|_| parse_error(line, *column, format!("invalid value `{}`", token)))
+    })
+    .collect::<Result<Vec<Value>, _>>()?;
+
+  if values.len() == 1 {
+    Ok(values.remove(0))
+  } else {
+    Ok(Value::Array(values))
+  }
+}
+
+// Splits a line at whitespace, keeping quoted strings and bracketed JSON together.
+// Columns count characters from 1 like the other formats' errors.
+fn tokenize(line: &str, number: usize) -> Result<Vec<(usize, &str)>, SceneError> {
+  let mut tokens = Vec::new();
+  let mut chars = line.char_indices().enumerate().peekable();
+
+  while let Some((column, (start, c))) = chars.next() {
+    if c.is_whitespace() {
+      continue;
+    }
+
+    if c == '#' {
+      break;
+    }
+
+    // Whitespace only ends a token outside of strings and brackets.
+
+    let mut depth = i32::from(c == '[' || c == '{');
+    let mut in_string = c == '"';
+    let mut escaped = false;
+    let mut end = start + c.len_utf8();
+
+    while let Some(&(_, (index, c))) = chars.peek() {
+      if !in_string && depth == 0 && c.is_whitespace() {
+        break;
+      }
+
+      chars.next();
+      end = index + c.len_utf8();
+
+      if in_string {
+        match c {
+          _ if escaped => escaped = false,
+          '\\' => escaped = true,
+          '"' => in_string = false,
+          _ => (),
+        }
+      } else {
+        match c {
+          '"' => in_string = true,
+          '[' | '{' => depth += 1,
+          ']' | '}' => depth -= 1,
+          _ => (),
+        }
+      }
+    }
+
+    if in_string || depth > 0 {
+      return Err(parse_error(number, column + 1, format!("unterminated `{}`", &line[start..end])));
+    }
+
+    tokens.push((column + 1, &line[start..end]));
+  }
+
+  Ok(tokens)
+}
+
+fn is_key(token: &str) -> bool {
+  let mut chars = token.chars();
+
+  chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+    && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+    && !matches!(token, "true" | "false" | "null")
+}
+
+// Keys that can be written bare, as they contain no dots.
+fn is_plain_key(key: &str) -> bool {
+  is_key(key) && !key.contains('.')
+}
+
+// Vectors are written as their three coordinates, which read back as a list
+// that serde accepts for the same struct.
+fn is_vector(value: &Value) -> bool {
+  value.as_object().is_some_and(|table| {
+    table.len() == 3 && ["x", "y", "z"].iter().all(|key| table.get(*key).is_some_and(Value::is_number))
+  })
+}
+
+fn is_table(value: &Value) -> bool {
+  value.as_object().is_some_and(|table| table.keys().all(|key| is_plain_key(key)))
+}
+
+fn item_statement(names: &[(&str, &str)], item: &Value) -> Option<String> {
+  let (tag, fields) = item.as_object().filter(|item| item.len() == 1)?.iter().next()?;
+  let (name, _) = names.iter().find(|(_, variant)| variant == tag)?;
+
+  is_table(fields).then(|| statement(name, &flatten(fields)))
+}
+
+// Nested tables become dotted keys, empty ones are written as {} so they survive.
+fn flatten(value: &Value) -> Vec<(String, &Value)> {
+  let mut pairs = Vec::new();
+
+  if let Value::Object(table) = value {
+    for (key, value) in table {
+      match value {
+        Value::Object(nested) if is_table(value) && !is_vector(value) && !nested.is_empty() => {
+          pairs.extend(flatten(value).into_iter().map(|(path, value)| (format!("{}.{}", key, path), value)));
+        },
+        _ => pairs.push((key.clone(), value)),
+      }
+    }
+  }
+
+  pairs
+}
+
+fn statement(name: &str, pairs: &[(String, &Value)]) -> String {
+  let mut line = name.to_string();
+
+  for (key, value) in pairs {
+    line.push(' ');
+    line.push_str(key);
+    line.push(' ');
+    line.push_str(&format_value(value));
+  }
+
+  line
+}
+
+// Lists of plain values are written space separated, anything else as compact JSON.
+fn format_value(value: &Value) -> String {
+  match value {
+    Value::Object(vector) if is_vector(value) => ["x", "y", "z"].map(|axis| vector[axis].to_string()).join(" "),
+    Value::Array(values) if values.len() > 1 && values.iter().all(|value| !value.is_array() && !value.is_object() && !value.is_null()) => {
+      values.iter().map(Value::to_string).collect::<Vec<_>>().join(" ")
+    },
+    value => value.to_string(),
+  }
+}
+
+fn parse_error(line: usize, column: usize, message: String) -> SceneError {
+  SceneError::Parse { line, column, message }
+}
\ No newline at end of file
@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use crate::image::Image;
+
+// The heatmap goes from black through blue, red and yellow to white.
+const HEATMAP: [[u8; 3]; 5] = [[0, 0, 0], [0, 0, 255], [255, 0, 0], [255, 255, 0], [255, 255, 255]];
+
+// What tracing a single pixel took, collected while it is rendered.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PixelStats {
+  pub primary_rays: u32,
+  pub shadow_rays: u32,
+  pub secondary_rays: u32,
+  pub intersection_tests: u32,
+  pub depth: u32,
+}
+
+impl Default for PixelStats {
+  fn default() -> Self {
+    PixelStats::new()
+  }
+}
+
+impl PixelStats {
+  pub fn new() -> PixelStats {
+    PixelStats {
+      primary_rays: 0,
+      shadow_rays: 0,
+      secondary_rays: 0,
+      intersection_tests: 0,
+      depth: 0,
+    }
+  }
+
+  pub fn rays(&self) -> u32 {
+    self.primary_rays + self.shadow_rays + self.secondary_rays
+  }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct RenderStats {
+  pub width: u32,
+  pub height: u32,
+  pub primary_rays: u64,
+  pub shadow_rays: u64,
+  pub secondary_rays: u64,
+  pub intersection_tests: u64,
+  pub ray_generation: Duration,
+  pub tracing: Duration,
+  pub texture_upload: Duration,
+  depth: u64,
+  cost: Vec<u32>,
+}
+
+impl RenderStats {
+  // Pixels are in the order the renderer stores them, bottom row first.
+  pub fn new(width: u32, height: u32, pixels: &[PixelStats]) -> RenderStats {
+    let mut stats = RenderStats {
+      width,
+      height,
+      primary_rays: 0,
+      shadow_rays: 0,
+      secondary_rays: 0,
+      intersection_tests: 0,
+      ray_generation: Duration::ZERO,
+      tracing: Duration::ZERO,
+      texture_upload: Duration::ZERO,
+      depth: 0,
+      cost: Vec::with_capacity(pixels.len()),
+    };
+
+    for pixel in pixels {
+      stats.primary_rays += pixel.primary_rays as u64;
+      stats.shadow_rays += pixel.shadow_rays as u64;
+      stats.secondary_rays += pixel.secondary_rays as u64;
+      stats.intersection_tests += pixel.intersection_tests as u64;
+      stats.depth += pixel.depth as u64;
+      stats.cost.push(pixel.intersection_tests);
+    }
+
+    stats
+  }
+
+  pub fn rays(&self) -> u64 {
+    self.primary_rays + self.shadow_rays + self.secondary_rays
+  }
+
+  // How many rays deep the paths went, a pixel whose camera ray missed counts as one.
+  pub fn average_depth(&self) -> f64 {
+    self.depth as f64 / self.cost.len().max(1) as f64
+  }
+
+  pub fn tests_per_pixel(&self) -> f64 {
+    self.intersection_tests as f64 / self.cost.len().max(1) as f64
+  }
+
+  // Intersection tests per pixel, bottom row first.
+  pub fn cost(&self) -> &[u32] {
+    &self.cost
+  }
+
+  pub fn max_cost(&self) -> u32 {
+    self.cost.iter().copied().max().unwrap_or(0)
+  }
+
+  // Colors every pixel by its share of the most expensive pixel's cost.
+  pub fn heatmap(&self) -> Image {
+    let max = self.max_cost().max(1) as f32;
+
+    let pixels = self.cost
+      .chunks_exact(self.width.max(1) as usize)
+      .rev()
+      .flat_map(|row| row.iter().map(|cost| heat(*cost as f32 / max)))
+      .collect();
+
+    Image::new(self.width, self.height, pixels)
+  }
+}
+
+fn heat(t: f32) -> [u8; 3] {
+  let position = t.clamp(0.0, 1.0) * (HEATMAP.len() - 1) as f32;
+  let index = (position as usize).min(HEATMAP.len() - 2);
+  let fraction = position - index as f32;
+
+  let [from, to] = [HEATMAP[index], HEATMAP[index + 1]];
+
+  [0, 1, 2].map(|i| (from[i] as f32 + (to[i] as f32 - from[i] as f32) * fraction).round() as u8)
+}
\ No newline at end of file
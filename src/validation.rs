@@ -0,0 +1,232 @@
+use std::cmp::Reverse;
+
+use crate::{world::{World, Object, Light, Material, CameraType, Selection, RGB}, vector::{Vector3, Float}};
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum Severity {
+  Warning,
+  Error,
+}
+
+impl ToString for Severity {
+  fn to_string(&self) -> String {
+    match *self {
+      Severity::Warning => String::from("Warning"),
+      Severity::Error => String::from("Error"),
+    }
+  }
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct Problem {
+  pub severity: Severity,
+  pub subject: Selection,
+  pub field: &'static str,
+  pub message: String,
+}
+
+impl ToString for Problem {
+  fn to_string(&self) -> String {
+    format!("{} {}: {}", self.subject.to_string(), self.field, self.message)
+  }
+}
+
+struct Validator {
+  problems: Vec<Problem>,
+  subject: Selection,
+}
+
+pub fn validate(world: &World) -> Vec<Problem> {
+  let mut validator = Validator {
+    problems: Vec::new(),
+    subject: Selection::Camera,
+  };
+
+  for (index, object) in world.objects().iter().enumerate() {
+    validator.subject = Selection::Object(index);
+    validator.object(object);
+  }
+
+  for (index, light) in world.lights().iter().enumerate() {
+    validator.subject = Selection::Light(index);
+    validator.light(light);
+  }
+
+  validator.subject = Selection::Camera;
+  validator.camera(world);
+
+  validator.problems.sort_by_key(|problem| Reverse(problem.severity));
+  validator.problems
+}
+
+pub fn has_errors(problems: &[Problem]) -> bool {
+  problems.iter().any(|problem| problem.severity == Severity::Error)
+}
+
+impl Validator {
+  fn report(&mut self, severity: Severity, field: &'static str, message: &str) {
+    self.problems.push(Problem {
+      severity,
+      subject: self.subject,
+      field,
+      message: String::from(message),
+    });
+  }
+
+  fn error(&mut self, field: &'static str, message: &str) {
+    self.report(Severity::Error, field, message);
+  }
+
+  fn warning(&mut self, field: &'static str, message: &str) {
+    self.report(Severity::Warning, field, message);
+  }
+
+  fn finite_vector(&mut self, field: &'static str, vector: &Vector3) -> bool {
+    let finite = vector.x.is_finite() && vector.y.is_finite() && vector.z.is_finite();
+
+    if !finite {
+      self.error(field, "contains NaN or infinite components");
+    }
+
+    finite
+  }
+
+  fn direction(&mut self, field: &'static str, direction: &Vector3) {
+    if self.finite_vector(field, direction) && direction.mag() == 0.0 {
+      self.error(field, "must not be zero");
+    }
+  }
+
+  fn positive(&mut self, field: &'static str, value: Float) {
+    if !value.is_finite() {
+      self.error(field, "is NaN or infinite");
+    } else if value <= 0.0 {
+      self.error(field, "must be greater than zero");
+    }
+  }
+
+  fn non_negative(&mut self, field: &'static str, value: Float) {
+    if !value.is_finite() {
+      self.error(field, "is NaN or infinite");
+    } else if value < 0.0 {
+      self.warning(field, "is negative");
+    }
+  }
+
+  fn unit_range(&mut self, field: &'static str, value: Float) {
+    if !value.is_finite() {
+      self.error(field, "is NaN or infinite");
+    } else if !(0.0..=1.0).contains(&value) {
+      self.warning(field, "is outside the range 0 to 1");
+    }
+  }
+
+  fn color(&mut self, field: &'static str, color: &RGB) {
+    if color.iter().any(|channel| !channel.is_finite()) {
+      self.error(field, "contains NaN or infinite channels");
+    } else if color.iter().any(|channel| *channel < 0.0) {
+      self.warning(field, "has negative channels");
+    }
+  }
+
+  fn object(&mut self, object: &Object) {
+    match object {
+      Object::Sphere(sphere) => {
+        self.finite_vector("position", &sphere.position);
+        self.positive("radius", sphere.radius);
+        self.material(&sphere.material);
+      },
+      Object::Plane(plane) => {
+        self.finite_vector("position", &plane.position);
+        self.positive("width", plane.width);
+        self.positive("height", plane.height);
+        self.material(&plane.material);
+      },
+      Object::Mesh(mesh) => {
+        self.finite_vector("position", &mesh.position);
+
+        let vertices = mesh.triangles.iter().flat_map(|triangle| triangle.iter());
+
+        if mesh.triangles.is_empty() {
+          self.warning("triangles", "is empty, the mesh is invisible");
+        } else if vertices.clone().any(|vertex| !(vertex.x.is_finite() && vertex.y.is_finite() && vertex.z.is_finite())) {
+          self.error("triangles", "contain NaN or infinite vertices");
+        }
+
+        self.material(&mesh.material);
+      },
+    }
+  }
+
+  fn material(&mut self, material: &Material) {
+    self.color("color", &material.color);
+    self.non_negative("ambient reflection", material.ambient_reflection);
+    self.non_negative("diffuse reflection", material.diffuse_reflection);
+
+    if material.has_specular {
+      self.non_negative("specular reflection", material.specular_reflection);
+    }
+
+    self.unit_range("reflectivity", material.reflectivity);
+    self.unit_range("transparency", material.transparency);
+
+    if material.reflectivity + material.transparency > 1.0 {
+      self.warning("transparency", "reflectivity and transparency add up to more than 1");
+    }
+
+    self.positive("refractive index", material.refractive_index);
+
+    if material.transparency > 0.0 && material.refractive_index > 0.0 && material.refractive_index < 1.0 {
+      self.warning("refractive index", "is below 1, lower than vacuum");
+    }
+  }
+
+  fn light(&mut self, light: &Light) {
+    match light {
+      Light::Ambient(light) => {
+        self.color("color", &light.color);
+      },
+      Light::Directional(light) => {
+        self.color("color", &light.color);
+        self.direction("direction", &light.direction);
+      },
+      Light::Point(light) => {
+        self.color("color", &light.color);
+        self.finite_vector("position", &light.position);
+      },
+    }
+  }
+
+  fn camera(&mut self, world: &World) {
+    let camera = world.camera();
+    let camera_info = camera.camera_info();
+
+    self.finite_vector("position", &camera_info.position);
+    self.direction("forward", &camera_info.forward);
+
+    let forward = camera_info.forward;
+
+    if forward.mag() > 0.0 && forward.x == 0.0 && forward.z == 0.0 {
+      self.error("forward", "must not point straight up or down");
+    }
+
+    match camera_info.camera_type {
+      CameraType::Perspective => self.positive("vertical fov", camera_info.vertical_fov),
+      CameraType::Orthographic => self.positive("camera height", camera_info.camera_height),
+    }
+
+    if camera_info.viewport_width == 0 {
+      self.error("viewport width", "must be greater than zero");
+    }
+
+    if camera_info.viewport_height == 0 {
+      self.error("viewport height", "must be greater than zero");
+    }
+
+    if *camera.depth() == 0 {
+      self.warning("depth", "is zero, every pixel will use the miss color");
+    }
+
+    self.color("miss color", &camera_info.miss_color);
+  }
+}
\ No newline at end of file
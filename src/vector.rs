@@ -2,11 +2,23 @@ use std::ops::{Add, Sub, Mul, Div, Neg};
 
 use serde::{Serialize, Deserialize};
 
+#[cfg(not(feature = "f32"))]
+pub type Float = f64;
+
+#[cfg(not(feature = "f32"))]
+pub use std::f64::consts;
+
+#[cfg(feature = "f32")]
+pub type Float = f32;
+
+#[cfg(feature = "f32")]
+pub use std::f32::consts;
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Vector3 {
-  pub x: f64,
-  pub y: f64,
-  pub z: f64
+  pub x: Float,
+  pub y: Float,
+  pub z: Float
 }
 
 impl Neg for Vector3 {
@@ -21,10 +33,10 @@ impl Neg for Vector3 {
   }
 }
 
-impl Div<f64> for Vector3 {
+impl Div<Float> for Vector3 {
   type Output = Vector3;
 
-  fn div(self, rhs: f64) -> Self::Output {
+  fn div(self, rhs: Float) -> Self::Output {
     return Vector3::new(
       self.x / rhs,
       self.y / rhs,
@@ -45,10 +57,10 @@ impl Div for Vector3 {
   }
 }
 
-impl Mul<f64> for Vector3 {
+impl Mul<Float> for Vector3 {
   type Output = Vector3;
 
-  fn mul(self, rhs: f64) -> Self::Output {
+  fn mul(self, rhs: Float) -> Self::Output {
     return Vector3::new(
       self.x * rhs,
       self.y * rhs,
@@ -69,10 +81,10 @@ impl Mul for Vector3 {
   }
 }
 
-impl Sub<f64> for Vector3 {
+impl Sub<Float> for Vector3 {
   type Output = Vector3;
 
-  fn sub(self, rhs: f64) -> Self::Output {
+  fn sub(self, rhs: Float) -> Self::Output {
     return Vector3::new(
       self.x - rhs,
       self.y - rhs,
@@ -93,10 +105,10 @@ impl Sub for Vector3 {
   }
 }
 
-impl Add<f64> for Vector3 {
+impl Add<Float> for Vector3 {
   type Output = Vector3;
 
-  fn add(self, rhs: f64) -> Self::Output {
+  fn add(self, rhs: Float) -> Self::Output {
     return Vector3::new(
       self.x + rhs,
       self.y + rhs,
@@ -118,7 +130,7 @@ impl Add for Vector3 {
 }
 
 impl Vector3 {
-  pub fn new(x: f64, y: f64, z: f64) -> Vector3 {
+  pub fn new(x: Float, y: Float, z: Float) -> Vector3 {
     Vector3 { x, y, z }
   }
 
@@ -127,7 +139,7 @@ impl Vector3 {
     self / mag
   }
 
-  pub fn dot(&self, rhs: &Vector3) -> f64 {
+  pub fn dot(&self, rhs: &Vector3) -> Float {
     self.x * rhs.x +
     self.y * rhs.y +
     self.z * rhs.z
@@ -141,19 +153,19 @@ impl Vector3 {
     )
   }
 
-  pub fn mag(&self) -> f64 {
+  pub fn mag(&self) -> Float {
     (self.x.powi(2) + self.y.powi(2) + self.z.powi(2)).sqrt()
   }
 
-  pub fn x_mut(&mut self) -> &mut f64 {
+  pub fn x_mut(&mut self) -> &mut Float {
     &mut self.x
   }
 
-  pub fn y_mut(&mut self) -> &mut f64 {
+  pub fn y_mut(&mut self) -> &mut Float {
     &mut self.y
   }
 
-  pub fn z_mut(&mut self) -> &mut f64 {
+  pub fn z_mut(&mut self) -> &mut Float {
     &mut self.z
   }
 }
\ No newline at end of file
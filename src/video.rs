@@ -0,0 +1,181 @@
+use std::{fs::File, io::{self, Write, BufWriter}, path::Path};
+
+use crate::{image::{Image, to_io_error}, vector::Float};
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum VideoFormat {
+  Y4m,
+  Gif,
+  Apng,
+}
+
+impl ToString for VideoFormat {
+  fn to_string(&self) -> String {
+    match *self {
+      VideoFormat::Y4m => String::from("Y4M"),
+      VideoFormat::Gif => String::from("GIF"),
+      VideoFormat::Apng => String::from("APNG"),
+    }
+  }
+}
+
+impl VideoFormat {
+  pub const ALL: [VideoFormat; 3] = [VideoFormat::Y4m, VideoFormat::Gif, VideoFormat::Apng];
+
+  pub fn extension(&self) -> &'static str {
+    match *self {
+      VideoFormat::Y4m => "y4m",
+      VideoFormat::Gif => "gif",
+      VideoFormat::Apng => "apng",
+    }
+  }
+
+  // Plain .png paths stay image sequences, animated PNGs need the .apng extension.
+  pub fn from_path(path: &Path) -> Option<VideoFormat> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+
+    VideoFormat::ALL.into_iter().find(|format| format.extension() == extension)
+  }
+}
+
+pub struct Y4mWriter<W: Write> {
+  writer: W,
+  width: u32,
+  height: u32,
+}
+
+impl<W: Write> Y4mWriter<W> {
+  pub fn new(mut writer: W, width: u32, height: u32, frame_rate: Float) -> io::Result<Y4mWriter<W>> {
+    let (numerator, denominator) = if frame_rate.fract() == 0.0 {
+      (frame_rate as u32, 1)
+    } else {
+      ((frame_rate * 1000.0).round() as u32, 1000)
+    };
+
+    writeln!(writer, "YUV4MPEG2 W{} H{} F{}:{} Ip A1:1 C444", width, height, numerator, denominator)?;
+
+    Ok(Y4mWriter {
+      writer,
+      width,
+      height,
+    })
+  }
+
+  // Planar 4:4:4 with BT.601 limited range, which every player that reads y4m understands.
+  pub fn write_frame(&mut self, image: &Image) -> io::Result<()> {
+    check_size(image, self.width, self.height)?;
+
+    let mut planes = vec![0; image.pixels.len() * 3];
+    let (y, chroma) = planes.split_at_mut(image.pixels.len());
+    let (u, v) = chroma.split_at_mut(image.pixels.len());
+
+    for (index, [red, green, blue]) in image.pixels.iter().enumerate() {
+      let (red, green, blue) = (*red as f32, *green as f32, *blue as f32);
+
+      y[index] = (16.0 + 0.257 * red + 0.504 * green + 0.098 * blue).round() as u8;
+      u[index] = (128.0 - 0.148 * red - 0.291 * green + 0.439 * blue).round() as u8;
+      v[index] = (128.0 + 0.439 * red - 0.368 * green - 0.071 * blue).round() as u8;
+    }
+
+    self.writer.write_all(b"FRAME\n")?;
+    self.writer.write_all(&planes)
+  }
+
+  pub fn finish(mut self) -> io::Result<W> {
+    self.writer.flush()?;
+
+    Ok(self.writer)
+  }
+}
+
+pub enum VideoWriter {
+  Y4m(Y4mWriter<BufWriter<File>>),
+  Gif {
+    encoder: gif::Encoder<BufWriter<File>>,
+    width: u16,
+    height: u16,
+    delay: u16,
+  },
+  Apng {
+    writer: png::Writer<BufWriter<File>>,
+    width: u32,
+    height: u32,
+  },
+}
+
+impl VideoWriter {
+  // APNG stores the frame count up front, so it has to be known before the first frame.
+  pub fn create(path: &Path, format: VideoFormat, width: u32, height: u32, frame_rate: Float, frames: u32) -> io::Result<VideoWriter> {
+    let file = BufWriter::new(File::create(path)?);
+
+    match format {
+      VideoFormat::Y4m => Ok(VideoWriter::Y4m(Y4mWriter::new(file, width, height, frame_rate)?)),
+      VideoFormat::Gif => {
+        let (width, height) = (gif_size(width)?, gif_size(height)?);
+
+        let mut encoder = gif::Encoder::new(file, width, height, &[]).map_err(to_io_error)?;
+        encoder.set_repeat(gif::Repeat::Infinite).map_err(to_io_error)?;
+
+        // GIF delays are whole hundredths of a second, so 24 fps plays back at 25.
+        let delay = (100.0 / frame_rate).round().max(1.0) as u16;
+
+        Ok(VideoWriter::Gif { encoder, width, height, delay })
+      },
+      VideoFormat::Apng => {
+        let mut encoder = png::Encoder::new(file, width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_animated(frames.max(1), 0).map_err(to_io_error)?;
+        encoder.set_frame_delay(100, (frame_rate * 100.0).round().clamp(1.0, u16::MAX as Float) as u16).map_err(to_io_error)?;
+
+        let writer = encoder.write_header().map_err(to_io_error)?;
+
+        Ok(VideoWriter::Apng { writer, width, height })
+      },
+    }
+  }
+
+  pub fn write_frame(&mut self, image: &Image) -> io::Result<()> {
+    let data: Vec<u8> = image.pixels.iter().flat_map(|pixel| *pixel).collect();
+
+    match self {
+      VideoWriter::Y4m(writer) => writer.write_frame(image),
+      VideoWriter::Gif { encoder, width, height, delay } => {
+        check_size(image, *width as u32, *height as u32)?;
+
+        let mut frame = gif::Frame::from_rgb_speed(*width, *height, &data, 10);
+        frame.delay = *delay;
+
+        encoder.write_frame(&frame).map_err(to_io_error)
+      },
+      VideoWriter::Apng { writer, width, height } => {
+        check_size(image, *width, *height)?;
+
+        writer.write_image_data(&data).map_err(to_io_error)
+      },
+    }
+  }
+
+  pub fn finish(self) -> io::Result<()> {
+    match self {
+      VideoWriter::Y4m(writer) => writer.finish().map(|_| ()),
+      VideoWriter::Gif { encoder, .. } => encoder.into_inner().map_err(to_io_error)?.flush(),
+      VideoWriter::Apng { writer, .. } => writer.finish().map_err(to_io_error),
+    }
+  }
+}
+
+fn check_size(image: &Image, width: u32, height: u32) -> io::Result<()> {
+  if image.width != width || image.height != height {
+    return Err(io::Error::new(
+      io::ErrorKind::InvalidInput,
+      format!("frame is {}x{} but the video is {}x{}", image.width, image.height, width, height),
+    ));
+  }
+
+  Ok(())
+}
+
+fn gif_size(size: u32) -> io::Result<u16> {
+  u16::try_from(size).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "GIF frames can be at most 65535 pixels wide"))
+}
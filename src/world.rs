@@ -1,21 +1,119 @@
-use std::{fs::File, io::BufReader};
+use std::collections::BTreeMap;
 
-use glium::glutin::event::VirtualKeyCode;
 use rayon::prelude::{IntoParallelRefMutIterator, ParallelIterator, IndexedParallelIterator};
 use rfd::FileDialog;
 use serde::{Serialize, Deserialize};
 
-use crate::{vector::Vector3, event_manager::EventManager, quaternion::Quaternion};
+use crate::{vector::{Vector3, Float}, event_manager::EventManager, bindings::{Bindings, Action, Profile}, quaternion::Quaternion, animation::Animation, scene_file::{self, SceneError, SceneFormat}, gltf_import};
 
 pub type RGB = [f32; 3];
 
+// Keeps zooming in from passing through the orbit point.
+const MIN_ORBIT_DISTANCE: Float = 0.1;
+// How far ahead the camera orbits when it has no target.
+const DEFAULT_ORBIT_DISTANCE: Float = 10.0;
+// Framing a single point or a flat object still leaves some room around it.
+const MIN_FRAME_RADIUS: Float = 0.5;
+
 #[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
 pub enum Object {
   Sphere(Sphere),
   Plane(Plane),
+  Mesh(Mesh),
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+impl Object {
+  pub fn position(&self) -> &Vector3 {
+    match self {
+      Object::Sphere(sphere) => &sphere.position,
+      Object::Plane(plane) => &plane.position,
+      Object::Mesh(mesh) => &mesh.position,
+    }
+  }
+
+  pub fn position_mut(&mut self) -> &mut Vector3 {
+    match self {
+      Object::Sphere(sphere) => &mut sphere.position,
+      Object::Plane(plane) => &mut plane.position,
+      Object::Mesh(mesh) => &mut mesh.position,
+    }
+  }
+
+  pub fn material(&self) -> &Material {
+    match self {
+      Object::Sphere(sphere) => &sphere.material,
+      Object::Plane(plane) => &plane.material,
+      Object::Mesh(mesh) => &mesh.material,
+    }
+  }
+
+  pub fn material_mut(&mut self) -> &mut Material {
+    match self {
+      Object::Sphere(sphere) => &mut sphere.material,
+      Object::Plane(plane) => &mut plane.material,
+      Object::Mesh(mesh) => &mut mesh.material,
+    }
+  }
+
+  pub fn material_name(&self) -> Option<&str> {
+    match self {
+      Object::Sphere(sphere) => sphere.material_name.as_deref(),
+      Object::Plane(plane) => plane.material_name.as_deref(),
+      Object::Mesh(mesh) => mesh.material_name.as_deref(),
+    }
+  }
+
+  pub fn set_material_name(&mut self, name: Option<String>) {
+    match self {
+      Object::Sphere(sphere) => sphere.material_name = name,
+      Object::Plane(plane) => plane.material_name = name,
+      Object::Mesh(mesh) => mesh.material_name = name,
+    }
+  }
+
+  pub fn node(&self) -> &Node {
+    match self {
+      Object::Sphere(sphere) => &sphere.node,
+      Object::Plane(plane) => &plane.node,
+      Object::Mesh(mesh) => &mesh.node,
+    }
+  }
+
+  pub fn node_mut(&mut self) -> &mut Node {
+    match self {
+      Object::Sphere(sphere) => &mut sphere.node,
+      Object::Plane(plane) => &mut plane.node,
+      Object::Mesh(mesh) => &mut mesh.node,
+    }
+  }
+
+  // Corners of the box around the object, in world space.
+  pub fn bounds(&self) -> Option<[Vector3; 2]> {
+    match self {
+      Object::Sphere(sphere) => {
+        let radius = Vector3::new(sphere.radius, sphere.radius, sphere.radius);
+        Some([sphere.position - radius, sphere.position + radius])
+      },
+      Object::Plane(plane) => {
+        let half = Vector3::new(plane.width / 2.0, 0.0, plane.height / 2.0);
+        Some([plane.position - half, plane.position + half])
+      },
+      Object::Mesh(mesh) => mesh.bounds().map(|[min, max]| [min + mesh.position, max + mesh.position]),
+    }
+  }
+}
+
+impl ToString for Object {
+  fn to_string(&self) -> String {
+    match *self {
+      Object::Sphere(_) => String::from("Sphere"),
+      Object::Plane(_) => String::from("Plane"),
+      Object::Mesh(_) => String::from("Mesh"),
+    }
+  }
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
 pub enum Light {
   Ambient(AmbientLight),
   Directional(DirectionalLight),
@@ -32,23 +130,60 @@ impl ToString for Light {
   }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+impl Light {
+  pub fn node(&self) -> &Node {
+    match self {
+      Light::Ambient(light) => &light.node,
+      Light::Directional(light) => &light.node,
+      Light::Point(light) => &light.node,
+    }
+  }
+
+  pub fn node_mut(&mut self) -> &mut Node {
+    match self {
+      Light::Ambient(light) => &mut light.node,
+      Light::Directional(light) => &mut light.node,
+      Light::Point(light) => &mut light.node,
+    }
+  }
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AmbientLight {
   pub color: RGB,
+  #[serde(flatten)]
+  pub node: Node,
+}
+
+impl Default for AmbientLight {
+  fn default() -> Self {
+    AmbientLight::new()
+  }
 }
 
 impl AmbientLight {
   pub fn new() -> AmbientLight {
     AmbientLight { 
-      color: [0.1, 0.05, 0.05]
+      color: [0.1, 0.05, 0.05],
+      node: Node::new(),
     }
   }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PointLight {
   pub color: RGB,
   pub position: Vector3,
+  #[serde(flatten)]
+  pub node: Node,
+}
+
+impl Default for PointLight {
+  fn default() -> Self {
+    PointLight::new()
+  }
 }
 
 impl PointLight {
@@ -56,14 +191,24 @@ impl PointLight {
     PointLight {
       color: [1.0, 1.0, 1.0],
       position: Vector3::new(80.0, 60.0, -40.0),
+      node: Node::new(),
     }
   }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DirectionalLight {
   pub color: RGB,
   pub direction: Vector3,
+  #[serde(flatten)]
+  pub node: Node,
+}
+
+impl Default for DirectionalLight {
+  fn default() -> Self {
+    DirectionalLight::new()
+  }
 }
 
 impl DirectionalLight {
@@ -71,22 +216,30 @@ impl DirectionalLight {
     DirectionalLight {
       color: [1.0, 1.0, 1.0],
       direction: Vector3::new(0.5, 0.5, -10.0),
+      node: Node::new(),
     }
   }
 }
 
 #[derive(PartialEq, Copy, Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
 pub struct Material {
-  pub ambient_reflection: f64,
+  pub ambient_reflection: Float,
   pub has_specular: bool,
-  pub specular_reflection: f64,
-  pub diffuse_reflection: f64,
-  pub reflectivity: f64,
-  pub transparency: f64,
-  pub refractive_index: f64,
+  pub specular_reflection: Float,
+  pub diffuse_reflection: Float,
+  pub reflectivity: Float,
+  pub transparency: Float,
+  pub refractive_index: Float,
   pub color: RGB,
 }
 
+impl Default for Material {
+  fn default() -> Self {
+    Material::new()
+  }
+}
+
 impl Material {
   pub fn new() -> Material {
     Material {
@@ -100,14 +253,75 @@ impl Material {
       refractive_index: 1.0,
     }
   }
+
+  pub const PRESETS: [&'static str; 5] = ["glass", "chrome", "plastic", "rubber", "water"];
+
+  pub fn preset(name: &str) -> Option<Material> {
+    let material = match name {
+      "glass" => Material {
+        ambient_reflection: 0.1,
+        diffuse_reflection: 0.1,
+        specular_reflection: 80.0,
+        reflectivity: 0.1,
+        transparency: 0.9,
+        refractive_index: 1.5,
+        color: [0.95, 0.97, 1.0],
+        ..Material::new()
+      },
+      "chrome" => Material {
+        ambient_reflection: 0.2,
+        diffuse_reflection: 0.2,
+        specular_reflection: 100.0,
+        reflectivity: 0.85,
+        color: [0.85, 0.85, 0.9],
+        ..Material::new()
+      },
+      "plastic" => Material {
+        specular_reflection: 30.0,
+        reflectivity: 0.05,
+        color: [0.8, 0.15, 0.1],
+        ..Material::new()
+      },
+      "rubber" => Material {
+        has_specular: false,
+        diffuse_reflection: 0.9,
+        color: [0.15, 0.15, 0.15],
+        ..Material::new()
+      },
+      "water" => Material {
+        ambient_reflection: 0.2,
+        diffuse_reflection: 0.2,
+        specular_reflection: 60.0,
+        reflectivity: 0.15,
+        transparency: 0.8,
+        refractive_index: 1.33,
+        color: [0.6, 0.8, 1.0],
+        ..Material::new()
+      },
+      _ => return None,
+    };
+
+    Some(material)
+  }
 }
 
 #[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
 pub struct Plane {
   pub position: Vector3,
-  pub width: f64,
-  pub height: f64,
+  pub width: Float,
+  pub height: Float,
   pub material: Material,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub material_name: Option<String>,
+  #[serde(flatten)]
+  pub node: Node,
+}
+
+impl Default for Plane {
+  fn default() -> Self {
+    Plane::new()
+  }
 }
 
 impl Plane {
@@ -116,15 +330,22 @@ impl Plane {
       position: Vector3::new(0.0, -2.0, 10.0),
       width: 100.0,
       height: 100.0,
-      material: Material::new()
+      material: Material::new(),
+      material_name: None,
+      node: Node::new(),
     }
   }
 
-  pub fn trace_ray(&self, ray: &Ray) -> Option<f64> {
+  pub fn trace_ray(&self, ray: &Ray) -> Option<Float> {
     let origin = self.position - ray.position;
     let up = Vector3::new(0.0, 1.0, 0.0);
 
     let t = origin.dot(&up) / ray.direction.dot(&up);
+
+    if !t.is_finite() {
+      return None;
+    }
+
     let position = ray.position_from_distance(t);
 
     if 
@@ -145,10 +366,21 @@ impl Plane {
 }
 
 #[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Sphere {
   pub position: Vector3,
-  pub radius: f64,
+  pub radius: Float,
   pub material: Material,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub material_name: Option<String>,
+  #[serde(flatten)]
+  pub node: Node,
+}
+
+impl Default for Sphere {
+  fn default() -> Self {
+    Sphere::new()
+  }
 }
 
 impl Sphere {
@@ -156,11 +388,13 @@ impl Sphere {
     Sphere {
       position: Vector3::new(0.0, 0.0, 10.0),
       radius: 2.0,
-      material: Material::new()
+      material: Material::new(),
+      material_name: None,
+      node: Node::new(),
     }
   }
 
-  pub fn trace_ray(&self, ray: &Ray) -> [Option<f64>; 2] {
+  pub fn trace_ray(&self, ray: &Ray) -> [Option<Float>; 2] {
     let origin = ray.position - self.position;
 
     let a = ray.direction.dot(&ray.direction);
@@ -169,18 +403,18 @@ impl Sphere {
 
     let discriminant = b.powi(2) - 4.0 * a * c;
 
-    if discriminant < 0_f64 {
+    if discriminant < 0.0 {
       return [None, None];
     }
 
-    let t1 = (-b + discriminant.sqrt()) / (2_f64 * a);
-    let t2 = (-b - discriminant.sqrt()) / (2_f64 * a);
+    let t1 = (-b + discriminant.sqrt()) / (2.0 * a);
+    let t2 = (-b - discriminant.sqrt()) / (2.0 * a);
 
-    if t1 < 0.01_f64 {
+    if t1 < 0.01 {
       return [None, None];
     }
 
-    if t2 < 0.01_f64 {
+    if t2 < 0.01 {
       return [Some(t1), None];
     }
 
@@ -188,11 +422,214 @@ impl Sphere {
   }
 }
 
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Selection {
+  Object(usize),
+  Light(usize),
+  Camera,
+}
+
+impl ToString for Selection {
+  fn to_string(&self) -> String {
+    match *self {
+      Selection::Object(index) => format!("Object {}", index),
+      Selection::Light(index) => format!("Light {}", index),
+      Selection::Camera => String::from("Camera"),
+    }
+  }
+}
+
+// Outliner details shared by objects and lights, stored inline with their other fields.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Node {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub name: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub group: Option<String>,
+  // Hidden objects and lights are left out of renders.
+  #[serde(skip_serializing_if = "is_false")]
+  pub hidden: bool,
+  // Locked ones can't be picked in the viewport or moved with the gizmo.
+  #[serde(skip_serializing_if = "is_false")]
+  pub locked: bool,
+}
+
+impl Default for Node {
+  fn default() -> Self {
+    Node::new()
+  }
+}
+
+impl Node {
+  pub fn new() -> Node {
+    Node {
+      name: None,
+      group: None,
+      hidden: false,
+      locked: false,
+    }
+  }
+}
+
+fn is_false(value: &bool) -> bool {
+  !*value
+}
+
+pub type Triangle = [Vector3; 3];
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Mesh {
+  pub position: Vector3,
+  pub triangles: Vec<Triangle>,
+  pub material: Material,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub material_name: Option<String>,
+  #[serde(flatten)]
+  pub node: Node,
+  #[serde(skip)]
+  bounds: Option<[Vector3; 2]>,
+}
+
+impl Default for Mesh {
+  fn default() -> Self {
+    Mesh::new(Vec::new())
+  }
+}
+
+impl Mesh {
+  pub fn new(triangles: Vec<Triangle>) -> Mesh {
+    let mut mesh = Mesh {
+      position: Vector3::new(0.0, 0.0, 0.0),
+      triangles,
+      material: Material::new(),
+      material_name: None,
+      node: Node::new(),
+      bounds: None,
+    };
+
+    mesh.update_bounds();
+    mesh
+  }
+
+  pub fn update_bounds(&mut self) {
+    let mut bounds: Option<[Vector3; 2]> = None;
+
+    for vertex in self.triangles.iter().flat_map(|triangle| triangle.iter()) {
+      bounds = Some(match bounds {
+        Some([min, max]) => [
+          Vector3::new(min.x.min(vertex.x), min.y.min(vertex.y), min.z.min(vertex.z)),
+          Vector3::new(max.x.max(vertex.x), max.y.max(vertex.y), max.z.max(vertex.z)),
+        ],
+        None => [*vertex, *vertex],
+      });
+    }
+
+    self.bounds = bounds;
+  }
+
+  pub fn bounds(&self) -> Option<[Vector3; 2]> {
+    self.bounds
+  }
+
+  pub fn trace_ray(&self, ray: &Ray) -> Option<(Float, Vector3)> {
+    self.trace_ray_counted(ray, &mut 0)
+  }
+
+  // Adds the bounding box and every triangle that was tested to `tests`.
+  pub fn trace_ray_counted(&self, ray: &Ray, tests: &mut u32) -> Option<(Float, Vector3)> {
+    let origin = ray.position - self.position;
+
+    if let Some(bounds) = self.bounds {
+      *tests += 1;
+
+      if !Mesh::hits_box(origin, ray.direction, bounds) {
+        return None;
+      }
+    }
+
+    *tests += self.triangles.len() as u32;
+
+    let mut closest: Option<(Float, Vector3)> = None;
+
+    for [a, b, c] in &self.triangles {
+      let edge1 = *b - *a;
+      let edge2 = *c - *a;
+
+      let p = ray.direction.cross(&edge2);
+      let determinant = edge1.dot(&p);
+
+      if determinant.abs() < 1e-9 {
+        continue;
+      }
+
+      let inverse = 1.0 / determinant;
+      let s = origin - *a;
+      let u = s.dot(&p) * inverse;
+
+      if !(0.0..=1.0).contains(&u) {
+        continue;
+      }
+
+      let q = s.cross(&edge1);
+      let v = ray.direction.dot(&q) * inverse;
+
+      if v < 0.0 || u + v > 1.0 {
+        continue;
+      }
+
+      let t = edge2.dot(&q) * inverse;
+
+      if t >= 0.01 && closest.is_none_or(|(closest, _)| t < closest) {
+        closest = Some((t, edge1.cross(&edge2).normalise()));
+      }
+    }
+
+    closest
+  }
+
+  fn hits_box(origin: Vector3, direction: Vector3, [min, max]: [Vector3; 2]) -> bool {
+    let mut near = Float::NEG_INFINITY;
+    let mut far = Float::INFINITY;
+
+    for (origin, direction, min, max) in [
+      (origin.x, direction.x, min.x, max.x),
+      (origin.y, direction.y, min.y, max.y),
+      (origin.z, direction.z, min.z, max.z),
+    ] {
+      let t1 = (min - origin) / direction;
+      let t2 = (max - origin) / direction;
+
+      near = near.max(t1.min(t2));
+      far = far.min(t1.max(t2));
+    }
+
+    near <= far && far >= 0.0
+  }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct World {
+  // Left out means none, rather than the demo scene World::new starts with.
+  #[serde(default)]
   objects: Vec<Object>,
+  #[serde(default)]
   lights: Vec<Light>,
   camera: Camera,
+  materials: BTreeMap<String, Material>,
+  animation: Animation,
+  #[serde(skip)]
+  frame: Float,
+  #[serde(skip)]
+  selection: Option<Selection>,
+}
+
+impl Default for World {
+  fn default() -> Self {
+    World::new()
+  }
 }
 
 impl World {
@@ -207,11 +644,15 @@ impl World {
         Light::Ambient(AmbientLight::new())
       ],
       camera: Camera::new(),
+      materials: BTreeMap::new(),
+      animation: Animation::new(),
+      frame: 0.0,
+      selection: None,
     }
   }
 
-  pub fn update(&mut self, event_manager: &EventManager) {
-    self.camera.update(event_manager);
+  pub fn update(&mut self, event_manager: &EventManager, bindings: &Bindings) {
+    self.camera.update(event_manager, bindings);
   }
 
   pub fn lights_mut(&mut self) -> &mut Vec<Light> {
@@ -230,6 +671,85 @@ impl World {
     &self.objects
   }
 
+  pub fn materials(&self) -> &BTreeMap<String, Material> {
+    &self.materials
+  }
+
+  pub fn materials_mut(&mut self) -> &mut BTreeMap<String, Material> {
+    &mut self.materials
+  }
+
+  pub fn animation(&self) -> &Animation {
+    &self.animation
+  }
+
+  pub fn animation_mut(&mut self) -> &mut Animation {
+    &mut self.animation
+  }
+
+  pub fn frame(&self) -> Float {
+    self.frame
+  }
+
+  pub fn set_frame(&mut self, frame: Float) {
+    let animation = std::mem::take(&mut self.animation);
+    animation.apply(self, frame);
+
+    self.animation = animation;
+    self.frame = frame;
+  }
+
+  // Copies named materials into the objects that use them, after the library was edited.
+  pub fn apply_materials(&mut self) {
+    for object in &mut self.objects {
+      let material = object.material_name().and_then(|name| self.materials.get(name)).copied();
+
+      if let Some(material) = material {
+        *object.material_mut() = material;
+      }
+    }
+  }
+
+  pub fn rename_material(&mut self, from: &str, to: &str) {
+    let material = match self.materials.remove(from) {
+      Some(material) => material,
+      None => return,
+    };
+
+    self.materials.insert(String::from(to), material);
+
+    for object in &mut self.objects {
+      if object.material_name() == Some(from) {
+        object.set_material_name(Some(String::from(to)));
+      }
+    }
+  }
+
+  // Objects keep their copy of a removed material.
+  pub fn remove_material(&mut self, name: &str) {
+    self.materials.remove(name);
+
+    for object in &mut self.objects {
+      if object.material_name() == Some(name) {
+        object.set_material_name(None);
+      }
+    }
+  }
+
+  // Objects whose material was edited by hand no longer follow their named material.
+  pub fn detach_edited_materials(&mut self) {
+    for object in &mut self.objects {
+      let edited = match object.material_name() {
+        Some(name) => self.materials.get(name) != Some(object.material()),
+        None => false,
+      };
+
+      if edited {
+        object.set_material_name(None);
+      }
+    }
+  }
+
   pub fn camera(&self) -> &Camera {
     &self.camera
   }
@@ -238,56 +758,252 @@ impl World {
     &mut self.camera
   }
 
-  pub fn save_world(&mut self) {
-    self.camera.rays.clear();
+  pub fn selection(&self) -> Option<Selection> {
+    self.selection
+  }
 
-    let file = FileDialog::new()
-      .add_filter("json", &["json"])
-      .set_directory("/")
-      .save_file();
+  pub fn select(&mut self, selection: Option<Selection>) {
+    self.selection = selection;
+  }
+
+  // A point light is framed as a point, the camera and the other lights have no bounds.
+  pub fn selection_bounds(&self) -> Option<[Vector3; 2]> {
+    match self.selection? {
+      Selection::Object(index) => self.objects.get(index)?.bounds(),
+      Selection::Light(index) => match self.lights.get(index)? {
+        Light::Point(light) => Some([light.position, light.position]),
+        _ => None,
+      },
+      Selection::Camera => None,
+    }
+  }
+
+  // Every visible object. Planes only count when there is nothing else, as a
+  // floor is usually much larger than what stands on it.
+  pub fn scene_bounds(&self) -> Option<[Vector3; 2]> {
+    let visible = || self.objects.iter().filter(|object| !object.node().hidden);
+    let solids = visible().filter(|object| !matches!(object, Object::Plane(_))).filter_map(Object::bounds);
+
+    union_bounds(solids).or_else(|| union_bounds(visible().filter_map(Object::bounds)))
+  }
+
+  // Both return false when there is nothing to frame.
+  pub fn frame_selected(&mut self) -> bool {
+    let bounds = self.selection_bounds();
+    self.frame_bounds(bounds)
+  }
 
-    if file.is_none() {
+  pub fn frame_all(&mut self) -> bool {
+    let bounds = self.scene_bounds();
+    self.frame_bounds(bounds)
+  }
+
+  fn frame_bounds(&mut self, bounds: Option<[Vector3; 2]>) -> bool {
+    match bounds {
+      Some(bounds) => {
+        self.camera.frame(bounds);
+        true
+      },
+      None => false,
+    }
+  }
+
+  // Moves an object to another place in the list, the selection and animation
+  // tracks keep pointing at the same objects.
+  pub fn move_object(&mut self, from: usize, to: usize) {
+    if from >= self.objects.len() || to >= self.objects.len() {
       return;
     }
 
-    let file = file.unwrap();
+    let object = self.objects.remove(from);
+    self.objects.insert(to, object);
 
-    serde_json::to_writer(&File::create(file.as_path()).unwrap(), self).unwrap();
+    self.animation.reindex(|object| moved_index(object, from, to), |light| light);
 
-    self.camera.calc_rays();
+    if let Some(Selection::Object(object)) = self.selection {
+      self.selection = Some(Selection::Object(moved_index(object, from, to)));
+    }
   }
 
-  pub fn open_world(&mut self) {
+  pub fn move_light(&mut self, from: usize, to: usize) {
+    if from >= self.lights.len() || to >= self.lights.len() {
+      return;
+    }
+
+    let light = self.lights.remove(from);
+    self.lights.insert(to, light);
+
+    self.animation.reindex(|object| object, |light| moved_index(light, from, to));
+
+    if let Some(Selection::Light(light)) = self.selection {
+      self.selection = Some(Selection::Light(moved_index(light, from, to)));
+    }
+  }
+
+  // Removes an object with its animation tracks. Tracks and the selection of
+  // later objects move down with them, a removed selection is cleared.
+  pub fn remove_object(&mut self, index: usize) -> Option<Object> {
+    if index >= self.objects.len() {
+      return None;
+    }
+
+    let object = self.objects.remove(index);
+
+    self.animation.tracks.retain(|track| track.object() != Some(index));
+    self.animation.reindex(|object| removed_index(object, index), |light| light);
+
+    self.selection = match self.selection {
+      Some(Selection::Object(object)) if object == index => None,
+      Some(Selection::Object(object)) => Some(Selection::Object(removed_index(object, index))),
+      selection => selection,
+    };
+
+    Some(object)
+  }
+
+  // Puts an object back where it was removed from, later objects move up.
+  pub fn insert_object(&mut self, index: usize, object: Object) {
+    let index = index.min(self.objects.len());
+    self.objects.insert(index, object);
+
+    self.animation.reindex(|object| inserted_index(object, index), |light| light);
+
+    if let Some(Selection::Object(object)) = self.selection {
+      self.selection = Some(Selection::Object(inserted_index(object, index)));
+    }
+  }
+
+  pub fn remove_light(&mut self, index: usize) -> Option<Light> {
+    if index >= self.lights.len() {
+      return None;
+    }
+
+    let light = self.lights.remove(index);
+
+    self.animation.tracks.retain(|track| track.light() != Some(index));
+    self.animation.reindex(|object| object, |light| removed_index(light, index));
+
+    self.selection = match self.selection {
+      Some(Selection::Light(light)) if light == index => None,
+      Some(Selection::Light(light)) => Some(Selection::Light(removed_index(light, index))),
+      selection => selection,
+    };
+
+    Some(light)
+  }
+
+  pub fn insert_light(&mut self, index: usize, light: Light) {
+    let index = index.min(self.lights.len());
+    self.lights.insert(index, light);
+
+    self.animation.reindex(|object| object, |light| inserted_index(light, index));
+
+    if let Some(Selection::Light(light)) = self.selection {
+      self.selection = Some(Selection::Light(inserted_index(light, index)));
+    }
+  }
+
+  pub fn same_scene(&self, other: &World) -> bool {
+    self.objects == other.objects &&
+    self.lights == other.lights &&
+    self.camera.camera_info == other.camera.camera_info &&
+    self.camera.depth == other.camera.depth
+  }
+
+  pub fn save_world(&mut self) -> Result<(), SceneError> {
+    let file = World::scene_dialog().save_file();
+
+    match file {
+      Some(file) => scene_file::save(file.as_path(), self),
+      None => Ok(()),
+    }
+  }
+
+  pub fn open_world(&mut self) -> Result<(), SceneError> {
+    let file = World::scene_dialog().pick_file();
+
+    let file = match file {
+      Some(file) => file,
+      None => return Ok(()),
+    };
+
+    self.replace(scene_file::load(file.as_path())?);
+
+    Ok(())
+  }
+
+  pub fn import_gltf(&mut self) -> Result<(), SceneError> {
     let file = FileDialog::new()
-      .add_filter("json", &["json"])
+      .add_filter("glTF", &gltf_import::EXTENSIONS)
       .set_directory("/")
       .pick_file();
 
-    if file.is_none() {
-      return;
-    }
+    let file = match file {
+      Some(file) => file,
+      None => return Ok(()),
+    };
+
+    self.replace(gltf_import::import(file.as_path())?);
 
-    let file = file.unwrap();
+    Ok(())
+  }
 
-    let file = File::open(file.as_path());
-    let reader = BufReader::new(file.unwrap());
+  fn replace(&mut self, data: World) {
+    self.camera = data.camera;
+    self.lights = data.lights;
+    self.objects = data.objects;
+    self.materials = data.materials;
+    self.animation = data.animation;
+    self.frame = 0.0;
+    self.selection = None;
+  }
 
-    let world: Result<World, serde_json::Error> = serde_json::from_reader(reader);
+  fn scene_dialog() -> FileDialog {
+    let mut dialog = FileDialog::new().set_directory("/");
 
-    match world {
-      Ok(data) => {
-        self.camera = data.camera;
-        self.lights = data.lights;
-        self.objects = data.objects;
-        self.camera.calc_rays();
-      },
-      Err(err) => {
-        println!("{}", err);
-      }
+    for format in SceneFormat::ALL {
+      dialog = dialog.add_filter(&format.to_string(), format.extensions());
     }
+
+    dialog
+  }
+}
+
+fn moved_index(index: usize, from: usize, to: usize) -> usize {
+  if index == from {
+    to
+  } else if from < index && index <= to {
+    index - 1
+  } else if to <= index && index < from {
+    index + 1
+  } else {
+    index
+  }
+}
+
+fn removed_index(index: usize, removed: usize) -> usize {
+  if index > removed {
+    index - 1
+  } else {
+    index
+  }
+}
+
+fn inserted_index(index: usize, inserted: usize) -> usize {
+  if index >= inserted {
+    index + 1
+  } else {
+    index
   }
 }
 
+fn union_bounds(bounds: impl Iterator<Item = [Vector3; 2]>) -> Option<[Vector3; 2]> {
+  bounds.reduce(|[min_a, max_a], [min_b, max_b]| [
+    Vector3::new(min_a.x.min(min_b.x), min_a.y.min(min_b.y), min_a.z.min(min_b.z)),
+    Vector3::new(max_a.x.max(max_b.x), max_a.y.max(max_b.y), max_a.z.max(max_b.z)),
+  ])
+}
+
 #[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum CameraType {
   Orthographic, Perspective
@@ -304,9 +1020,10 @@ impl ToString for CameraType {
 }
 
 #[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CameraInfo {
-  pub camera_height: f64,
-  pub vertical_fov: f64,
+  pub camera_height: Float,
+  pub vertical_fov: Float,
   pub camera_type: CameraType,
   pub viewport_width: u32,
   pub viewport_height: u32,
@@ -315,44 +1032,83 @@ pub struct CameraInfo {
   pub forward: Vector3,
 }
 
+impl Default for CameraInfo {
+  fn default() -> Self {
+    Self {
+      camera_height: 1.0,
+      vertical_fov: 1.0,
+      camera_type: CameraType::Perspective,
+      position: Vector3::new(0.0, 0.0, 0.0),
+      miss_color: [0.6, 0.8, 0.9],
+      forward: Vector3::new(0.0, 0.0, 1.0),
+      viewport_width: 720,
+      viewport_height: 480,
+    }
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DynamicResolution {
+  pub enabled: bool,
+  pub target_fps: f64,
+  pub min_scale: f64,
+  pub depth: u32,
+}
+
+impl Default for DynamicResolution {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      target_fps: 30.0,
+      min_scale: 0.25,
+      depth: 1,
+    }
+  }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Camera {
+  #[serde(skip)]
   camera_info_old: CameraInfo,
   camera_info: CameraInfo,
+  #[serde(skip)]
   rays: Vec<Ray>,
-  speed: f64,
+  speed: Float,
   depth: u32,
+  #[serde(skip)]
   moved: bool,
+  // The point the orbit profile turns around and zooms towards, when None it
+  // is a fixed distance in front of the camera.
+  #[serde(skip)]
+  target: Option<Vector3>,
+  dynamic_resolution: DynamicResolution,
+}
+
+impl Default for Camera {
+  fn default() -> Self {
+    Camera::new()
+  }
 }
 
 impl Camera {
   pub fn new() -> Camera {
-    let camera_info = CameraInfo {
-      camera_height: 1.0,
-      vertical_fov: 1.0,
-      camera_type: CameraType::Perspective,
-      position: Vector3::new(0.0, 0.0, 0.0),
-      miss_color: [0.6, 0.8, 0.9],
-      forward: Vector3::new(0.0, 0.0, 1.0),
-      viewport_width: 720,
-      viewport_height: 480,
-    };
+    let camera_info = CameraInfo::default();
 
-    let mut cam = Camera {
+    Camera {
       camera_info_old: camera_info,
       camera_info,
       depth: 5,
       rays: Vec::new(),
       speed: 0.1,
       moved: false,
-    };
-
-    cam.calc_rays();
-
-    cam
+      target: None,
+      dynamic_resolution: DynamicResolution::default(),
+    }
   }
 
-  pub fn speed_mut(&mut self) -> &mut f64 {
+  pub fn speed_mut(&mut self) -> &mut Float {
     &mut self.speed
   }
 
@@ -364,6 +1120,18 @@ impl Camera {
     &mut self.depth
   }
 
+  pub fn moved(&self) -> bool {
+    self.moved
+  }
+
+  pub fn dynamic_resolution(&self) -> &DynamicResolution {
+    &self.dynamic_resolution
+  }
+
+  pub fn dynamic_resolution_mut(&mut self) -> &mut DynamicResolution {
+    &mut self.dynamic_resolution
+  }
+
   pub fn camera_info_mut(&mut self) -> &mut CameraInfo {
     &mut self.camera_info
   }
@@ -372,7 +1140,37 @@ impl Camera {
     &self.camera_info
   }
 
-  pub fn update(&mut self, event_manager: &EventManager) {
+  pub fn target(&self) -> Option<Vector3> {
+    self.target
+  }
+
+  pub fn set_target(&mut self, target: Option<Vector3>) {
+    self.target = target;
+  }
+
+  // Keeps the view direction and backs away from the centre of the box until
+  // all of it fits, then orbits around that centre.
+  pub fn frame(&mut self, [min, max]: [Vector3; 2]) {
+    let center = (min + max) / 2.0;
+    let radius = ((max - min).mag() / 2.0).max(MIN_FRAME_RADIUS);
+
+    let distance = match self.camera_info.camera_type {
+      CameraType::Perspective => {
+        let tangent = self.camera_info.vertical_fov.min(self.horizontal_fov());
+        radius / tangent.atan().sin()
+      },
+      CameraType::Orthographic => {
+        let aspect = self.camera_info.viewport_width as Float / self.camera_info.viewport_height as Float;
+        self.camera_info.camera_height = radius / aspect.min(1.0);
+        radius * 2.0
+      },
+    };
+
+    self.camera_info.position = center - self.camera_info.forward.normalise() * distance;
+    self.target = Some(center);
+  }
+
+  pub fn update(&mut self, event_manager: &EventManager, bindings: &Bindings) {
     self.moved = false;
 
     if self.camera_info != self.camera_info_old {
@@ -381,55 +1179,107 @@ impl Camera {
 
     self.camera_info_old = self.camera_info;
 
-    if event_manager.is_left_mouse_down() {
-      let (yaw, pitch) = event_manager.mouse_move();
+    let controls = bindings.controls();
+    let (mouse_x, mouse_y) = event_manager.mouse_move();
+    let step = self.speed * bindings.speed_factor(event_manager);
 
-      if yaw != 0.0 || pitch != 0.0 {
-        let pitch = pitch * 0.05 * self.speed;
-        let yaw = yaw * 0.05 * self.speed;
+    if mouse_x != 0.0 || mouse_y != 0.0 {
+      if controls.is_active(Action::Rotate, event_manager) {
+        let yaw = mouse_x as Float * 0.05 * self.speed;
+        let pitch = mouse_y as Float * 0.05 * self.speed;
 
-        let forward = self.camera_info.forward;
-        let right = self.right();
-        let up = self.up();
+        match bindings.profile {
+          Profile::Fly => self.look(yaw, pitch),
+          Profile::Orbit => self.orbit(yaw, pitch),
+        }
+
+        self.moved = true;
+      } else if controls.is_active(Action::Pan, event_manager) {
+        // The scene follows the mouse.
+        let offset = (self.right() * mouse_x as Float - self.up() * mouse_y as Float) * 0.05 * step;
+        self.camera_info.position = self.camera_info.position + offset;
+        self.target = self.target.map(|target| target + offset);
+        self.moved = true;
+      }
+    }
+
+    let scroll = event_manager.scroll() as Float;
 
-        let q_pitch = Quaternion::from_angle_axis(pitch, right);
-        let q_yaw = Quaternion::from_angle_axis(yaw, up);
-        let p = Quaternion::from_vector_3(forward);
+    if scroll != 0.0 {
+      match bindings.profile {
+        Profile::Fly => self.speed *= bindings.wheel_speed.powf(scroll),
+        Profile::Orbit => self.zoom((1.0 - bindings.wheel_zoom).powf(scroll)),
+      }
 
-        let q = q_pitch * q_yaw;
+      self.moved |= bindings.profile == Profile::Orbit;
+    }
 
-        self.camera_info.forward = (q * p * q.inverse()).to_vector_3();
+    let movements = [
+      (Action::Forward, Action::Back, self.camera_info.forward),
+      (Action::Right, Action::Left, self.right()),
+      (Action::Up, Action::Down, self.up()),
+    ];
 
+    for (positive, negative, axis) in movements {
+      if controls.is_active(positive, event_manager) {
+        self.camera_info.position = self.camera_info.position + axis * step;
+        self.moved = true;
+      } else if controls.is_active(negative, event_manager) {
+        self.camera_info.position = self.camera_info.position - axis * step;
         self.moved = true;
       }
     }
+  }
 
-    if event_manager.is_key_down(VirtualKeyCode::W) {
-      self.camera_info.position = self.camera_info.position + self.camera_info.forward * self.speed;
-      self.moved = true;
-    } else if event_manager.is_key_down(VirtualKeyCode::S) {
-      self.camera_info.position = self.camera_info.position - self.camera_info.forward * self.speed;
-      self.moved = true;
-    }
+  fn look(&mut self, yaw: Float, pitch: Float) {
+    let q_pitch = Quaternion::from_angle_axis(pitch, self.right());
+    let q_yaw = Quaternion::from_angle_axis(yaw, self.up());
+    let q = q_pitch * q_yaw;
 
-    if event_manager.is_key_down(VirtualKeyCode::D) {
-      self.camera_info.position = self.camera_info.position + self.right() * self.speed;
-      self.moved = true;
-    } else if event_manager.is_key_down(VirtualKeyCode::A) {
-      self.camera_info.position = self.camera_info.position - self.right() * self.speed;
-      self.moved = true;
-    }
+    self.camera_info.forward = q.rotate(self.camera_info.forward);
+  }
 
-    if event_manager.is_key_down(VirtualKeyCode::Q) {
-      self.camera_info.position = self.camera_info.position + self.up() * self.speed;
-      self.moved = true;
-    } else if event_manager.is_key_down(VirtualKeyCode::E) {
-      self.camera_info.position = self.camera_info.position - self.up() * self.speed;
-      self.moved = true;
+  // Turns the camera like look does and swings it around the orbit point by the
+  // same rotation. A target set by picking or framing is faced first.
+  fn orbit(&mut self, yaw: Float, pitch: Float) {
+    let pivot = self.orbit_pivot();
+    self.look_at(pivot);
+
+    let q_pitch = Quaternion::from_angle_axis(pitch, self.right());
+    let q_yaw = Quaternion::from_angle_axis(yaw, self.up());
+    let q = q_pitch * q_yaw;
+
+    self.camera_info.position = pivot + q.rotate(self.camera_info.position - pivot);
+    self.camera_info.forward = q.rotate(self.camera_info.forward);
+  }
+
+  // Moves towards the orbit point, scaling the distance to it. Without a target
+  // the point in front of the camera becomes one, so zooming doesn't carry it along.
+  fn zoom(&mut self, factor: Float) {
+    let pivot = self.orbit_pivot();
+    self.look_at(pivot);
+
+    let offset = self.camera_info.position - pivot;
+    let distance = (offset.mag() * factor).max(MIN_ORBIT_DISTANCE);
+    let direction = if offset.mag() > 0.0 { offset.normalise() } else { -self.camera_info.forward.normalise() };
+
+    self.camera_info.position = pivot + direction * distance;
+    self.target = Some(pivot);
+  }
+
+  // Keeps the length of forward, which the rays are built from.
+  fn look_at(&mut self, point: Vector3) {
+    let offset = point - self.camera_info.position;
+
+    if offset.mag() > 0.0 {
+      self.camera_info.forward = offset.normalise() * self.camera_info.forward.mag();
     }
+  }
 
-    if self.moved {
-      self.calc_rays()
+  fn orbit_pivot(&self) -> Vector3 {
+    match self.target {
+      Some(target) => target,
+      None => self.camera_info.position + self.camera_info.forward.normalise() * DEFAULT_ORBIT_DISTANCE,
     }
   }
 
@@ -446,12 +1296,12 @@ impl Camera {
     self.camera_info.forward.cross(&plane_up).normalise()
   }
 
-  fn horizontal_fov(&self) -> f64 {
-    self.camera_info.viewport_width as f64 * self.camera_info.vertical_fov / self.camera_info.viewport_height as f64
+  fn horizontal_fov(&self) -> Float {
+    self.camera_info.viewport_width as Float * self.camera_info.vertical_fov / self.camera_info.viewport_height as Float
   }
 
-  fn camera_width(&self) -> f64 {
-    self.camera_info.viewport_width as f64 * self.camera_info.camera_height / self.camera_info.viewport_height as f64
+  fn camera_width(&self) -> Float {
+    self.camera_info.viewport_width as Float * self.camera_info.camera_height / self.camera_info.viewport_height as Float
   }
 
   pub fn calc_rays(&mut self) {
@@ -461,6 +1311,58 @@ impl Camera {
     }
   }
 
+  // x and y run from 0 to 1 across the viewport, with y = 0 on the first row of rays.
+  pub fn ray(&self, x: Float, y: Float) -> Ray {
+    self.ray_at(x, y, self.right(), self.up())
+  }
+
+  // The inverse of ray, None for points behind a perspective camera.
+  pub fn project(&self, point: Vector3) -> Option<(Float, Float)> {
+    let offset = point - self.camera_info.position;
+    let (right, up) = (self.right(), self.up());
+
+    let (view_x, view_y) = match self.camera_info.camera_type {
+      CameraType::Perspective => {
+        let forward = self.camera_info.forward;
+        let scale = offset.dot(&forward) / forward.dot(&forward);
+
+        if scale <= 0.0 {
+          return None;
+        }
+
+        (offset.dot(&right) / scale / self.horizontal_fov(), offset.dot(&up) / scale / self.camera_info.vertical_fov)
+      },
+      CameraType::Orthographic => {
+        (offset.dot(&right) / self.camera_width(), offset.dot(&up) / self.camera_info.camera_height)
+      },
+    };
+
+    Some(((view_x + 1.0) / 2.0, (view_y + 1.0) / 2.0))
+  }
+
+  fn ray_at(&self, x: Float, y: Float, right: Vector3, up: Vector3) -> Ray {
+    match self.camera_info.camera_type {
+      CameraType::Perspective => {
+        let view_y = self.camera_info.vertical_fov * (2.0 * y - 1.0);
+        let view_x = self.horizontal_fov() * (2.0 * x - 1.0);
+
+        Ray {
+          direction: (right * view_x + up * view_y + self.camera_info.forward).normalise(),
+          position: self.camera_info.position,
+        }
+      },
+      CameraType::Orthographic => {
+        let view_y = y * 2.0 - 1.0;
+        let view_x = x * 2.0 - 1.0;
+
+        Ray {
+          direction: self.camera_info.forward.normalise(),
+          position: self.camera_info.position + (right * view_x * self.camera_width() + up * view_y * self.camera_info.camera_height),
+        }
+      },
+    }
+  }
+
   fn calc_perspective_rays(&mut self) {
     let ray_count = self.camera_info.viewport_width as usize * self.camera_info.viewport_height as usize;
 
@@ -468,27 +1370,24 @@ impl Camera {
       self.rays.resize(ray_count, Ray::default());
     }
 
-    let v_fov = self.camera_info.vertical_fov as f64;
-    let h_fov = self.horizontal_fov() as f64;
-
     let right = self.right();
     let up = self.up();
 
-    self.rays.par_iter_mut().enumerate().for_each(|(i, ray)| {
+    let mut rays = std::mem::take(&mut self.rays);
+
+    rays.par_iter_mut().enumerate().for_each(|(i, ray)| {
       let sample_width = self.camera_info.viewport_width;
 
       let y = i as u32 / sample_width;
       let x = i as u32 % sample_width;
 
-      let x = x as f64 / self.camera_info.viewport_width as f64;
-      let y = y as f64 / self.camera_info.viewport_height as f64;
-
-      let view_y = v_fov * (2.0 * y - 1.0);
-      let view_x = h_fov * (2.0 * x - 1.0);
+      let x = x as Float / self.camera_info.viewport_width as Float;
+      let y = y as Float / self.camera_info.viewport_height as Float;
 
-      ray.direction = (right * view_x + up * view_y + self.camera_info.forward).normalise();
-      ray.position = self.camera_info.position;
+      *ray = self.ray_at(x, y, right, up);
     });
+
+    self.rays = rays;
   }
 
   fn calc_orthographic_rays(&mut self) {
@@ -498,24 +1397,24 @@ impl Camera {
       self.rays.resize(ray_count, Ray::default());
     }
 
-    let camera_height = self.camera_info.camera_height;
-    let camera_width = self.camera_width();
-
     let right = self.right();
     let up = self.up();
 
+    let mut rays = std::mem::take(&mut self.rays);
+
     for y in 0..self.camera_info.viewport_height {
-      let view_y = (y as f64 / self.camera_info.viewport_height as f64) * 2.0 - 1.0;
+      let view_y = y as Float / self.camera_info.viewport_height as Float;
 
       for x in 0..self.camera_info.viewport_width {
         let i = (y * self.camera_info.viewport_width + x) as usize;
 
-        let view_x = (x as f64 / self.camera_info.viewport_width as f64) * 2.0 - 1.0;
+        let view_x = x as Float / self.camera_info.viewport_width as Float;
 
-        self.rays[i].direction = self.camera_info.forward.normalise();
-        self.rays[i].position = self.camera_info.position + (right * view_x * camera_width + up * view_y * camera_height);
+        rays[i] = self.ray_at(view_x, view_y, right, up);
       }
     }
+
+    self.rays = rays;
   }
 }
 
@@ -535,7 +1434,7 @@ impl Default for Ray {
 }
 
 impl Ray {
-  pub fn position_from_distance(&self, distance: f64) -> Vector3 {
+  pub fn position_from_distance(&self, distance: Float) -> Vector3 {
     self.position + self.direction * distance
   }
 }
@@ -543,14 +1442,14 @@ impl Ray {
 pub struct RayIntersection<'a> {
   ray: &'a Ray,
   material: &'a Material,
-  distance: f64,
+  distance: Float,
   normal: Vector3,
-  n1: f64,
-  n2: f64,
+  n1: Float,
+  n2: Float,
 }
 
 impl<'a> RayIntersection <'a>{
-  pub fn new(ray: &'a Ray, material: &'a Material, distance: f64, normal: Vector3, n1: f64, n2: f64) -> RayIntersection<'a> {
+  pub fn new(ray: &'a Ray, material: &'a Material, distance: Float, normal: Vector3, n1: Float, n2: Float) -> RayIntersection<'a> {
     RayIntersection { 
       ray,
       material, 
@@ -565,7 +1464,7 @@ impl<'a> RayIntersection <'a>{
     &self.material
   }
 
-  pub fn distance(&self) -> &f64 {
+  pub fn distance(&self) -> &Float {
     &self.distance
   }
 
@@ -573,11 +1472,11 @@ impl<'a> RayIntersection <'a>{
     &self.normal
   }
 
-  pub fn n1(&self) -> &f64 {
+  pub fn n1(&self) -> &Float {
     &self.n1
   }
 
-  pub fn n2(&self) -> &f64 {
+  pub fn n2(&self) -> &Float {
     &self.n2
   }
 
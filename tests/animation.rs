@@ -0,0 +1,182 @@
+use std::path::{Path, PathBuf};
+
+use ray_tracer::{
+  animation::{self, Animation, Track, Keyframe, Interpolation},
+  headless,
+  scene_file::{self, SceneFormat},
+  vector::{Vector3, Float},
+  world::{World, Object, Sphere, Light, Selection},
+};
+
+fn keys(values: &[(Float, Float)], interpolation: Interpolation) -> Vec<Keyframe<Float>> {
+  values.iter().map(|(frame, value)| Keyframe::new(*frame, *value, interpolation)).collect()
+}
+
+fn assert_close(actual: Float, expected: Float) {
+  assert!((actual - expected).abs() < 1e-4, "{} != {}", actual, expected);
+}
+
+#[test]
+fn samples_linear_keyframes() {
+  let keyframes = keys(&[(0.0, 0.0), (10.0, 5.0), (20.0, -5.0)], Interpolation::Linear);
+
+  assert_close(animation::sample(&keyframes, 5.0).unwrap(), 2.5);
+  assert_close(animation::sample(&keyframes, 10.0).unwrap(), 5.0);
+  assert_close(animation::sample(&keyframes, 15.0).unwrap(), 0.0);
+}
+
+#[test]
+fn clamps_outside_the_keyframes() {
+  let keyframes = keys(&[(10.0, 1.0), (20.0, 2.0)], Interpolation::Bezier);
+
+  assert_close(animation::sample(&keyframes, -5.0).unwrap(), 1.0);
+  assert_close(animation::sample(&keyframes, 50.0).unwrap(), 2.0);
+  assert_eq!(animation::sample::<Float>(&[], 0.0), None);
+}
+
+#[test]
+fn bezier_keyframes_ease_through_neighbours() {
+  let keyframes = keys(&[(0.0, 0.0), (10.0, 10.0), (20.0, 0.0)], Interpolation::Bezier);
+
+  // The middle keyframe is a peak, so its tangent is flat and the curve eases into it.
+  let linear = 7.5;
+  let eased = animation::sample(&keyframes, 7.5).unwrap();
+
+  assert!(eased > linear && eased < 10.0, "{}", eased);
+  assert_close(animation::sample(&keyframes, 10.0).unwrap(), 10.0);
+  assert_close(animation::sample(&keyframes, 12.5).unwrap(), eased);
+
+  // Evenly spaced keyframes on a line stay on the line.
+  let straight = keys(&[(0.0, 0.0), (10.0, 10.0), (20.0, 20.0)], Interpolation::Bezier);
+  assert_close(animation::sample(&straight, 3.0).unwrap(), 3.0);
+}
+
+#[test]
+fn inserting_keeps_keyframes_sorted() {
+  let mut keyframes = Vec::new();
+
+  for (frame, value) in [(10.0, 1.0), (0.0, 0.0), (5.0, 0.5), (10.0, 2.0)] {
+    animation::insert_keyframe(&mut keyframes, Keyframe::new(frame, value, Interpolation::Linear));
+  }
+
+  let frames: Vec<(Float, Float)> = keyframes.iter().map(|keyframe| (keyframe.frame, keyframe.value)).collect();
+
+  assert_eq!(frames, vec![(0.0, 0.0), (5.0, 0.5), (10.0, 2.0)]);
+}
+
+fn animated_world() -> World {
+  let mut world = World::new();
+
+  let animation = world.animation_mut();
+  animation.end = 10;
+  animation.tracks = vec![
+    Track::CameraPosition(vec![
+      Keyframe::new(0.0, Vector3::new(0.0, 0.0, 0.0), Interpolation::Linear),
+      Keyframe::new(10.0, Vector3::new(10.0, 0.0, 0.0), Interpolation::Linear),
+    ]),
+    Track::ObjectPosition { object: 0, keyframes: vec![
+      Keyframe::new(0.0, Vector3::new(0.0, 0.0, 10.0), Interpolation::Linear),
+      Keyframe::new(10.0, Vector3::new(0.0, 4.0, 10.0), Interpolation::Linear),
+    ] },
+    Track::ObjectColor { object: 0, keyframes: vec![
+      Keyframe::new(0.0, [1.0, 0.0, 0.0], Interpolation::Linear),
+      Keyframe::new(10.0, [0.0, 0.0, 1.0], Interpolation::Linear),
+    ] },
+    Track::LightColor { light: 1, keyframes: vec![
+      Keyframe::new(0.0, [0.0, 0.0, 0.0], Interpolation::Linear),
+    ] },
+    Track::LightPosition { light: 99, keyframes: vec![
+      Keyframe::new(0.0, Vector3::new(0.0, 0.0, 0.0), Interpolation::Linear),
+    ] },
+  ];
+
+  world
+}
+
+#[test]
+fn applies_tracks_to_the_world() {
+  let mut world = animated_world();
+  world.set_frame(5.0);
+
+  assert_eq!(world.frame(), 5.0);
+  assert_eq!(world.camera().camera_info().position, Vector3::new(5.0, 0.0, 0.0));
+  assert_eq!(*world.objects()[0].position(), Vector3::new(0.0, 2.0, 10.0));
+  assert_eq!(world.objects()[0].material().color, [0.5, 0.0, 0.5]);
+
+  match &world.lights()[1] {
+    Light::Ambient(light) => assert_eq!(light.color, [0.0, 0.0, 0.0]),
+    light => panic!("unexpected light {:?}", light),
+  }
+
+  assert!(matches!(world.objects()[1], Object::Plane(_)));
+}
+
+#[test]
+fn animations_survive_scene_files() {
+  let world = animated_world();
+
+  for format in SceneFormat::ALL {
+    let text = scene_file::to_string_as(&world, format).unwrap();
+    let loaded = scene_file::from_str_as(&text, format).unwrap();
+
+    assert_eq!(loaded.animation(), world.animation(), "{}", format.to_string());
+  }
+
+  let loaded = scene_file::from_str(r#"{ "animation": { "end": 50 } }"#).unwrap();
+  assert_eq!(loaded.animation().end, 50);
+  assert_eq!(loaded.animation().frame_rate, Animation::new().frame_rate);
+}
+
+#[test]
+fn numbers_frame_files() {
+  let path = |output: &str, frame| headless::frame_path(Path::new(output), frame);
+
+  assert_eq!(path("out/frame_###.png", 7), PathBuf::from("out/frame_007.png"));
+  assert_eq!(path("out/frame_#.png", 1234), PathBuf::from("out/frame_1234.png"));
+  assert_eq!(path("out/frame.png", 7), PathBuf::from("out/frame_0007.png"));
+  assert_eq!(path("frame", 12), PathBuf::from("frame_0012"));
+}
+
+#[test]
+fn renders_frame_ranges() {
+  let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("animation");
+  std::fs::create_dir_all(&dir).unwrap();
+
+  let mut world = animated_world();
+  let camera_info = world.camera_mut().camera_info_mut();
+  camera_info.viewport_width = 8;
+  camera_info.viewport_height = 6;
+
+  headless::render_frames(&mut world, &dir.join("frame_##.png"), 2..=4).unwrap();
+
+  for frame in 2..=4 {
+    assert!(dir.join(format!("frame_0{}.png", frame)).exists());
+  }
+
+  assert!(!dir.join("frame_05.png").exists());
+}
+
+#[test]
+fn removing_drops_and_reindexes_tracks() {
+  let mut world = animated_world();
+  world.objects_mut().push(Object::Sphere(Sphere::new()));
+  world.animation_mut().tracks.push(Track::ObjectPosition { object: 2, keyframes: Vec::new() });
+  world.select(Some(Selection::Object(2)));
+
+  assert!(world.remove_object(0).is_some());
+
+  let objects: Vec<Option<usize>> = world.animation().tracks.iter().map(Track::object).collect();
+  assert_eq!(objects, vec![None, None, None, Some(1)]);
+  assert_eq!(world.selection(), Some(Selection::Object(1)));
+
+  world.select(Some(Selection::Light(1)));
+  assert!(world.remove_light(1).is_some());
+
+  assert!(world.animation().tracks.iter().all(|track| track.light() != Some(1)));
+  assert_eq!(world.selection(), None);
+
+  // Out of range removals change nothing.
+  let animation = world.animation().clone();
+  assert!(world.remove_object(5).is_none());
+  assert_eq!(world.animation(), &animation);
+}
\ No newline at end of file
@@ -0,0 +1,81 @@
+use std::fs;
+
+use glium::glutin::event::{VirtualKeyCode, MouseButton};
+use ray_tracer::bindings::{Bindings, Binding, Controls, Action, Profile};
+
+#[test]
+fn parses_names_in_any_case() {
+  assert_eq!(Binding::parse("w"), Some(Binding::Key(VirtualKeyCode::W)));
+  assert_eq!(Binding::parse(" PageUp "), Some(Binding::Key(VirtualKeyCode::PageUp)));
+  assert_eq!(Binding::parse("middlemouse"), Some(Binding::Mouse(MouseButton::Middle)));
+  assert_eq!(Binding::parse("SHIFT"), Some(Binding::Shift));
+  assert_eq!(Binding::parse("Hyper"), None);
+
+  let list = Binding::parse_list("W, up,").unwrap();
+  assert_eq!(list, vec![Binding::Key(VirtualKeyCode::W), Binding::Key(VirtualKeyCode::Up)]);
+  assert_eq!(Binding::list_to_string(&list), "W, Up");
+
+  assert_eq!(Binding::parse_list("W, Hyper").unwrap_err().to_string(), "unknown key or button \"Hyper\"");
+}
+
+#[test]
+fn fly_defaults_match_the_old_controls() {
+  let bindings = Bindings::new();
+  let controls = bindings.controls();
+
+  assert_eq!(bindings.profile, Profile::Fly);
+
+  let keys = [
+    (Action::Forward, VirtualKeyCode::W), (Action::Back, VirtualKeyCode::S),
+    (Action::Left, VirtualKeyCode::A), (Action::Right, VirtualKeyCode::D),
+    (Action::Up, VirtualKeyCode::Q), (Action::Down, VirtualKeyCode::E),
+    (Action::FrameSelected, VirtualKeyCode::F), (Action::FrameAll, VirtualKeyCode::Home),
+  ];
+
+  for (action, key) in keys {
+    assert_eq!(controls.bindings(action), &vec![Binding::Key(key)]);
+  }
+
+  assert_eq!(controls.rotate, vec![Binding::Mouse(MouseButton::Left)]);
+}
+
+#[test]
+fn profiles_have_their_own_controls() {
+  let mut bindings = Bindings::new();
+  bindings.profile = Profile::Orbit;
+
+  assert_eq!(bindings.controls(), &Controls::orbit());
+
+  bindings.controls_mut().pan = vec![Binding::Mouse(MouseButton::Right)];
+  assert_eq!(bindings.orbit.pan, vec![Binding::Mouse(MouseButton::Right)]);
+  assert_eq!(bindings.fly, Controls::fly());
+}
+
+#[test]
+fn saves_and_loads_bindings_files() {
+  let dir = std::env::temp_dir().join(format!("ray_tracer_bindings_{}", std::process::id()));
+  fs::create_dir_all(&dir).unwrap();
+  let path = dir.join("bindings.toml");
+
+  let mut bindings = Bindings::new();
+  bindings.profile = Profile::Orbit;
+  bindings.fast_factor = 10.0;
+  bindings.fly.forward = vec![Binding::Key(VirtualKeyCode::I), Binding::Ctrl];
+
+  bindings.save(&path).unwrap();
+  assert_eq!(Bindings::load(&path).unwrap(), bindings);
+
+  // Anything left out keeps its default.
+  fs::write(&path, "profile = \"Orbit\"\n\n[fly]\nforward = [\"Up\"]\n").unwrap();
+  let loaded = Bindings::load(&path).unwrap();
+
+  assert_eq!(loaded.profile, Profile::Orbit);
+  assert_eq!(loaded.fly.forward, vec![Binding::Key(VirtualKeyCode::Up)]);
+  assert_eq!(loaded.fly.back, Controls::fly().back);
+  assert_eq!(loaded.wheel_speed, Bindings::new().wheel_speed);
+
+  fs::write(&path, "[fly]\nforward = [\"Hyper\"]\n").unwrap();
+  assert!(Bindings::load(&path).is_err());
+
+  fs::remove_dir_all(&dir).unwrap();
+}
\ No newline at end of file
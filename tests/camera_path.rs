@@ -0,0 +1,109 @@
+use std::{fs, path::PathBuf};
+
+use ray_tracer::{
+  camera_path::CameraPath,
+  animation::{self, Track},
+  headless,
+  vector::{Vector3, Float},
+  world::{World, CameraInfo},
+};
+
+fn camera_at(x: Float, fov: Float) -> CameraInfo {
+  CameraInfo {
+    position: Vector3::new(x, 0.0, 0.0),
+    vertical_fov: fov,
+    ..CameraInfo::default()
+  }
+}
+
+fn recorded_path() -> CameraPath {
+  let mut path = CameraPath::new();
+
+  // Moves at a steady speed for ten frames, then stops and widens the fov.
+  for frame in 0..=20 {
+    let time = frame as Float * 0.125;
+    let x = if frame <= 10 { frame as Float } else { 10.0 };
+    let fov = if frame <= 10 { 1.0 } else { 1.0 + (frame - 10) as Float * 0.05 };
+
+    path.record(time, camera_at(x, fov));
+  }
+
+  path
+}
+
+#[test]
+fn samples_between_recorded_frames() {
+  let path = recorded_path();
+
+  assert_eq!(path.duration(), 2.5);
+  assert_eq!(path.sample(0.3125).unwrap().position, Vector3::new(2.5, 0.0, 0.0));
+  assert_eq!(path.sample(-1.0).unwrap().position, Vector3::new(0.0, 0.0, 0.0));
+  assert_eq!(path.sample(5.0).unwrap().vertical_fov, 1.5);
+  assert_eq!(CameraPath::new().sample(0.0), None);
+}
+
+#[test]
+fn paths_survive_files() {
+  let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("camera_path");
+  fs::create_dir_all(&dir).unwrap();
+
+  let file = dir.join("path.json");
+  let path = recorded_path();
+  path.save(&file).unwrap();
+
+  assert_eq!(CameraPath::load(&file).unwrap(), path);
+}
+
+#[test]
+fn converts_to_keyframes() {
+  let path = recorded_path();
+  let tracks = path.to_tracks(8.0, 0.001);
+
+  match tracks.as_slice() {
+    [Track::CameraPosition(position), Track::CameraForward(forward), Track::CameraFov(fov)] => {
+      let frames: Vec<Float> = position.iter().map(|keyframe| keyframe.frame).collect();
+
+      assert_eq!(frames, vec![0.0, 10.0, 20.0]);
+      assert_eq!(forward.len(), 2);
+      assert_eq!(fov.len(), 3);
+
+      for sample in &path.samples {
+        let frame = sample.time * 8.0;
+        let position = animation::sample(position, frame).unwrap();
+
+        assert!((position - sample.camera_info.position).mag() < 1e-3, "{:?} at {}", position, frame);
+      }
+    },
+    tracks => panic!("unexpected tracks {:?}", tracks),
+  }
+
+  let mut world = World::new();
+  world.animation_mut().tracks = tracks;
+  world.set_frame(5.0);
+
+  assert_eq!(world.camera().camera_info().position, Vector3::new(5.0, 0.0, 0.0));
+}
+
+#[test]
+fn replays_every_sample() {
+  let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("camera_path");
+  fs::create_dir_all(&dir).unwrap();
+
+  let mut world = World::new();
+  let camera_info = world.camera_mut().camera_info_mut();
+  camera_info.viewport_width = 8;
+  camera_info.viewport_height = 6;
+
+  let mut path = CameraPath::new();
+
+  for frame in 0..3 {
+    let mut camera_info = *world.camera().camera_info();
+    camera_info.position = Vector3::new(frame as Float, 0.0, 0.0);
+    path.record(frame as Float / 30.0, camera_info);
+  }
+
+  let file = dir.join("replay.json");
+  path.save(&file).unwrap();
+
+  assert_eq!(headless::replay(&mut world, &file).unwrap().len(), 3);
+}
\ No newline at end of file
@@ -0,0 +1,75 @@
+use ray_tracer::{
+  comparison::{self, Comparison, CompareMode, Metrics},
+  image::Image,
+  renderer::Renderer,
+  world::Material,
+};
+
+fn filled(width: u32, height: u32, color: [u8; 3]) -> Image {
+  Image::new(width, height, vec![color; (width * height) as usize])
+}
+
+#[test]
+fn measures_error() {
+  let a = filled(4, 3, [100, 100, 100]);
+  let b = filled(4, 3, [110, 90, 100]);
+
+  let same = Metrics::between(&a, &a).unwrap();
+  assert_eq!(same.rmse, 0.0);
+  assert!(same.psnr.is_infinite());
+
+  // Two of the three channels are off by 10.
+  let metrics = Metrics::between(&a, &b).unwrap();
+  let rmse = (200.0_f64 / 3.0).sqrt();
+  assert!((metrics.rmse - rmse).abs() < 1e-9);
+  assert!((metrics.psnr - 20.0 * (255.0 / rmse).log10()).abs() < 1e-9);
+
+  assert_eq!(Metrics::between(&a, &filled(3, 4, [0, 0, 0])), None);
+}
+
+#[test]
+fn composes_split_and_difference() {
+  let a = filled(4, 1, [10, 20, 30]);
+  let b = filled(4, 1, [20, 20, 20]);
+
+  let split = comparison::split(&a, &b, 0.5).unwrap();
+  assert_eq!(split.pixels, vec![[10, 20, 30], [10, 20, 30], [255, 255, 255], [20, 20, 20]]);
+
+  let difference = comparison::difference(&a, &b, 4.0).unwrap();
+  assert_eq!(difference.pixels, vec![[40, 0, 40]; 4]);
+
+  assert!(comparison::split(&a, &filled(2, 2, [0, 0, 0]), 0.5).is_none());
+}
+
+#[test]
+fn stores_snapshots_of_the_live_render() {
+  let mut comparison = Comparison::new();
+  assert!(!comparison.take_snapshot("Before"));
+
+  let chrome = Renderer::material_preview(&Material::preset("chrome").unwrap(), 32);
+  let rubber = Renderer::material_preview(&Material::preset("rubber").unwrap(), 32);
+
+  comparison.set_live(chrome.clone());
+  assert!(comparison.take_snapshot("Before"));
+  comparison.add_snapshot("Other", filled(32, 32, [0, 0, 0]));
+
+  comparison.select(Some(0));
+  assert_eq!(comparison.metrics().map(|metrics| metrics.rmse), Some(0.0));
+
+  // Tuning the material shows up once the next render completes.
+  comparison.set_live(rubber.clone());
+  let metrics = comparison.metrics().unwrap();
+  assert!(metrics.rmse > 0.0 && metrics.psnr.is_finite());
+
+  comparison.settings_mut().mode = CompareMode::Difference;
+  assert_eq!(comparison.compose(&rubber), comparison::difference(&chrome, &rubber, comparison.settings().gain));
+
+  comparison.select(Some(1));
+  comparison.remove_snapshot(0);
+  assert_eq!(comparison.active(), Some(0));
+  assert_eq!(comparison.snapshots()[0].name, "Other");
+
+  comparison.remove_snapshot(0);
+  assert_eq!(comparison.active(), None);
+  assert_eq!(comparison.compose(&rubber), None);
+}
\ No newline at end of file
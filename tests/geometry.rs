@@ -0,0 +1,207 @@
+use proptest::prelude::*;
+use ray_tracer::{
+  quaternion::Quaternion,
+  renderer::Renderer,
+  vector::{Vector3, Float},
+  world::{World, Object, Sphere, Plane, Ray},
+};
+
+fn tolerance(scale: Float) -> Float {
+  Float::EPSILON.sqrt() * scale.abs().max(1.0)
+}
+
+fn assert_close(actual: Float, expected: Float, scale: Float) {
+  assert!(
+    (actual - expected).abs() <= tolerance(scale),
+    "{} is not within {} of {}", actual, tolerance(scale), expected
+  );
+}
+
+fn float(range: f64) -> impl Strategy<Value = Float> {
+  (-range..range).prop_map(|value| value as Float)
+}
+
+fn vector(range: f64) -> impl Strategy<Value = Vector3> {
+  (float(range), float(range), float(range)).prop_map(|(x, y, z)| Vector3::new(x, y, z))
+}
+
+fn unit_vector() -> impl Strategy<Value = Vector3> {
+  vector(1.0)
+    .prop_filter("vector must not be degenerate", |vector| vector.mag() > 0.1)
+    .prop_map(Vector3::normalise)
+}
+
+fn sphere() -> impl Strategy<Value = Sphere> {
+  (vector(50.0), 0.1..20.0).prop_map(|(position, radius)| {
+    let mut sphere = Sphere::new();
+    sphere.position = position;
+    sphere.radius = radius as Float;
+    sphere
+  })
+}
+
+fn world_with(object: Object) -> World {
+  let mut world = World::new();
+  world.objects_mut().clear();
+  world.objects_mut().push(object);
+  world
+}
+
+proptest! {
+  #[test]
+  fn dot_is_commutative(a in vector(100.0), b in vector(100.0)) {
+    assert_close(a.dot(&b), b.dot(&a), a.mag() * b.mag());
+  }
+
+  #[test]
+  fn cross_is_orthogonal(a in vector(100.0), b in vector(100.0)) {
+    let cross = a.cross(&b);
+    let scale = a.mag() * b.mag() * a.mag().max(b.mag());
+
+    assert_close(cross.dot(&a), 0.0, scale);
+    assert_close(cross.dot(&b), 0.0, scale);
+  }
+
+  #[test]
+  fn normalise_gives_unit_length(a in vector(100.0).prop_filter("non-zero", |a| a.mag() > 1e-3)) {
+    assert_close(a.normalise().mag(), 1.0, 1.0);
+  }
+
+  #[test]
+  fn add_and_sub_are_inverse(a in vector(100.0), b in vector(100.0)) {
+    let c = (a + b) - b;
+
+    assert_close(c.x, a.x, 100.0);
+    assert_close(c.y, a.y, 100.0);
+    assert_close(c.z, a.z, 100.0);
+  }
+
+  #[test]
+  fn scaling_scales_magnitude(a in vector(100.0), k in float(10.0)) {
+    assert_close((a * k).mag(), a.mag() * k.abs(), a.mag() * k.abs());
+  }
+
+  #[test]
+  fn rotation_preserves_length(v in vector(100.0), axis in vector(1.0), angle in float(100.0)) {
+    let rotated = Quaternion::from_angle_axis(angle, axis).rotate(v);
+
+    assert_close(rotated.mag(), v.mag(), v.mag());
+  }
+
+  #[test]
+  fn rotation_leaves_axis_unchanged(axis in unit_vector(), angle in float(10.0)) {
+    let rotated = Quaternion::from_angle_axis(angle, axis * 5.0).rotate(axis);
+
+    assert_close(rotated.x, axis.x, 1.0);
+    assert_close(rotated.y, axis.y, 1.0);
+    assert_close(rotated.z, axis.z, 1.0);
+  }
+
+  #[test]
+  fn rotation_is_undone_by_opposite_angle(v in vector(100.0), axis in unit_vector(), angle in float(10.0)) {
+    let there = Quaternion::from_angle_axis(angle, axis).rotate(v);
+    let back = Quaternion::from_angle_axis(-angle, axis).rotate(there);
+
+    assert_close(back.x, v.x, v.mag());
+    assert_close(back.y, v.y, v.mag());
+    assert_close(back.z, v.z, v.mag());
+  }
+
+  #[test]
+  fn sphere_hits_lie_on_surface(sphere in sphere(), direction in unit_vector(), target in vector(1.0)) {
+    let target = sphere.position + target * (sphere.radius * 0.5);
+    let ray = Ray {
+      position: target - direction * (sphere.radius * 3.0),
+      direction,
+    };
+
+    let [t1, t2] = sphere.trace_ray(&ray);
+
+    prop_assert!(t1.is_some() && t2.is_some());
+
+    for t in [t1.unwrap(), t2.unwrap()] {
+      let distance = (ray.position_from_distance(t) - sphere.position).mag();
+      assert_close(distance, sphere.radius, sphere.radius * 10.0);
+    }
+
+    prop_assert!(t2.unwrap() <= t1.unwrap());
+  }
+
+  #[test]
+  fn sphere_misses_behind_ray(sphere in sphere(), direction in unit_vector()) {
+    let ray = Ray {
+      position: sphere.position + direction * (sphere.radius * 2.0),
+      direction,
+    };
+
+    prop_assert_eq!(sphere.trace_ray(&ray), [None, None]);
+  }
+
+  #[test]
+  fn sphere_normals_are_unit_length(sphere in sphere(), direction in unit_vector(), target in vector(1.0)) {
+    let ray = Ray {
+      position: sphere.position + target * (sphere.radius * 0.5) - direction * (sphere.radius * 3.0),
+      direction,
+    };
+
+    let world = world_with(Object::Sphere(sphere));
+    let intersection = Renderer::trace_ray(&ray, &world);
+
+    prop_assert!(intersection.is_some());
+    assert_close(intersection.unwrap().normal().mag(), 1.0, 1.0);
+  }
+
+  #[test]
+  fn plane_hits_lie_on_plane(position in vector(10.0), origin in vector(50.0), direction in unit_vector()) {
+    let mut plane = Plane::new();
+    plane.position = position;
+
+    let ray = Ray {
+      position: origin,
+      direction,
+    };
+
+    if let Some(t) = plane.trace_ray(&ray) {
+      let hit = ray.position_from_distance(t);
+
+      prop_assert!(t >= 0.01);
+      assert_close(hit.y, plane.position.y, t);
+      prop_assert!((hit.x - plane.position.x).abs() <= plane.width / 2.0);
+      prop_assert!((hit.z - plane.position.z).abs() <= plane.height / 2.0);
+    }
+  }
+
+  #[test]
+  fn plane_ignores_parallel_rays(origin in vector(50.0), x in float(1.0), z in float(1.0)) {
+    let ray = Ray {
+      position: origin,
+      direction: Vector3::new(x, 0.0, z),
+    };
+
+    prop_assert_eq!(Plane::new().trace_ray(&ray), None);
+  }
+
+  #[test]
+  fn refraction_obeys_snells_law(
+    incident in unit_vector(),
+    normal in unit_vector(),
+    n1 in 1.0..2.5,
+    n2 in 1.0..2.5,
+  ) {
+    let (n1, n2) = (n1 as Float, n2 as Float);
+
+    let cos_incident = -normal.dot(&incident);
+    prop_assume!(cos_incident > 0.05);
+
+    let sin_incident = normal.cross(&incident).mag();
+    prop_assume!(n1 / n2 * sin_incident < 0.95);
+
+    let refracted = Renderer::calc_snells_law(incident, normal, n1, n2);
+    let sin_refracted = normal.cross(&refracted).mag();
+
+    assert_close(refracted.mag(), 1.0, 1.0);
+    assert_close(n1 * sin_incident, n2 * sin_refracted, 10.0);
+    assert_close(normal.cross(&incident).dot(&refracted), 0.0, 10.0);
+    prop_assert!(refracted.dot(&normal) < 0.0);
+  }
+}
\ No newline at end of file
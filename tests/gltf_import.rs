@@ -0,0 +1,164 @@
+use std::{fs, path::PathBuf};
+
+use ray_tracer::{
+  gltf_import,
+  scene_file::{self, SceneFormat},
+  renderer::Renderer,
+  vector::{Vector3, Float},
+  world::{World, Object, Light, Ray, CameraType},
+};
+
+fn write_quad_scene(name: &str, nodes: &str) -> PathBuf {
+  let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("gltf");
+  fs::create_dir_all(&dir).unwrap();
+
+  let mut buffer = Vec::new();
+
+  for value in [-1.0f32, -1.0, 0.0, 1.0, -1.0, 0.0, 1.0, 1.0, 0.0, -1.0, 1.0, 0.0] {
+    buffer.extend_from_slice(&value.to_le_bytes());
+  }
+
+  for index in [0u16, 1, 2, 0, 2, 3] {
+    buffer.extend_from_slice(&index.to_le_bytes());
+  }
+
+  let bin = format!("{}.bin", name);
+  fs::write(dir.join(&bin), &buffer).unwrap();
+
+  let json = format!(r#"{{
+    "asset": {{ "version": "2.0" }},
+    "extensionsUsed": ["KHR_lights_punctual"],
+    "extensions": {{
+      "KHR_lights_punctual": {{
+        "lights": [{{ "type": "directional", "color": [1.0, 0.5, 0.5], "intensity": 0.5 }}]
+      }}
+    }},
+    "scene": 0,
+    "scenes": [{{ "nodes": [0, 1, 2] }}],
+    "nodes": {nodes},
+    "cameras": [{{ "type": "perspective", "perspective": {{ "yfov": 1.0, "aspectRatio": 1.5, "znear": 0.1 }} }}],
+    "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1, "material": 0 }}] }}],
+    "materials": [{{
+      "name": "red",
+      "pbrMetallicRoughness": {{ "baseColorFactor": [1.0, 0.0, 0.0, 1.0], "metallicFactor": 0.0, "roughnessFactor": 0.5 }}
+    }}],
+    "buffers": [{{ "uri": "{bin}", "byteLength": 60 }}],
+    "bufferViews": [
+      {{ "buffer": 0, "byteOffset": 0, "byteLength": 48 }},
+      {{ "buffer": 0, "byteOffset": 48, "byteLength": 12 }}
+    ],
+    "accessors": [
+      {{ "bufferView": 0, "componentType": 5126, "count": 4, "type": "VEC3", "min": [-1.0, -1.0, 0.0], "max": [1.0, 1.0, 0.0] }},
+      {{ "bufferView": 1, "componentType": 5123, "count": 6, "type": "SCALAR" }}
+    ]
+  }}"#);
+
+  let path = dir.join(format!("{}.gltf", name));
+  fs::write(&path, json).unwrap();
+
+  path
+}
+
+fn import_quad(name: &str, mesh_node: &str) -> World {
+  let nodes = format!(r#"[
+    {mesh_node},
+    {{ "camera": 0, "translation": [0.0, 0.0, 5.0] }},
+    {{ "extensions": {{ "KHR_lights_punctual": {{ "light": 0 }} }} }}
+  ]"#);
+
+  gltf_import::import(&write_quad_scene(name, &nodes)).unwrap()
+}
+
+fn assert_close(actual: Vector3, expected: Vector3) {
+  assert!((actual - expected).mag() < 1e-5, "{:?} != {:?}", actual, expected);
+}
+
+#[test]
+fn imports_meshes_materials_cameras_and_lights() {
+  let world = import_quad("quad", r#"{ "mesh": 0, "name": "Floor" }"#);
+
+  match world.objects().as_slice() {
+    [Object::Mesh(mesh)] => {
+      assert_eq!(mesh.triangles.len(), 2);
+      assert_eq!(mesh.material_name.as_deref(), Some("red"));
+      assert_eq!(mesh.material.color, [1.0, 0.0, 0.0]);
+      assert_close(mesh.position, Vector3::new(0.0, 0.0, 0.0));
+      assert_eq!(mesh.node.name.as_deref(), Some("Floor"));
+    },
+    objects => panic!("unexpected objects {:?}", objects),
+  }
+
+  assert!(world.materials().contains_key("red"));
+
+  let camera_info = world.camera().camera_info();
+  assert_eq!(camera_info.camera_type, CameraType::Perspective);
+  assert_close(camera_info.position, Vector3::new(0.0, 0.0, -5.0));
+  assert_close(camera_info.forward, Vector3::new(0.0, 0.0, 1.0));
+  assert!((camera_info.vertical_fov - (0.5 as Float).tan()).abs() < 1e-6);
+  assert_eq!(camera_info.viewport_width, 720);
+
+  match world.lights().as_slice() {
+    [Light::Ambient(_), Light::Directional(light)] => {
+      assert_eq!(light.color, [0.5, 0.25, 0.25]);
+      assert_close(light.direction, Vector3::new(0.0, 0.0, 1.0));
+    },
+    lights => panic!("unexpected lights {:?}", lights),
+  }
+}
+
+#[test]
+fn applies_node_transforms() {
+  let nodes = r#"[
+    { "translation": [2.0, 0.0, -3.0], "children": [3] },
+    { "camera": 0, "translation": [0.0, 0.0, 5.0] },
+    { "extensions": { "KHR_lights_punctual": { "light": 0 } } },
+    { "mesh": 0, "scale": [2.0, 1.0, 1.0] }
+  ]"#;
+
+  let world = gltf_import::import(&write_quad_scene("hierarchy", nodes)).unwrap();
+
+  match world.objects().as_slice() {
+    [Object::Mesh(mesh)] => {
+      assert_close(mesh.position, Vector3::new(2.0, 0.0, 3.0));
+
+      let [min, max] = mesh.bounds().unwrap();
+      assert_close(min, Vector3::new(-2.0, -1.0, 0.0));
+      assert_close(max, Vector3::new(2.0, 1.0, 0.0));
+    },
+    objects => panic!("unexpected objects {:?}", objects),
+  }
+}
+
+#[test]
+fn renders_imported_meshes() {
+  let mut world = import_quad("rendered", r#"{ "mesh": 0 }"#);
+
+  let camera_info = world.camera_mut().camera_info_mut();
+  camera_info.viewport_width = 30;
+  camera_info.viewport_height = 20;
+
+  let image = Renderer::new().render(&mut world).to_image();
+  let center = image.pixels[(10 * 30 + 15) as usize];
+
+  assert!(center[0] > 2 * center[1] && center[0] > 2 * center[2], "{:?}", center);
+}
+
+#[test]
+fn meshes_survive_scene_files() {
+  let world = import_quad("saved", r#"{ "mesh": 0 }"#);
+
+  let text = scene_file::to_string_as(&world, SceneFormat::Toml).unwrap();
+  let loaded = scene_file::from_str_as(&text, SceneFormat::Toml).unwrap();
+
+  assert!(loaded.same_scene(&world));
+
+  let ray = Ray {
+    position: Vector3::new(0.5, 0.5, -5.0),
+    direction: Vector3::new(0.0, 0.0, 1.0),
+  };
+
+  match &loaded.objects()[0] {
+    Object::Mesh(mesh) => assert!(mesh.trace_ray(&ray).is_some()),
+    object => panic!("unexpected object {:?}", object),
+  }
+}
\ No newline at end of file
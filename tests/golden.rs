@@ -0,0 +1,177 @@
+use std::{env, fs, path::PathBuf};
+
+use ray_tracer::{
+  image::Image,
+  renderer::Renderer,
+  vector::Vector3,
+  world::{World, Object, Sphere, Light, DirectionalLight, AmbientLight, PointLight, CameraType},
+};
+
+const WIDTH: u32 = 96;
+const HEIGHT: u32 = 64;
+
+// A pixel counts as different once its luminance-weighted channel difference exceeds this.
+const PIXEL_THRESHOLD: f64 = 6.0;
+const MAX_DIFFERING_PIXELS: f64 = 0.002;
+
+fn golden_dir() -> PathBuf {
+  PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden")
+}
+
+fn output_dir() -> PathBuf {
+  PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("golden")
+}
+
+fn render(mut world: World) -> Image {
+  let camera_info = world.camera_mut().camera_info_mut();
+  camera_info.viewport_width = WIDTH;
+  camera_info.viewport_height = HEIGHT;
+
+  Renderer::new().render(&mut world).to_image()
+}
+
+fn pixel_difference(a: [u8; 3], b: [u8; 3]) -> f64 {
+  let channel = |i: usize| (a[i] as f64 - b[i] as f64).abs();
+
+  0.299 * channel(0) + 0.587 * channel(1) + 0.114 * channel(2)
+}
+
+fn diff_image(actual: &Image, reference: &Image) -> (Image, usize) {
+  let mut differing = 0;
+
+  let pixels = actual.pixels.iter().zip(&reference.pixels).map(|(a, r)| {
+    let difference = pixel_difference(*a, *r);
+
+    if difference > PIXEL_THRESHOLD {
+      differing += 1;
+      [(64.0 + difference * 8.0).min(255.0) as u8, 0, 0]
+    } else {
+      let gray = (r[0] as u16 + r[1] as u16 + r[2] as u16) / 12;
+      [gray as u8, gray as u8, gray as u8]
+    }
+  }).collect();
+
+  (Image::new(actual.width, actual.height, pixels), differing)
+}
+
+fn check(name: &str, world: World) {
+  let actual = render(world);
+  let reference_path = golden_dir().join(format!("{}.png", name));
+
+  if env::var_os("BLESS").is_some() {
+    fs::create_dir_all(golden_dir()).unwrap();
+    actual.save_png(&reference_path).unwrap();
+    return;
+  }
+
+  let reference = Image::load_png(&reference_path).unwrap_or_else(|err| {
+    panic!("could not load {}: {}, run `BLESS=1 cargo test --test golden` to create it", reference_path.display(), err)
+  });
+
+  assert_eq!((actual.width, actual.height), (reference.width, reference.height), "{} changed size", name);
+
+  let (diff, differing) = diff_image(&actual, &reference);
+  let allowed = (actual.pixels.len() as f64 * MAX_DIFFERING_PIXELS) as usize;
+
+  if differing > allowed {
+    fs::create_dir_all(output_dir()).unwrap();
+
+    let actual_path = output_dir().join(format!("{}.actual.png", name));
+    let diff_path = output_dir().join(format!("{}.diff.png", name));
+
+    actual.save_png(&actual_path).unwrap();
+    diff.save_png(&diff_path).unwrap();
+
+    panic!(
+      "{} differs from its reference in {} pixels (allowed {}), see {} and {}",
+      name, differing, allowed, actual_path.display(), diff_path.display()
+    );
+  }
+}
+
+fn sphere(position: Vector3, radius: f64) -> Sphere {
+  let mut sphere = Sphere::new();
+  sphere.position = position;
+  sphere.radius = radius as _;
+  sphere
+}
+
+#[test]
+fn default_scene() {
+  check("default_scene", World::new());
+}
+
+#[test]
+fn reflection() {
+  let mut world = World::new();
+
+  let mut mirror = sphere(Vector3::new(1.5, 0.0, 9.0), 1.5);
+  mirror.material.reflectivity = 0.7;
+  mirror.material.color = [0.9, 0.9, 0.9];
+
+  let mut red = sphere(Vector3::new(-1.5, 0.0, 8.0), 1.0);
+  red.material.color = [1.0, 0.2, 0.2];
+
+  world.objects_mut().clear();
+  world.objects_mut().push(Object::Sphere(mirror));
+  world.objects_mut().push(Object::Sphere(red));
+
+  check("reflection", world);
+}
+
+#[test]
+fn refraction() {
+  let mut world = World::new();
+
+  let mut glass = sphere(Vector3::new(0.0, 0.0, 6.0), 1.2);
+  glass.material.transparency = 0.9;
+  glass.material.refractive_index = 1.5;
+  glass.material.color = [1.0, 1.0, 1.0];
+
+  let mut behind = sphere(Vector3::new(0.5, 0.0, 12.0), 2.0);
+  behind.material.color = [0.2, 0.4, 1.0];
+
+  world.objects_mut().insert(0, Object::Sphere(glass));
+  world.objects_mut().push(Object::Sphere(behind));
+
+  check("refraction", world);
+}
+
+#[test]
+fn orthographic() {
+  let mut world = World::new();
+
+  let camera_info = world.camera_mut().camera_info_mut();
+  camera_info.camera_type = CameraType::Orthographic;
+  camera_info.camera_height = 3.0;
+
+  check("orthographic", world);
+}
+
+#[test]
+fn directional_light() {
+  let mut world = World::new();
+
+  let lights = world.lights_mut();
+  lights.clear();
+  lights.push(Light::Directional(DirectionalLight::new()));
+  lights.push(Light::Ambient(AmbientLight::new()));
+
+  check("directional_light", world);
+}
+
+#[test]
+fn shadows() {
+  let mut world = World::new();
+
+  let mut light = PointLight::new();
+  light.position = Vector3::new(-10.0, 20.0, 5.0);
+
+  world.lights_mut().clear();
+  world.lights_mut().push(Light::Point(light));
+  world.lights_mut().push(Light::Ambient(AmbientLight::new()));
+
+  world.objects_mut().push(Object::Sphere(sphere(Vector3::new(3.0, -1.0, 12.0), 1.0)));
+
+  check("shadows", world);
+}
\ No newline at end of file
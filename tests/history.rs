@@ -0,0 +1,216 @@
+use ray_tracer::{
+  animation::{Track, Keyframe, Interpolation},
+  history::{History, Target, Command},
+  vector::Vector3,
+  world::{World, Object, Sphere, Light, PointLight, Selection},
+};
+
+fn labels(history: &History) -> Vec<&str> {
+  history.undo_steps().iter().map(|step| step.label.as_str()).collect()
+}
+
+fn move_object(world: &mut World, history: &mut History, index: usize, position: Vector3) {
+  history.watch(world, Target::Object(index));
+  *world.objects_mut()[index].position_mut() = position;
+  history.edited(Target::Object(index));
+}
+
+#[test]
+fn undoes_and_redoes_edits() {
+  let mut world = World::new();
+  let mut history = History::new(&world);
+
+  move_object(&mut world, &mut history, 0, Vector3::new(1.0, 0.0, 10.0));
+  history.update(&world, false);
+
+  history.execute(&mut world, Command::AddObject(Object::Sphere(Sphere::new())));
+  history.update(&world, false);
+
+  history.execute(&mut world, Command::RemoveLight(0));
+  history.update(&world, false);
+
+  assert_eq!(labels(&history), vec!["Edit Object 0", "Add Object", "Remove Light"]);
+
+  assert!(history.undo(&mut world));
+  assert!(history.undo(&mut world));
+  assert_eq!(world.objects().len(), 2);
+  assert_eq!(world.lights(), World::new().lights());
+  assert_eq!(*world.objects()[0].position(), Vector3::new(1.0, 0.0, 10.0));
+
+  assert!(history.redo(&mut world));
+  assert_eq!(world.objects().len(), 3);
+  assert_eq!(history.redo_steps().len(), 1);
+
+  assert!(history.undo(&mut world));
+  assert!(history.undo(&mut world));
+  assert!(!history.undo(&mut world));
+  assert_eq!(world.objects(), World::new().objects());
+}
+
+#[test]
+fn records_nothing_that_was_not_edited() {
+  let mut world = World::new();
+  let mut history = History::new(&world);
+
+  // Watching alone, or an edit that ends where it started, is no step.
+  history.watch(&world, Target::Object(0));
+  history.update(&world, false);
+
+  move_object(&mut world, &mut history, 1, Vector3::new(0.0, 0.0, 0.0));
+  move_object(&mut world, &mut history, 1, *World::new().objects()[1].position());
+  history.update(&world, false);
+
+  assert!(!history.can_undo());
+}
+
+#[test]
+fn coalesces_continuous_drags() {
+  let mut world = World::new();
+  let mut history = History::new(&world);
+
+  for x in 1..=10 {
+    move_object(&mut world, &mut history, 0, Vector3::new(x as _, 0.0, 10.0));
+    history.update(&world, true);
+  }
+
+  // The last change can arrive on the frame the drag ends.
+  move_object(&mut world, &mut history, 0, Vector3::new(11.0, 0.0, 10.0));
+  history.update(&world, false);
+
+  move_object(&mut world, &mut history, 1, Vector3::new(0.0, -3.0, 10.0));
+  history.update(&world, false);
+
+  assert_eq!(labels(&history), vec!["Edit Object 0", "Edit Object 1"]);
+
+  history.undo(&mut world);
+  history.undo(&mut world);
+  assert_eq!(*world.objects()[0].position(), Vector3::new(0.0, 0.0, 10.0));
+
+  history.redo(&mut world);
+  assert_eq!(*world.objects()[0].position(), Vector3::new(11.0, 0.0, 10.0));
+}
+
+#[test]
+fn new_edits_clear_redo() {
+  let mut world = World::new();
+  let mut history = History::new(&world);
+
+  history.execute(&mut world, Command::AddLight(Light::Point(PointLight::new())));
+  history.update(&world, false);
+  history.undo(&mut world);
+
+  history.execute(&mut world, Command::RemoveObject(0));
+  history.update(&world, false);
+
+  assert!(!history.can_redo());
+  assert_eq!(labels(&history), vec!["Remove Object"]);
+}
+
+#[test]
+fn flying_is_not_recorded() {
+  let mut world = World::new();
+  let mut history = History::new(&world);
+
+  history.change(&mut world, &[Target::Camera], |world| world.camera_mut().camera_info_mut().vertical_fov = 0.5);
+  history.update(&world, false);
+
+  history.watch(&world, Target::Camera);
+  world.camera_mut().camera_info_mut().position = Vector3::new(5.0, 0.0, 0.0);
+  history.edited(Target::Camera);
+  history.update(&world, false);
+
+  assert_eq!(labels(&history), vec!["Edit Camera"]);
+
+  // Undoing the camera edit leaves the camera where it was flown to.
+  history.undo(&mut world);
+  let camera_info = world.camera().camera_info();
+  assert_eq!(camera_info.vertical_fov, 1.0);
+  assert_eq!(camera_info.position, Vector3::new(5.0, 0.0, 0.0));
+}
+
+#[test]
+fn edits_during_playback_are_kept() {
+  let mut world = World::new();
+  world.animation_mut().tracks.push(Track::ObjectPosition {
+    object: 0,
+    keyframes: vec![Keyframe::new(0.0, Vector3::new(0.0, 5.0, 10.0), Interpolation::Linear)],
+  });
+
+  let mut history = History::new(&world);
+
+  history.watch(&world, Target::Light(0));
+  world.set_frame(3.0);
+  history.update(&world, false);
+  assert!(!history.can_undo());
+
+  history.change(&mut world, &[Target::Light(0)], |world| world.lights_mut()[0].node_mut().hidden = true);
+  world.set_frame(4.0);
+  history.update(&world, false);
+
+  assert_eq!(labels(&history), vec!["Edit Light 0"]);
+
+  history.undo(&mut world);
+  assert!(!world.lights()[0].node().hidden);
+}
+
+#[test]
+fn removing_restores_tracks_and_position() {
+  let mut world = World::new();
+  world.animation_mut().tracks.push(Track::ObjectColor {
+    object: 0,
+    keyframes: vec![Keyframe::new(0.0, [1.0, 0.0, 0.0], Interpolation::Linear)],
+  });
+  world.select(Some(Selection::Object(1)));
+
+  let animation = world.animation().clone();
+  let mut history = History::new(&world);
+
+  history.execute(&mut world, Command::RemoveObject(0));
+  history.execute(&mut world, Command::MoveLight(0, 1));
+  history.update(&world, false);
+
+  assert_eq!(labels(&history), vec!["Edit Scene"]);
+  assert!(world.animation().tracks.is_empty());
+  assert_eq!(world.selection(), Some(Selection::Object(0)));
+
+  history.undo(&mut world);
+  assert_eq!(world.objects(), World::new().objects());
+  assert_eq!(world.lights(), World::new().lights());
+  assert_eq!(world.animation(), &animation);
+  assert_eq!(world.selection(), Some(Selection::Object(1)));
+
+  history.redo(&mut world);
+  assert_eq!(world.objects().len(), 1);
+  assert!(matches!(world.lights()[0], Light::Ambient(_)));
+}
+
+#[test]
+fn records_replaced_scenes_and_fixes_the_selection() {
+  let mut world = World::new();
+  let mut history = History::new(&world);
+
+  history.execute(&mut world, Command::AddObject(Object::Sphere(Sphere::new())));
+  history.update(&world, true);
+
+  let before = world.clone();
+  world.objects_mut().clear();
+  world.lights_mut().clear();
+  history.replaced(&world, before, "Open Scene");
+
+  assert_eq!(labels(&history), vec!["Add Object", "Open Scene"]);
+
+  history.undo(&mut world);
+  world.select(Some(Selection::Object(2)));
+  assert_eq!(world.objects().len(), 3);
+
+  history.undo(&mut world);
+  assert_eq!(world.objects().len(), 2);
+  assert_eq!(world.selection(), None);
+
+  history.jump(&mut world, 2);
+  assert!(world.objects().is_empty() && world.lights().is_empty());
+
+  // Opening nothing records nothing.
+  history.replaced(&world, world.clone(), "Open Scene");
+  assert_eq!(history.undo_steps().len(), 2);
+}
\ No newline at end of file
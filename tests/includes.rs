@@ -0,0 +1,134 @@
+use std::{fs, path::PathBuf};
+
+use ray_tracer::{scene_file::{self, SceneError}, world::{Object, Light}};
+
+fn scene_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+  let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("includes").join(name);
+
+  for (file, contents) in files {
+    let path = dir.join(file);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+  }
+
+  dir
+}
+
+const RIG: &str = r#"
+[[lights]]
+[lights.Point]
+color = [1.0, 0.9, 0.8]
+position = { x = 0.0, y = 10.0, z = 0.0 }
+
+[[lights]]
+[lights.Ambient]
+color = [0.1, 0.1, 0.1]
+
+[camera]
+depth = 3
+"#;
+
+const MATERIALS: &str = r#"
+materials:
+  glass:
+    transparency: 0.9
+    refractive_index: 1.5
+  chrome:
+    reflectivity: 0.9
+"#;
+
+#[test]
+fn merges_included_files() {
+  let dir = scene_dir("merge", &[
+    ("library/rig.toml", RIG),
+    ("library/materials.yaml", MATERIALS),
+    ("scene.json", r#"{
+      "include": ["library/rig.toml", "library/materials.yaml"],
+      "objects": [{ "Sphere": { "radius": 1.0, "material_name": "glass" } }],
+      "lights": [{ "Ambient": { "color": [0.2, 0.2, 0.2] } }]
+    }"#),
+  ]);
+
+  let world = scene_file::load(&dir.join("scene.json")).unwrap();
+
+  assert_eq!(world.lights().len(), 3);
+  assert!(matches!(world.lights()[0], Light::Point(_)));
+  assert_eq!(*world.camera().depth(), 3);
+  assert_eq!(world.materials().len(), 2);
+  assert_eq!(world.objects()[0].material().refractive_index, 1.5);
+}
+
+#[test]
+fn including_file_overrides_fields() {
+  let dir = scene_dir("override", &[
+    ("rig.toml", RIG),
+    ("materials.yaml", MATERIALS),
+    ("scene.toml", r#"
+include = ["rig.toml", "materials.yaml"]
+
+[camera]
+depth = 7
+
+[materials.glass]
+refractive_index = 1.33
+
+[[objects]]
+[objects.Sphere]
+material_name = "glass"
+"#),
+  ]);
+
+  let world = scene_file::load(&dir.join("scene.toml")).unwrap();
+  let material = world.objects()[0].material();
+
+  assert_eq!(*world.camera().depth(), 7);
+  assert_eq!(material.refractive_index, 1.33);
+  assert_eq!(material.transparency, 0.9);
+}
+
+#[test]
+fn resolves_nested_includes_relative_to_each_file() {
+  let dir = scene_dir("nested", &[
+    ("assets/set.yaml", "include: materials/library.yaml\nobjects:\n  - Plane:\n      material_name: chrome\n"),
+    ("assets/materials/library.yaml", MATERIALS),
+    ("scenes/scene.yaml", "include: [../assets/set.yaml]\nobjects:\n  - Sphere:\n      material_name: glass\n"),
+  ]);
+
+  let world = scene_file::load(&dir.join("scenes").join("scene.yaml")).unwrap();
+
+  match world.objects().as_slice() {
+    [Object::Plane(plane), Object::Sphere(sphere)] => {
+      assert_eq!(plane.material.reflectivity, 0.9);
+      assert_eq!(sphere.material.transparency, 0.9);
+    },
+    objects => panic!("unexpected objects {:?}", objects),
+  }
+}
+
+#[test]
+fn rejects_include_cycles() {
+  let dir = scene_dir("cycle", &[
+    ("a.yaml", "include: b.yaml\n"),
+    ("b.yaml", "include: a.yaml\n"),
+  ]);
+
+  let err = scene_file::load(&dir.join("a.yaml")).unwrap_err();
+
+  assert!(err.to_string().contains("includes itself"), "{}", err);
+}
+
+#[test]
+fn reports_which_include_failed() {
+  let dir = scene_dir("broken", &[
+    ("broken.toml", "[camera\n"),
+    ("scene.toml", "include = \"broken.toml\"\n"),
+  ]);
+
+  match scene_file::load(&dir.join("scene.toml")).unwrap_err() {
+    SceneError::Include { path, source } => {
+      assert!(path.ends_with("broken.toml"));
+      assert!(matches!(*source, SceneError::Parse { line: 1, .. }));
+    },
+    err => panic!("unexpected error {:?}", err),
+  }
+}
\ No newline at end of file
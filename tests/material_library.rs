@@ -0,0 +1,88 @@
+use ray_tracer::{
+  renderer::Renderer,
+  scene_file,
+  world::{World, Object, Sphere, Material},
+};
+
+fn library_world() -> World {
+  let mut world = World::new();
+  world.materials_mut().insert(String::from("gold"), Material::preset("chrome").unwrap());
+
+  for object in world.objects_mut() {
+    object.set_material_name(Some(String::from("gold")));
+  }
+
+  world.objects_mut().push(Object::Sphere(Sphere::new()));
+  world.apply_materials();
+
+  world
+}
+
+#[test]
+fn presets_are_distinct() {
+  let presets: Vec<Material> = Material::PRESETS.iter().map(|name| Material::preset(name).unwrap()).collect();
+
+  for (i, a) in presets.iter().enumerate() {
+    for b in &presets[i + 1..] {
+      assert_ne!(a, b);
+    }
+  }
+
+  assert_eq!(Material::preset("cheese"), None);
+}
+
+#[test]
+fn editing_a_named_material_updates_its_objects() {
+  let mut world = library_world();
+
+  world.materials_mut().get_mut("gold").unwrap().color = [1.0, 0.8, 0.1];
+  world.apply_materials();
+
+  assert_eq!(world.objects()[0].material().color, [1.0, 0.8, 0.1]);
+  assert_eq!(world.objects()[1].material().color, [1.0, 0.8, 0.1]);
+  assert_eq!(world.objects()[2].material(), &Material::new());
+}
+
+#[test]
+fn renaming_and_removing_follow_references() {
+  let mut world = library_world();
+
+  world.rename_material("gold", "brass");
+
+  assert!(world.materials().contains_key("brass") && !world.materials().contains_key("gold"));
+  assert_eq!(world.objects()[0].material_name(), Some("brass"));
+
+  world.remove_material("brass");
+
+  assert!(world.materials().is_empty());
+  assert_eq!(world.objects()[0].material_name(), None);
+  assert_eq!(world.objects()[0].material(), &Material::preset("chrome").unwrap());
+}
+
+#[test]
+fn scenes_can_use_presets_by_name() {
+  let world = scene_file::from_str(r#"{ "objects": [{ "Sphere": { "material_name": "water" } }] }"#).unwrap();
+
+  assert_eq!(world.objects()[0].material(), &Material::preset("water").unwrap());
+  assert_eq!(world.materials().get("water"), Material::preset("water").as_ref());
+
+  // A definition in the scene wins over the preset.
+  let world = scene_file::from_str(r#"{
+    "materials": { "water": { "transparency": 0.5 } },
+    "objects": [{ "Sphere": { "material_name": "water" } }]
+  }"#).unwrap();
+
+  assert_eq!(world.objects()[0].material().transparency, 0.5);
+}
+
+#[test]
+fn previews_materials() {
+  let chrome = Renderer::material_preview(&Material::preset("chrome").unwrap(), 24);
+  let rubber = Renderer::material_preview(&Material::preset("rubber").unwrap(), 24);
+
+  assert_eq!((chrome.width, chrome.height, chrome.pixels.len()), (24, 24, 576));
+
+  // The sphere fills the middle of the thumbnail.
+  let middle = 12 * 24 + 12;
+  assert_ne!(chrome.pixels[middle], rubber.pixels[middle]);
+}
\ No newline at end of file
@@ -0,0 +1,104 @@
+use ray_tracer::{vector::{Vector3, Float}, world::{World, Object, Sphere, Plane, Mesh, Selection, CameraType}};
+
+fn sphere_at(position: Vector3, radius: Float) -> Object {
+  let mut sphere = Sphere::new();
+  sphere.position = position;
+  sphere.radius = radius;
+
+  Object::Sphere(sphere)
+}
+
+fn scene(camera_type: CameraType) -> World {
+  let mut world = World::new();
+
+  world.objects_mut().push(sphere_at(Vector3::new(8.0, 1.0, 30.0), 2.0));
+  world.objects_mut().push(sphere_at(Vector3::new(-6.0, 3.0, 25.0), 1.0));
+
+  let camera_info = world.camera_mut().camera_info_mut();
+  camera_info.camera_type = camera_type;
+  camera_info.viewport_width = 40;
+  camera_info.viewport_height = 20;
+
+  world
+}
+
+fn assert_close(a: Vector3, b: Vector3) {
+  assert!((a - b).mag() < 1e-4, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn objects_have_world_space_bounds() {
+  let sphere = sphere_at(Vector3::new(1.0, 2.0, 3.0), 0.5);
+  let [min, max] = sphere.bounds().unwrap();
+  assert_close(min, Vector3::new(0.5, 1.5, 2.5));
+  assert_close(max, Vector3::new(1.5, 2.5, 3.5));
+
+  let mut plane = Plane::new();
+  plane.position = Vector3::new(0.0, -1.0, 0.0);
+  plane.width = 4.0;
+  plane.height = 2.0;
+  let [min, max] = Object::Plane(plane).bounds().unwrap();
+  assert_close(min, Vector3::new(-2.0, -1.0, -1.0));
+  assert_close(max, Vector3::new(2.0, -1.0, 1.0));
+
+  let mut mesh = Mesh::new(vec![[Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 2.0, 1.0)]]);
+  mesh.position = Vector3::new(10.0, 0.0, 0.0);
+  let [min, max] = Object::Mesh(mesh).bounds().unwrap();
+  assert_close(min, Vector3::new(10.0, 0.0, 0.0));
+  assert_close(max, Vector3::new(11.0, 2.0, 1.0));
+
+  assert_eq!(Object::Mesh(Mesh::new(Vec::new())).bounds(), None);
+}
+
+#[test]
+fn frame_all_keeps_every_object_in_view() {
+  for camera_type in [CameraType::Perspective, CameraType::Orthographic] {
+    let mut world = scene(camera_type);
+    assert!(world.frame_all());
+
+    let camera = world.camera();
+
+    for object in &world.objects()[2..] {
+      let [min, max] = object.bounds().unwrap();
+
+      for corner in [min, max] {
+        let (x, y) = camera.project(corner).unwrap();
+        assert!((0.0..=1.0).contains(&x) && (0.0..=1.0).contains(&y), "{:?} at {}, {}", camera_type, x, y);
+      }
+    }
+  }
+}
+
+#[test]
+fn frame_all_leaves_out_the_floor_unless_it_is_alone() {
+  let mut world = World::new();
+  let sphere = world.objects()[0].bounds().unwrap();
+  assert_eq!(world.scene_bounds(), Some(sphere));
+
+  world.objects_mut().remove(0);
+  let floor = world.objects()[0].bounds().unwrap();
+  assert_eq!(world.scene_bounds(), Some(floor));
+
+  world.objects_mut()[0].node_mut().hidden = true;
+  assert_eq!(world.scene_bounds(), None);
+  assert!(!world.frame_all());
+}
+
+#[test]
+fn frame_selected_centres_the_selection_and_orbits_around_it() {
+  let mut world = scene(CameraType::Perspective);
+  let forward = world.camera().camera_info().forward;
+
+  world.select(None);
+  assert!(!world.frame_selected());
+
+  world.select(Some(Selection::Object(3)));
+  assert!(world.frame_selected());
+
+  let camera = world.camera();
+  let (x, y) = camera.project(Vector3::new(-6.0, 3.0, 25.0)).unwrap();
+  assert!((x - 0.5).abs() < 1e-4 && (y - 0.5).abs() < 1e-4);
+
+  assert_eq!(camera.camera_info().forward, forward);
+  assert_eq!(camera.target(), Some(Vector3::new(-6.0, 3.0, 25.0)));
+}
\ No newline at end of file
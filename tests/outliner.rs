@@ -0,0 +1,134 @@
+use ray_tracer::{
+  animation::{Track, Keyframe, Interpolation},
+  history::{History, Command},
+  outliner::Checked,
+  renderer::Renderer,
+  scene_file::{self, SceneFormat},
+  vector::Vector3,
+  world::{World, Object, Sphere, Light, Selection},
+};
+
+fn named_world() -> World {
+  let mut world = World::new();
+
+  let node = world.objects_mut()[0].node_mut();
+  node.name = Some(String::from("Ball"));
+  node.group = Some(String::from("Props"));
+  node.locked = true;
+
+  world.lights_mut()[1].node_mut().hidden = true;
+
+  world
+}
+
+#[test]
+fn names_and_toggles_survive_scene_files() {
+  let world = named_world();
+
+  for format in SceneFormat::ALL {
+    let text = scene_file::to_string_as(&world, format).unwrap();
+    let loaded = scene_file::from_str_as(&text, format).unwrap();
+
+    assert_eq!(loaded.objects(), world.objects(), "{}", format.to_string());
+    assert_eq!(loaded.lights(), world.lights(), "{}", format.to_string());
+  }
+
+  // The fields sit next to the others and are left out when unset.
+  let text = scene_file::to_string_as(&world, SceneFormat::Json).unwrap();
+  assert!(text.contains(r#""name": "Ball""#), "{}", text);
+  assert!(!text.contains("\"group\": null"), "{}", text);
+
+  let loaded = scene_file::from_str(r#"{ "objects": [{ "Sphere": { "radius": 1.0, "name": "Small", "hidden": true } }] }"#).unwrap();
+  let node = loaded.objects()[0].node();
+  assert_eq!(node.name.as_deref(), Some("Small"));
+  assert!(node.hidden && !node.locked);
+}
+
+#[test]
+fn hidden_objects_are_not_rendered() {
+  let mut world = World::new();
+  let camera_info = world.camera_mut().camera_info_mut();
+  camera_info.viewport_width = 30;
+  camera_info.viewport_height = 20;
+
+  assert_eq!(Renderer::pick(&world, 0.5, 0.5), Some(0));
+
+  world.objects_mut()[0].node_mut().hidden = true;
+
+  assert_eq!(Renderer::pick(&world, 0.5, 0.5), None);
+}
+
+#[test]
+fn reordering_keeps_tracks_and_selection() {
+  let mut world = World::new();
+  world.objects_mut().push(Object::Sphere(Sphere::new()));
+  world.select(Some(Selection::Object(2)));
+
+  world.animation_mut().tracks = vec![
+    Track::ObjectPosition { object: 0, keyframes: vec![Keyframe::new(0.0, Vector3::new(0.0, 0.0, 0.0), Interpolation::Linear)] },
+    Track::ObjectColor { object: 2, keyframes: vec![Keyframe::new(0.0, [1.0, 0.0, 0.0], Interpolation::Linear)] },
+    Track::LightColor { light: 0, keyframes: vec![Keyframe::new(0.0, [1.0, 0.0, 0.0], Interpolation::Linear)] },
+  ];
+
+  let moved = world.objects()[2].clone();
+  world.move_object(2, 0);
+
+  assert_eq!(world.objects()[0], moved);
+  assert_eq!(world.selection(), Some(Selection::Object(0)));
+
+  let indices: Vec<usize> = world.animation().tracks.iter().map(|track| match track {
+    Track::ObjectPosition { object, .. } | Track::ObjectColor { object, .. } => *object,
+    Track::LightColor { light, .. } => *light,
+    _ => unreachable!(),
+  }).collect();
+
+  assert_eq!(indices, vec![1, 0, 0]);
+
+  world.move_light(0, 1);
+  assert!(matches!(world.lights()[0], Light::Ambient(_)));
+  assert!(matches!(world.animation().tracks[2], Track::LightColor { light: 1, .. }));
+
+  // Out of range moves are ignored.
+  world.move_object(5, 0);
+  assert_eq!(world.objects()[0], moved);
+}
+#[test]
+fn checks_are_dropped_when_entries_come_and_go() {
+  let mut world = World::new();
+  let mut history = History::new(&world);
+  let mut checked = Checked::new();
+
+  checked.sync(&world, &history);
+  checked.set(Selection::Object(0), true);
+  checked.set(Selection::Object(1), true);
+  assert_eq!(checked.targets(&world), vec![Selection::Object(0), Selection::Object(1)]);
+
+  history.execute(&mut world, Command::RemoveObject(1));
+  history.update(&world, false);
+
+  // Removed entries are left out even before the checks are synced.
+  assert_eq!(checked.targets(&world), vec![Selection::Object(0)]);
+
+  checked.duplicate(&mut world, &mut history, &[Selection::Object(1)]);
+  assert_eq!(world.objects().len(), 1);
+
+  checked.sync(&world, &history);
+  assert!(checked.is_empty());
+
+  // Undoing an add clears them too.
+  checked.set(Selection::Object(0), true);
+  history.execute(&mut world, Command::AddObject(Object::Sphere(Sphere::new())));
+  history.update(&world, false);
+  checked.sync(&world, &history);
+  checked.set(Selection::Object(1), true);
+
+  history.undo(&mut world);
+  checked.sync(&world, &history);
+  assert!(checked.is_empty());
+
+  world.select(Some(Selection::Object(0)));
+  let targets = checked.targets(&world);
+  checked.duplicate(&mut world, &mut history, &targets);
+  assert_eq!(world.objects().len(), 2);
+  assert_eq!(world.selection(), Some(Selection::Object(1)));
+}
\ No newline at end of file
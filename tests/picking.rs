@@ -0,0 +1,107 @@
+use ray_tracer::{renderer::Renderer, vector::{Vector3, Float}, world::{World, CameraType}};
+
+fn small_world(camera_type: CameraType) -> World {
+  let mut world = World::new();
+
+  let camera_info = world.camera_mut().camera_info_mut();
+  camera_info.camera_type = camera_type;
+  camera_info.camera_height = 3.0;
+  camera_info.viewport_width = 30;
+  camera_info.viewport_height = 20;
+
+  world
+}
+
+#[test]
+fn picks_objects_under_the_cursor() {
+  let world = small_world(CameraType::Perspective);
+
+  assert_eq!(Renderer::pick(&world, 0.5, 0.5), Some(0));
+  assert_eq!(Renderer::pick(&world, 0.5, 0.05), Some(1));
+  assert_eq!(Renderer::pick(&world, 0.5, 0.95), None);
+}
+
+#[test]
+fn picking_rays_match_rendered_rays() {
+  for camera_type in [CameraType::Perspective, CameraType::Orthographic] {
+    let mut world = small_world(camera_type);
+    world.camera_mut().calc_rays();
+
+    let camera = world.camera();
+
+    for (i, ray) in camera.rays()[..600].iter().enumerate() {
+      let x = (i % 30) as Float / 30.0;
+      let y = (i / 30) as Float / 20.0;
+      let picked = camera.ray(x, y);
+
+      assert_eq!((picked.position, picked.direction), (ray.position, ray.direction), "pixel {}", i);
+    }
+  }
+}
+
+#[test]
+fn projecting_inverts_camera_rays() {
+  for camera_type in [CameraType::Perspective, CameraType::Orthographic] {
+    let mut world = small_world(camera_type);
+    let camera_info = world.camera_mut().camera_info_mut();
+    camera_info.position = Vector3::new(1.0, 2.0, -3.0);
+    camera_info.forward = Vector3::new(0.3, -0.2, 1.0);
+
+    let camera = world.camera();
+
+    for (x, y) in [(0.5, 0.5), (0.1, 0.8), (0.9, 0.25)] {
+      let ray = camera.ray(x, y);
+      let (projected_x, projected_y) = camera.project(ray.position_from_distance(7.0)).unwrap();
+
+      assert!((projected_x - x).abs() < 1e-4 && (projected_y - y).abs() < 1e-4, "{} {} -> {} {}", x, y, projected_x, projected_y);
+    }
+  }
+}
+
+#[test]
+fn points_behind_the_camera_do_not_project() {
+  let world = small_world(CameraType::Perspective);
+
+  assert_eq!(world.camera().project(Vector3::new(0.0, 0.0, -5.0)), None);
+  assert_eq!(world.camera().project(Vector3::new(0.0, 0.0, 10.0)), Some((0.5, 0.5)));
+}
+
+#[test]
+fn rendered_object_ids_match_picking() {
+  for packet_tracing in [true, false] {
+    let mut world = small_world(CameraType::Perspective);
+
+    let mut renderer = Renderer::new();
+    *renderer.packet_tracing_mut() = packet_tracing;
+    renderer.render(&mut world);
+
+    let object_ids = renderer.rendered_object_ids();
+    assert_eq!(object_ids.len(), 600);
+
+    for (i, object_id) in object_ids.iter().enumerate() {
+      let picked = Renderer::pick(&world, (i % 30) as Float / 30.0, (i / 30) as Float / 20.0);
+
+      assert_eq!(*object_id, picked, "pixel {} with packet tracing {}", i, packet_tracing);
+    }
+  }
+}
+
+#[test]
+fn outlines_the_edge_of_an_object() {
+  let mut object_ids = vec![None; 25];
+
+  for y in 1..4 {
+    for x in 1..4 {
+      object_ids[y * 5 + x] = Some(2);
+    }
+  }
+
+  object_ids[0] = Some(1);
+
+  let mut image_buffer = vec![[0, 0, 0]; 25];
+  Renderer::draw_outline(&mut image_buffer, &object_ids, 5, 2, [255, 0, 0]);
+
+  let outlined: Vec<usize> = (0..25).filter(|i| image_buffer[*i] == [255, 0, 0]).collect();
+
+  assert_eq!(outlined, vec![6, 7, 8, 11, 13, 16, 17, 18]);
+}
\ No newline at end of file
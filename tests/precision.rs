@@ -0,0 +1,86 @@
+use ray_tracer::{renderer::Renderer, vector::{Vector3, Float}, world::{Ray, Sphere, Camera}};
+
+const TOLERANCE: f64 = 1e-4;
+
+// Float is already f64 unless the f32 feature is on.
+#[cfg(feature = "f32")]
+fn wide(value: Float) -> f64 {
+  f64::from(value)
+}
+
+#[cfg(not(feature = "f32"))]
+fn wide(value: Float) -> f64 {
+  value
+}
+
+fn assert_close(actual: Float, expected: f64) {
+  let error = (wide(actual) - expected).abs() / expected.abs().max(1.0);
+  assert!(error < TOLERANCE, "{} differs from f64 reference {}", actual, expected);
+}
+
+fn reference_sphere_distances(origin: [f64; 3], direction: [f64; 3], center: [f64; 3], radius: f64) -> (f64, f64) {
+  let o = [origin[0] - center[0], origin[1] - center[1], origin[2] - center[2]];
+
+  let a = direction[0] * direction[0] + direction[1] * direction[1] + direction[2] * direction[2];
+  let b = 2.0 * (o[0] * direction[0] + o[1] * direction[1] + o[2] * direction[2]);
+  let c = o[0] * o[0] + o[1] * o[1] + o[2] * o[2] - radius * radius;
+
+  let root = (b * b - 4.0 * a * c).sqrt();
+
+  ((-b + root) / (2.0 * a), (-b - root) / (2.0 * a))
+}
+
+#[test]
+fn sphere_distances_agree_with_f64_reference() {
+  let sphere = Sphere::new();
+
+  for i in 0..50 {
+    let offset = i as f64 / 50.0 - 0.5;
+    let direction = [offset * 0.3, offset * -0.2, 1.0];
+
+    let ray = Ray {
+      position: Vector3::new(0.0, 0.0, 0.0),
+      direction: Vector3::new(direction[0] as Float, direction[1] as Float, direction[2] as Float),
+    };
+
+    let center = [sphere.position.x, sphere.position.y, sphere.position.z].map(wide);
+    let (far, near) = reference_sphere_distances([0.0; 3], direction, center, wide(sphere.radius));
+
+    let [t1, t2] = sphere.trace_ray(&ray);
+
+    assert_close(t1.unwrap(), far);
+    assert_close(t2.unwrap(), near);
+  }
+}
+
+#[test]
+fn camera_rays_are_unit_length() {
+  let mut camera = Camera::new();
+  camera.calc_rays();
+
+  for ray in camera.rays() {
+    assert_close(ray.direction.mag(), 1.0);
+  }
+}
+
+#[test]
+fn refraction_agrees_with_f64_reference() {
+  let normal = Vector3::new(0.0, 1.0, 0.0);
+
+  for i in 1..20 {
+    let angle = i as f64 / 20.0 * 1.2;
+    let incident = [angle.sin(), -angle.cos(), 0.0];
+
+    let refracted = Renderer::calc_snells_law(
+      Vector3::new(incident[0] as Float, incident[1] as Float, 0.0),
+      normal,
+      1.0,
+      1.5,
+    );
+
+    let sin_refracted = angle.sin() / 1.5;
+
+    assert_close(refracted.x, sin_refracted);
+    assert_close(refracted.y, -(1.0 - sin_refracted * sin_refracted).sqrt());
+  }
+}
\ No newline at end of file
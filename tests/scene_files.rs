@@ -0,0 +1,220 @@
+use ray_tracer::{scene_file::{self, SceneError, SceneFormat}, world::{World, Object, Sphere, Material}};
+
+fn load(name: &str) -> World {
+  let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(name);
+  scene_file::load(&path).unwrap()
+}
+
+#[test]
+fn loads_demo_scene() {
+  let world = load("demo.json");
+
+  assert_eq!(world.objects().len(), 5);
+  assert_eq!(world.lights().len(), 2);
+  assert_eq!(world.camera().camera_info().viewport_width, 720);
+  assert_eq!(world.camera().camera_info().viewport_height, 480);
+}
+
+#[test]
+fn loads_lots_of_balls_scene() {
+  let world = load("lots of balls.json");
+
+  assert_eq!(world.objects().len(), 11);
+  assert_eq!(world.camera().camera_info().viewport_width, 1920);
+}
+
+#[test]
+fn round_trips_through_current_version() {
+  let world = load("lots of balls.json");
+
+  let mut json = Vec::new();
+  scene_file::to_writer(&mut json, &world).unwrap();
+
+  let document: serde_json::Value = serde_json::from_slice(&json).unwrap();
+  assert_eq!(document["version"], scene_file::FORMAT_VERSION);
+  assert!(document["camera"].get("rays").is_none());
+
+  let loaded = scene_file::from_str(std::str::from_utf8(&json).unwrap()).unwrap();
+  assert!(loaded.same_scene(&world));
+}
+
+#[test]
+fn fills_missing_fields_with_defaults() {
+  let world = scene_file::from_str(r#"{
+    "version": 1,
+    "objects": [{ "Sphere": { "position": { "x": 1.0, "y": 2.0, "z": 3.0 } } }],
+    "camera": { "camera_info": { "viewport_width": 64 } }
+  }"#).unwrap();
+
+  match &world.objects()[..] {
+    [Object::Sphere(sphere)] => {
+      assert_eq!(sphere.position.z, 3.0);
+      assert_eq!(sphere.radius, Sphere::new().radius);
+      assert_eq!(sphere.material, Material::new());
+    },
+    objects => panic!("unexpected objects {:?}", objects),
+  }
+
+  assert!(world.lights().is_empty());
+  assert_eq!(world.camera().camera_info().viewport_width, 64);
+  assert_eq!(world.camera().camera_info().viewport_height, 480);
+
+  let world = scene_file::from_str(r#"{ "version": 1 }"#).unwrap();
+  assert!(world.objects().is_empty());
+}
+
+#[test]
+fn rejects_newer_versions() {
+  let json = format!(r#"{{ "version": {} }}"#, scene_file::FORMAT_VERSION + 1);
+  let err = scene_file::from_str(&json).unwrap_err();
+
+  assert!(err.to_string().contains("newer"));
+}
+
+#[test]
+fn reports_syntax_error_position() {
+  let err = scene_file::from_str("{\n  \"objects\": [\n    ,\n  ]\n}").unwrap_err();
+
+  match err {
+    SceneError::Parse { line, column, .. } => assert_eq!((line, column), (3, 5)),
+    err => panic!("unexpected error {:?}", err),
+  }
+}
+
+#[test]
+fn reports_invalid_field_position() {
+  let json = format!("{{\n  \"version\": {},\n  \"camera\": {{ \"depth\": \"deep\" }}\n}}", scene_file::FORMAT_VERSION);
+  let err = scene_file::from_str(&json).unwrap_err();
+
+  match &err {
+    SceneError::Parse { line, .. } => assert_eq!(*line, 3),
+    err => panic!("unexpected error {:?}", err),
+  }
+
+  assert!(err.to_string().contains("line 3"));
+}
+
+#[test]
+fn reports_missing_file() {
+  let err = scene_file::load(std::path::Path::new("does not exist.json")).unwrap_err();
+
+  assert!(matches!(err, SceneError::Io(_)));
+}
+
+#[test]
+fn rejects_non_object_documents() {
+  let err = scene_file::from_str("[]").unwrap_err();
+
+  assert!(matches!(err, SceneError::Validation(_)));
+}
+
+#[test]
+fn round_trips_through_every_format() {
+  let world = load("demo.json");
+
+  for format in SceneFormat::ALL {
+    let text = scene_file::to_string_as(&world, format).unwrap();
+    let loaded = scene_file::from_str_as(&text, format).unwrap();
+
+    assert!(loaded.same_scene(&world), "{} round trip changed the scene", format.to_string());
+  }
+}
+
+#[test]
+fn picks_format_from_extension() {
+  let format = |name: &str| SceneFormat::from_path(std::path::Path::new(name));
+
+  assert_eq!(format("scene.toml"), SceneFormat::Toml);
+  assert_eq!(format("scene.YML"), SceneFormat::Yaml);
+  assert_eq!(format("scene.yaml"), SceneFormat::Yaml);
+  assert_eq!(format("scene.json"), SceneFormat::Json);
+  assert_eq!(format("demo.scene"), SceneFormat::Text);
+  assert_eq!(format("scene"), SceneFormat::Json);
+}
+
+#[test]
+fn resolves_named_materials() {
+  let world = scene_file::from_str_as(r#"
+[materials.glass]
+transparency = 0.9
+refractive_index = 1.5
+
+[[objects]]
+[objects.Sphere]
+radius = 1.0
+material_name = "glass"
+"#, SceneFormat::Toml).unwrap();
+
+  let material = world.objects()[0].material();
+
+  assert_eq!(world.objects()[0].material_name(), Some("glass"));
+  assert_eq!(material.transparency, 0.9);
+  assert_eq!(material.refractive_index, 1.5);
+  assert_eq!(material.color, Material::new().color);
+}
+
+#[test]
+fn rejects_undefined_materials() {
+  let err = scene_file::from_str_as("
+objects:
+  - Sphere:
+      material_name: steel
+", SceneFormat::Yaml).unwrap_err();
+
+  assert!(matches!(err, SceneError::Validation(_)));
+  assert!(err.to_string().contains("steel"));
+}
+
+#[test]
+fn reports_toml_and_yaml_error_positions() {
+  let toml = format!("version = {}\n[camera]\ndepth = \"deep\"\n", scene_file::FORMAT_VERSION);
+  let toml = scene_file::from_str_as(&toml, SceneFormat::Toml).unwrap_err();
+  let yaml = format!("version: {}\ncamera:\n  depth: deep\n", scene_file::FORMAT_VERSION);
+  let yaml = scene_file::from_str_as(&yaml, SceneFormat::Yaml).unwrap_err();
+
+  for err in [toml, yaml] {
+    match err {
+      SceneError::Parse { line, .. } => assert_eq!(line, 3),
+      err => panic!("unexpected error {:?}", err),
+    }
+  }
+}
+#[test]
+fn reads_the_text_format() {
+  let world = scene_file::from_str_as(r#"
+# A sphere in glass over a floor.
+version 1
+camera depth 3 camera_info.position 0 1 -5 camera_info.miss_color 0.1 0.2 0.3
+material "frosted glass" transparency 0.9 refractive_index 1.5
+sphere position 0 0 10 radius 2 material_name "frosted glass" name "Ball"
+plane position 0 -1 0 material.color 0.5 0.5 0.5
+point_light position 0 5 0 color 1 1 1
+"#, SceneFormat::Text).unwrap();
+
+  let camera_info = world.camera().camera_info();
+  assert_eq!(*world.camera().depth(), 3);
+  assert_eq!(camera_info.position.y, 1.0);
+  assert_eq!(camera_info.miss_color, [0.1, 0.2, 0.3]);
+  assert_eq!(camera_info.viewport_width, World::new().camera().camera_info().viewport_width);
+
+  match &world.objects()[..] {
+    [Object::Sphere(sphere), Object::Plane(plane)] => {
+      assert_eq!((sphere.position.z, sphere.radius), (10.0, 2.0));
+      assert_eq!(sphere.node.name.as_deref(), Some("Ball"));
+      assert_eq!(sphere.material.transparency, 0.9);
+      assert_eq!(plane.material.color, [0.5, 0.5, 0.5]);
+    },
+    objects => panic!("unexpected objects {:?}", objects),
+  }
+
+  assert_eq!(world.lights().len(), 1);
+}
+
+#[test]
+fn reports_text_format_error_positions() {
+  let err = scene_file::from_str_as("version 1\nsphere radius 1 position 0 @ 0\n", SceneFormat::Text).unwrap_err();
+  assert!(err.to_string().contains("line 2, column 28"), "{}", err);
+
+  let err = scene_file::from_str_as("sphere radius\n", SceneFormat::Text).unwrap_err();
+  assert!(err.to_string().contains("missing value for `radius`"), "{}", err);
+}
\ No newline at end of file
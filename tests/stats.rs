@@ -0,0 +1,78 @@
+use ray_tracer::{
+  renderer::Renderer,
+  stats::{PixelStats, RenderStats},
+  world::World,
+};
+
+fn small_world() -> World {
+  let mut world = World::new();
+
+  let camera_info = world.camera_mut().camera_info_mut();
+  camera_info.viewport_width = 40;
+  camera_info.viewport_height = 30;
+
+  world
+}
+
+fn render_stats(world: &mut World, packet_tracing: bool) -> RenderStats {
+  let mut renderer = Renderer::new();
+  *renderer.packet_tracing_mut() = packet_tracing;
+  renderer.render(world);
+
+  renderer.stats()
+}
+
+#[test]
+fn counts_rays_and_tests() {
+  let mut world = small_world();
+  let stats = render_stats(&mut world, false);
+
+  let pixels = 40 * 30;
+  let objects = world.objects().len() as u64;
+
+  assert_eq!((stats.width, stats.height, stats.cost().len()), (40, 30, pixels as usize));
+  assert_eq!(stats.primary_rays, pixels);
+  assert!(stats.shadow_rays > 0);
+  assert!(stats.intersection_tests >= stats.rays() * objects);
+  assert_eq!(stats.cost().iter().map(|cost| *cost as u64).sum::<u64>(), stats.intersection_tests);
+
+  let depth = *world.camera().depth() as f64;
+  assert!(stats.average_depth() >= 1.0 && stats.average_depth() <= depth);
+}
+
+#[test]
+fn packets_count_like_single_rays() {
+  let stats = render_stats(&mut small_world(), false);
+  let packet_stats = render_stats(&mut small_world(), true);
+
+  assert_eq!(stats.cost(), packet_stats.cost());
+  assert_eq!(
+    (stats.primary_rays, stats.shadow_rays, stats.secondary_rays),
+    (packet_stats.primary_rays, packet_stats.shadow_rays, packet_stats.secondary_rays),
+  );
+}
+
+#[test]
+fn hidden_objects_are_not_tested() {
+  let mut world = small_world();
+  let before = render_stats(&mut world, true);
+
+  world.objects_mut()[0].node_mut().hidden = true;
+  let after = render_stats(&mut world, true);
+
+  assert_eq!(after.primary_rays, before.primary_rays);
+  assert!(after.intersection_tests < before.intersection_tests);
+}
+
+#[test]
+fn heatmap_scales_to_the_most_expensive_pixel() {
+  let pixel = |intersection_tests| PixelStats { primary_rays: 1, intersection_tests, depth: 1, ..PixelStats::new() };
+
+  // The bottom row comes first, like the rendered image buffer.
+  let stats = RenderStats::new(2, 2, &[pixel(0), pixel(2), pixel(4), pixel(8)]);
+  let heatmap = stats.heatmap();
+
+  assert_eq!(stats.max_cost(), 8);
+  assert_eq!(stats.average_depth(), 1.0);
+  assert_eq!(heatmap.pixels, vec![[255, 0, 0], [255, 255, 255], [0, 0, 0], [0, 0, 255]]);
+}
\ No newline at end of file
@@ -0,0 +1,82 @@
+use ray_tracer::{scene_file, validation::{self, Severity}, vector::{Vector3, Float}, world::{World, Object, Light, Sphere, DirectionalLight, Selection}};
+
+fn load(name: &str) -> World {
+  let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(name);
+  scene_file::load(&path).unwrap()
+}
+
+#[test]
+fn default_world_is_valid() {
+  assert_eq!(validation::validate(&World::new()), vec![]);
+}
+
+#[test]
+fn shipped_scenes_have_no_errors() {
+  for name in ["demo.json", "lots of balls.json"] {
+    let problems = validation::validate(&load(name));
+
+    assert!(!validation::has_errors(&problems), "{}: {:?}", name, problems);
+  }
+}
+
+#[test]
+fn reports_zero_radius_spheres() {
+  let mut world = World::new();
+  let mut sphere = Sphere::new();
+  sphere.radius = 0.0;
+  world.objects_mut().push(Object::Sphere(sphere));
+
+  let problems = validation::validate(&world);
+
+  assert_eq!(problems.len(), 1);
+  assert_eq!(problems[0].severity, Severity::Error);
+  assert_eq!(problems[0].subject, Selection::Object(2));
+  assert_eq!(problems[0].field, "radius");
+}
+
+#[test]
+fn reports_nan_positions_and_negative_refractive_indices() {
+  let mut world = World::new();
+
+  if let Object::Sphere(sphere) = &mut world.objects_mut()[0] {
+    sphere.position = Vector3::new(Float::NAN, 0.0, 10.0);
+    sphere.material.refractive_index = -1.5;
+  }
+
+  let fields: Vec<_> = validation::validate(&world).iter()
+    .map(|problem| (problem.severity, problem.subject, problem.field))
+    .collect();
+
+  assert!(fields.contains(&(Severity::Error, Selection::Object(0), "position")));
+  assert!(fields.contains(&(Severity::Error, Selection::Object(0), "refractive index")));
+}
+
+#[test]
+fn reports_zero_light_directions() {
+  let mut world = World::new();
+  let mut light = DirectionalLight::new();
+  light.direction = Vector3::new(0.0, 0.0, 0.0);
+  world.lights_mut().push(Light::Directional(light));
+
+  let problems = validation::validate(&world);
+
+  assert_eq!(problems.len(), 1);
+  assert_eq!(problems[0].subject, Selection::Light(2));
+  assert_eq!(problems[0].field, "direction");
+}
+
+#[test]
+fn reports_camera_problems() {
+  let mut world = World::new();
+  world.camera_mut().camera_info_mut().forward = Vector3::new(0.0, 1.0, 0.0);
+  *world.camera_mut().depth_mut() = 0;
+
+  let problems = validation::validate(&world);
+
+  assert_eq!(problems.len(), 2);
+  assert_eq!(problems[0].severity, Severity::Error);
+  assert_eq!(problems[0].field, "forward");
+  assert_eq!(problems[1].severity, Severity::Warning);
+  assert_eq!(problems[1].field, "depth");
+  assert!(problems.iter().all(|problem| problem.subject == Selection::Camera));
+}
\ No newline at end of file
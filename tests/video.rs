@@ -0,0 +1,130 @@
+use std::{fs::{self, File}, path::PathBuf};
+
+use ray_tracer::{image::Image, video::{Y4mWriter, VideoFormat, VideoWriter}, headless, world::World};
+
+fn video_dir() -> PathBuf {
+  let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("video");
+  fs::create_dir_all(&dir).unwrap();
+  dir
+}
+
+fn frames() -> Vec<Image> {
+  [[0, 0, 0], [255, 255, 255], [255, 0, 0]].iter()
+    .map(|color| Image::new(4, 2, vec![*color; 8]))
+    .collect()
+}
+
+#[test]
+fn writes_y4m_frames() {
+  let mut writer = Y4mWriter::new(Vec::new(), 4, 2, 24.0).unwrap();
+
+  for frame in frames() {
+    writer.write_frame(&frame).unwrap();
+  }
+
+  let data = writer.finish().unwrap();
+  let header = b"YUV4MPEG2 W4 H2 F24:1 Ip A1:1 C444\n";
+
+  assert!(data.starts_with(header));
+  assert_eq!(data.len(), header.len() + 3 * (6 + 4 * 2 * 3));
+
+  let frame = |index: usize| &data[header.len() + index * (6 + 24)..][..6 + 24];
+
+  assert_eq!(&frame(0)[..6], b"FRAME\n");
+  assert_eq!(frame(0)[6], 16);
+  assert_eq!(frame(1)[6], 235);
+  assert_eq!(frame(1)[6 + 8], 128);
+
+  // Red has a high V and a low U.
+  assert!(frame(2)[6 + 16] > 200 && frame(2)[6 + 8] < 128);
+}
+
+#[test]
+fn writes_fractional_y4m_frame_rates() {
+  let data = Y4mWriter::new(Vec::new(), 1, 1, 29.97).unwrap().finish().unwrap();
+
+  assert!(data.starts_with(b"YUV4MPEG2 W1 H1 F29970:1000 "));
+}
+
+#[test]
+fn rejects_frames_of_the_wrong_size() {
+  let mut writer = Y4mWriter::new(Vec::new(), 4, 2, 24.0).unwrap();
+
+  assert!(writer.write_frame(&Image::new(2, 2, vec![[0, 0, 0]; 4])).is_err());
+}
+
+#[test]
+fn picks_formats_from_extensions() {
+  assert_eq!(VideoFormat::from_path(&PathBuf::from("out.Y4M")), Some(VideoFormat::Y4m));
+  assert_eq!(VideoFormat::from_path(&PathBuf::from("out.gif")), Some(VideoFormat::Gif));
+  assert_eq!(VideoFormat::from_path(&PathBuf::from("out.apng")), Some(VideoFormat::Apng));
+  assert_eq!(VideoFormat::from_path(&PathBuf::from("out.png")), None);
+}
+
+#[test]
+fn writes_animated_gifs() {
+  let path = video_dir().join("frames.gif");
+  let mut writer = VideoWriter::create(&path, VideoFormat::Gif, 4, 2, 25.0, 3).unwrap();
+
+  for frame in frames() {
+    writer.write_frame(&frame).unwrap();
+  }
+
+  writer.finish().unwrap();
+
+  let mut options = gif::DecodeOptions::new();
+  options.set_color_output(gif::ColorOutput::RGBA);
+  let mut decoder = options.read_info(File::open(&path).unwrap()).unwrap();
+
+  let mut colors = Vec::new();
+
+  while let Some(frame) = decoder.read_next_frame().unwrap() {
+    assert_eq!(frame.delay, 4);
+    colors.push([frame.buffer[0], frame.buffer[1], frame.buffer[2]]);
+  }
+
+  assert_eq!(colors, vec![[0, 0, 0], [255, 255, 255], [255, 0, 0]]);
+}
+
+#[test]
+fn writes_animated_pngs() {
+  let path = video_dir().join("frames.apng");
+  let mut writer = VideoWriter::create(&path, VideoFormat::Apng, 4, 2, 24.0, 3).unwrap();
+
+  for frame in frames() {
+    writer.write_frame(&frame).unwrap();
+  }
+
+  writer.finish().unwrap();
+
+  let mut reader = png::Decoder::new(File::open(&path).unwrap()).read_info().unwrap();
+  let animation = reader.info().animation_control.unwrap();
+
+  assert_eq!(animation.num_frames, 3);
+
+  let mut data = vec![0; 4 * 2 * 3];
+
+  for color in [[0, 0, 0], [255, 255, 255], [255, 0, 0]] {
+    reader.next_frame(&mut data).unwrap();
+    assert_eq!(data[..3], color);
+  }
+}
+
+#[test]
+fn renders_frame_ranges_to_video() {
+  let dir = video_dir();
+
+  let mut world = World::new();
+  let camera_info = world.camera_mut().camera_info_mut();
+  camera_info.viewport_width = 8;
+  camera_info.viewport_height = 6;
+
+  let output = dir.join("render.y4m");
+  headless::render_frames(&mut world, &output, 0..=4).unwrap();
+
+  let data = fs::read(&output).unwrap();
+  let header = b"YUV4MPEG2 W8 H6 F24:1 Ip A1:1 C444\n";
+
+  assert!(data.starts_with(header));
+  assert_eq!(data.len(), header.len() + 5 * (6 + 8 * 6 * 3));
+}
\ No newline at end of file